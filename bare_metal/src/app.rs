@@ -1,40 +1,84 @@
+use core::cell::Cell;
+
 use crate::board::front_panel::FrontPanel;
-use crate::board::keyboard::Keyboard;
+use crate::board::keyboard::{KeyEvent, Keyboard};
 use crate::board::keypad::KeyCode;
+use crate::board::panel_patterns::{PanelOutput, PanelPatterns, Pattern};
 use crate::board::peripherals::Peripherals;
-use crate::board::peripherals::PeripheralsAvrHardware;
-use crate::geometry::point::Point;
 use crate::menu::model::DataModel;
 use crate::menu::screen_buffer::ScreenBuffer;
 use crate::menu::widget::execucao::MenuExecucaoControler;
 use crate::menu::widget::main_menu::MainMenu;
 use crate::menu::widget::manual_mode::ManualModeMenuControler;
+use crate::menu::widget::navigation::Navigator;
 use crate::menu::widget::splash::Splash;
 use crate::menu::widget::submenu_programa::menu_programa_controler::MenuProgramaControler;
 use crate::menu::widget::submenu_programa::spec::{MenuProgramaAreanaSelector, MenuProgramaArena};
 use crate::menu::widget::widget::Widget;
-use crate::microcontroler::timer::now;
+use crate::microcontroler::delay::delay_ms;
 use crate::microcontroler::{serial, timer};
+use crate::scheduler::{Priority, Scheduler};
 use cross_platform::protocol::datalink::datalink::Datalink;
 use cross_platform::protocol::transport::channel::Channel;
 use cross_platform::protocol::transport::transport_layer::cmpp_value::MechanicalProperties;
+use cross_platform::protocol::transport::transport_layer::new_proposal::Displacement;
 use cross_platform::protocol::transport::transport_layer::TransportLayer;
+use cross_platform::types::time::Duration;
+
+/// Beeps and blinks the error led
+fn signal_error(panel_patterns: &PanelPatterns) {
+    panel_patterns.play(PanelOutput::Buzzer, Pattern::ERROR);
+    panel_patterns.play(PanelOutput::LedErro, Pattern::ERROR);
+}
 
 /// TODO: Implement user interaction with the signal emitted
-fn emit_print_go_signal(transport: &TransportLayer) {
+fn emit_print_go_signal(transport: &TransportLayer, panel_patterns: &PanelPatterns) {
     match transport.print_go() {
         Ok(_status) => {
             // TODO: Inform user that a print signal was successful sent to cmpp board
         }
         Err(_error) => {
             // TODO: Inform user what kind of error happened
+            signal_error(panel_patterns)
         }
     }
 }
 
+/// Stops the motion of all the axes, requested by holding the `STOP` key
+/// TODO: Implement user interaction with the signal emitted
+fn emergency_stop(transports: [&TransportLayer; 2], panel_patterns: &PanelPatterns) {
+    let mut has_failed = false;
+    for transport in transports {
+        // the other axes are stopped even if one of them does not answer
+        has_failed |= transport.stop().is_err();
+    }
+    if has_failed {
+        signal_error(panel_patterns)
+    } else {
+        panel_patterns.play(PanelOutput::Buzzer, Pattern::BEEP)
+    }
+}
+
 /// TODO: Make this a type
 type Baudrate = u32;
 
+/// Tasks of the main loop, see [`Scheduler`]
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum AppTask {
+    /// Scans the keyboard and sends the keys to the menus
+    Keyboard,
+    /// Updates the state of the menus
+    Update,
+    /// Beeps and blinks of the front panel
+    FrontPanel,
+    /// Redraws the lcd
+    Render,
+    /// Reads the status of the cmpp boards shown in the screen (ie: current position)
+    StatusPolling,
+    /// Writes into eeprom the changes saved by the user, one record by time (write-behind)
+    Eeprom,
+}
+
 /// High-level cmpp driver  
 ///
 /// Represents an entire Cmpp Axis System, including unit of measurement convertion
@@ -53,11 +97,8 @@ impl CmppAxis {
         mechanical_properties: MechanicalProperties,
     ) -> Self {
         // set callbacks
-        fn now__() -> u16 {
-            timer::now() as u16
-        }
         fn try_rx() -> Result<Option<u8>, ()> {
-            Ok(serial::try_receive())
+            serial::try_receive_checked()
         }
         fn try_tx(byte: u8) -> Option<()> {
             serial::try_transmit(byte).ok()
@@ -65,11 +106,15 @@ impl CmppAxis {
         // instantiation
         let datalink = Datalink {
             channel,
-            now: now__,
+            now: timer::now_instant,
             timeout_ms,
             try_rx,
             try_tx,
             debug_reception: None,
+            // TODO: the driver-enable pin of the RS-485 transceiver is not wired in the board yet
+            half_duplex: None,
+            resynchronize: true,
+            discarded_bytes: Cell::new(0),
         };
         Self {
             mechanical_properties,
@@ -85,8 +130,9 @@ impl CmppAxis {
     }
 }
 
-/// Entry point of the main application
-pub fn run() -> ! {
+/// Entry point of the main application, the peripherals are given by the platform (the board or
+/// the simulator)
+pub fn run(peripherals: impl Peripherals) -> ! {
     // /////////////////////////////////////////////////////////////////////
     // Initialize system
     // ////////////////////////////////////////////////////////////////////
@@ -119,8 +165,8 @@ pub fn run() -> ! {
     serial::init(baudrate);
 
     // other peripherals
-    let peripherals = PeripheralsAvrHardware::new();
-    let mut front_panel = peripherals.get_front_panel();
+    let front_panel = peripherals.get_front_panel();
+    let panel_patterns = PanelPatterns::new();
     let mut keyboard = peripherals.get_keyboard();
     let mut screen_buffer = peripherals.get_screen_buffer();
 
@@ -162,24 +208,27 @@ pub fn run() -> ! {
         data_model: &'a DataModel,
         transport_x: &'a TransportLayer,
         transport_y: &'a TransportLayer,
-        front_panel: &'a mut impl FrontPanel,
+        panel_patterns: &'a PanelPatterns,
     ) -> impl Widget + 'a {
         // menu root
         let initial_menu_selector = MenuProgramaAreanaSelector::MenuPrograma;
-        // child menus
+        // screens
+        let splash = Splash::new(&data_model, &transport_x, &transport_y);
+        let main_menu = MainMenu::new(&transport_x, &transport_y, &data_model);
         let menu_programa_controler =
             MenuProgramaControler::new(initial_menu_selector, &menu_programa_arena);
         let menu_manual_controler = ManualModeMenuControler::new(&transport_x);
-        let menu_execucao_controler = MenuExecucaoControler::new(&transport_x);
-        // parent menu
-        MainMenu::new(
+        let menu_execucao_controler =
+            MenuExecucaoControler::new(&transport_x, &data_model.status_do_eixo_x);
+        // the navigation starts showing the splash screen, then the main menu
+        Navigator::new(
+            splash,
+            main_menu,
             menu_manual_controler,
             menu_execucao_controler,
             menu_programa_controler,
-            &transport_x,
-            &transport_y,
             &data_model,
-            front_panel,
+            panel_patterns,
         )
     }
 
@@ -189,26 +238,9 @@ pub fn run() -> ! {
         &data_model,
         &transport_x,
         &transport_y,
-        &mut front_panel,
+        &panel_patterns,
     );
 
-    // ///////////////////////////////////////
-    //  Show initial splash window
-    // ///////////////////////////////////////
-    //
-    let mut splash_window = Splash::new(&data_model, &transport_x, &transport_y);
-
-    while splash_window.is_running() {
-        if let Some(key) = keyboard.get_key() {
-            splash_window.send_key(key);
-        }
-
-        splash_window.update();
-        let _start_point = Point::new(0, 0);
-        splash_window.draw(&mut screen_buffer);
-        screen_buffer.render();
-    }
-
     // /////////////////////////////////////////////////////////////////////
     //  Main loop
     // ////////////////////////////////////////////////////////////////////
@@ -216,30 +248,82 @@ pub fn run() -> ! {
     fn start_main_loop(
         mut screen_buffer: ScreenBuffer,
         mut keyboard: impl Keyboard,
+        mut front_panel: impl FrontPanel,
+        panel_patterns: &PanelPatterns,
         mut menu_controler: impl Widget,
-        transport: &TransportLayer,
+        data_model: &DataModel,
+        transport_x: &TransportLayer,
+        transport_y: &TransportLayer,
     ) -> ! {
-        let fps = 30; // frames_per_second for lcd display redraw -> 30_fps = 200_milisecs
-        let mut next_frame = now() + (1000 / fps);
+        let fps = 30; // frames_per_second for lcd display redraw -> 30_fps = 33_milisecs
+        let mut scheduler: Scheduler<AppTask, 6> = Scheduler::new();
+        scheduler.add_periodic(AppTask::Keyboard, Priority::High, Duration::from_millis(1));
+        scheduler.add_periodic(
+            AppTask::FrontPanel,
+            Priority::High,
+            Duration::from_millis(5),
+        );
+        scheduler.add_periodic(AppTask::Update, Priority::Normal, Duration::from_millis(10));
+        scheduler.add_periodic(
+            AppTask::Render,
+            Priority::Low,
+            Duration::from_millis(1000 / fps),
+        );
+        scheduler.add_periodic(
+            AppTask::StatusPolling,
+            Priority::Normal,
+            Duration::from_millis(250),
+        );
+        scheduler.add_periodic(AppTask::Eeprom, Priority::Low, Duration::from_millis(5));
         loop {
-            // Proccess keystrokes
-            if let Some(key) = keyboard.get_key() {
-                match key {
-                    KeyCode::KEY_F2 => emit_print_go_signal(&transport),
-                    _ => menu_controler.send_key(key),
+            let has_run = scheduler.run_next(|task| match task {
+                AppTask::Keyboard => {
+                    if let Some(event) = keyboard.get_key() {
+                        panel_patterns.play(PanelOutput::Buzzer, Pattern::KEY_CLICK);
+                        match event {
+                            KeyEvent::Pressed(KeyCode::KEY_F2) => {
+                                emit_print_go_signal(&transport_x, panel_patterns)
+                            }
+                            KeyEvent::LongPress(KeyCode::KEY_STOP) => {
+                                emergency_stop([transport_x, transport_y], panel_patterns)
+                            }
+                            _ => menu_controler.send_key_event(event),
+                        }
+                    }
                 }
-            }
-            // Update calculations
-            menu_controler.update();
-
-            // Render next frame
-            if now() > next_frame {
-                next_frame = now() + (1000 / fps);
-                menu_controler.draw(&mut screen_buffer, Point::new(0, 0));
-                screen_buffer.render();
+                AppTask::Update => menu_controler.update(),
+                AppTask::FrontPanel => panel_patterns.update(&mut front_panel),
+                AppTask::Render => {
+                    menu_controler.draw(&mut screen_buffer.full_region());
+                    screen_buffer.render();
+                }
+                AppTask::StatusPolling => {
+                    let status = &data_model.status_do_eixo_x;
+                    if status.monitorado.get() {
+                        let posicao_atual = transport_x.posicao_atual();
+                        status
+                            .posicao_atual
+                            .set(posicao_atual.ok().map(|Displacement(posicao)| posicao));
+                    }
+                }
+                AppTask::Eeprom => {
+                    data_model.gravar_proxima_alteracao();
+                }
+            });
+            if !has_run {
+                delay_ms(scheduler.idle_time().as_millis() as u64);
             }
         }
     }
 
-    start_main_loop(screen_buffer, keyboard, menu_controler, &transport_x)
+    start_main_loop(
+        screen_buffer,
+        keyboard,
+        front_panel,
+        &panel_patterns,
+        menu_controler,
+        &data_model,
+        &transport_x,
+        &transport_y,
+    )
 }
@@ -1,9 +1,16 @@
 pub mod front_panel;
+#[cfg(not(target_arch = "avr"))]
+pub mod host;
+#[cfg(target_arch = "avr")]
 pub mod input_expander;
 pub mod keyboard;
 pub mod keypad;
 pub mod lcd;
+#[cfg(target_arch = "avr")]
 pub mod output_expander;
+pub mod panel_patterns;
 pub mod peripherals;
+#[cfg(target_arch = "avr")]
 pub mod shiftin;
+#[cfg(target_arch = "avr")]
 pub mod shiftout;
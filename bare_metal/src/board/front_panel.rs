@@ -4,6 +4,8 @@
 //! These leds and the buzzer is parte of the interface human-machine and has
 //! purpose to inform the user some events, like errors and keypressed.
 //!
+//! NOTE: To beep or blink without blocking use [`super::panel_patterns`].
+//!
 //! # Example
 //!
 //! ```
@@ -33,13 +35,6 @@ pub trait FrontPanel {
 
     // optinal methods
 
-    /// Beeps for a particular duration and stops
-    fn Beep(&mut self, duration_milisecs: u16) {
-        self.BUZZER(true);
-        delay_ms(duration_milisecs as u64);
-        self.BUZZER(false)
-    }
-
     fn all(&mut self, on: bool) {
         self.LED_ERRO(on);
         self.LED_POS_ALC(on);
@@ -64,18 +59,23 @@ pub trait FrontPanel {
 
 //
 
-use crate::{board::output_expander::OutputExpander, microcontroler::delay::delay_ms};
+#[cfg(target_arch = "avr")]
+use crate::board::output_expander::OutputExpander;
+use crate::microcontroler::delay::delay_ms;
 
+#[cfg(target_arch = "avr")]
 pub struct FrontPanelAvrHardware<'a> {
     output_expander: &'a OutputExpander,
 }
 
+#[cfg(target_arch = "avr")]
 impl<'a> FrontPanelAvrHardware<'a> {
     pub fn new(output_expander: &'a OutputExpander) -> Self {
         Self { output_expander }
     }
 }
 
+#[cfg(target_arch = "avr")]
 impl<'a> FrontPanel for FrontPanelAvrHardware<'a> {
     fn LED_ERRO(&mut self, on: bool) {
         self.output_expander.LED_ERRO(on).commit()
@@ -0,0 +1,8 @@
+//! Host implementations (test doubles) of the board peripherals, they replace the hardware when
+//! the widgets run in the PC, ie: in the tests.
+//!
+//! The time is given by the virtual clock of [`crate::microcontroler::timer`].
+
+pub mod front_panel;
+pub mod keyboard;
+pub mod lcd;
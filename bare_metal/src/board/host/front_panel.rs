@@ -0,0 +1,46 @@
+//! Front panel which just records the state of the leds and of the buzzer
+
+use crate::board::front_panel::FrontPanel;
+
+/// State of the leds and of the buzzer, `true` if on
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct FrontPanelRecorder {
+    pub led_erro: bool,
+    pub led_pos_alc: bool,
+    pub buzzer: bool,
+    pub led_manual: bool,
+    pub led_execucao: bool,
+    pub led_programa: bool,
+}
+
+impl FrontPanelRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FrontPanel for FrontPanelRecorder {
+    fn LED_ERRO(&mut self, on: bool) {
+        self.led_erro = on;
+    }
+
+    fn LED_POS_ALC(&mut self, on: bool) {
+        self.led_pos_alc = on;
+    }
+
+    fn BUZZER(&mut self, on: bool) {
+        self.buzzer = on;
+    }
+
+    fn LED_MANUAL(&mut self, on: bool) {
+        self.led_manual = on;
+    }
+
+    fn LED_EXECUCAO(&mut self, on: bool) {
+        self.led_execucao = on;
+    }
+
+    fn LED_PROGRAMA(&mut self, on: bool) {
+        self.led_programa = on;
+    }
+}
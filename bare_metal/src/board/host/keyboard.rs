@@ -0,0 +1,89 @@
+//! Keyboard which types a script of keys, as if the user was pressing them
+
+use std::collections::VecDeque;
+
+use crate::{
+    board::{
+        keyboard::{KeyEvent, Keyboard},
+        keypad::KeyCode,
+    },
+    microcontroler::timer::now_instant,
+};
+use cross_platform::types::time::{Duration, Instant};
+
+/// Types the keys of the script in order, each one after its waiting time (in milisecs of the
+/// virtual clock) counted from the previous key. The keys are pressed, other events (ie: long
+/// presses) are given with [`ScriptedKeyboard::event_after`].
+///
+/// ```
+/// let keyboard = ScriptedKeyboard::new()
+///     .key(KeyCode::KEY_PROGRAMA)
+///     .key_after(3000, KeyCode::KEY_ENTER)
+///     .wait(1000);
+/// ```
+pub struct ScriptedKeyboard {
+    /// Keys not typed yet, with the time to wait before each one. `None` is a pause without key.
+    script: VecDeque<(Duration, Option<KeyEvent>)>,
+    /// Time of the last key typed (or of the creation of the keyboard)
+    last_key_time: Instant,
+}
+
+impl ScriptedKeyboard {
+    /// Time between the keys if it is not given (in milisecs), longer than the debounce time of the
+    /// keyboard
+    pub const DEFAULT_INTERVAL: u64 = 300;
+
+    pub fn new() -> Self {
+        Self {
+            script: VecDeque::new(),
+            last_key_time: now_instant(),
+        }
+    }
+
+    pub fn key(self, key: KeyCode) -> Self {
+        self.key_after(Self::DEFAULT_INTERVAL, key)
+    }
+
+    pub fn keys(self, keys: &[KeyCode]) -> Self {
+        keys.iter().fold(self, |keyboard, key| keyboard.key(*key))
+    }
+
+    /// Types the key after the given time (in milisecs) since the previous key
+    pub fn key_after(self, wait: u64, key: KeyCode) -> Self {
+        self.event_after(wait, KeyEvent::Pressed(key))
+    }
+
+    /// Sends the event after the given time (in milisecs) since the previous key
+    pub fn event_after(mut self, wait: u64, event: KeyEvent) -> Self {
+        self.script.push_back((Self::duration(wait), Some(event)));
+        self
+    }
+
+    /// Waits the given time (in milisecs) without typing any key, ie: to let a message timeout
+    pub fn wait(mut self, wait: u64) -> Self {
+        self.script.push_back((Self::duration(wait), None));
+        self
+    }
+
+    fn duration(milisecs: u64) -> Duration {
+        Duration::from_millis(milisecs as u32)
+    }
+
+    /// True if all the keys of the script were typed
+    pub fn is_finished(&self) -> bool {
+        self.script.is_empty()
+    }
+}
+
+impl Keyboard for ScriptedKeyboard {
+    fn get_key(&mut self) -> Option<KeyEvent> {
+        let (wait, event) = *self.script.front()?;
+        let now = now_instant();
+        if !now.has_reached(self.last_key_time + wait) {
+            return None;
+        }
+        self.script.pop_front();
+        self.last_key_time = now;
+        event
+    }
+}
@@ -0,0 +1,119 @@
+//! Lcd which keeps the text shown in memory instead of sending it to the display
+
+use core::cell::{Cell, RefCell};
+
+use crate::{
+    board::lcd::{
+        glyph::Glyph,
+        interface::{Lcd, NUMBER_OF_CUSTOM_CHARS},
+        size::{NUMBER_OF_COLS, NUMBER_OF_LINES},
+    },
+    geometry::point::Point,
+};
+
+/// Text framebuffer with the same size of the hardware lcd. As the hardware, the chars beyond the
+/// right border are discarded.
+pub struct LcdFramebuffer {
+    lines: RefCell<[[u8; NUMBER_OF_COLS as usize]; NUMBER_OF_LINES as usize]>,
+    cursor: Cell<Point>,
+    /// Chars and commands received, to check how much the display is written
+    bytes_received: Cell<u32>,
+    /// Bitmaps stored in each CGRAM slot, `None` if the slot was never written
+    custom_chars: RefCell<[Option<[u8; 8]>; NUMBER_OF_CUSTOM_CHARS as usize]>,
+}
+
+impl LcdFramebuffer {
+    pub fn new() -> Self {
+        Self {
+            lines: RefCell::new([[b' '; NUMBER_OF_COLS as usize]; NUMBER_OF_LINES as usize]),
+            cursor: Cell::new(Point::new(0, 0)),
+            bytes_received: Cell::new(0),
+            custom_chars: RefCell::new([None; NUMBER_OF_CUSTOM_CHARS as usize]),
+        }
+    }
+
+    fn count_byte(&self) {
+        self.bytes_received.update(|bytes| bytes + 1);
+    }
+
+    pub fn get_bytes_received(&self) -> u32 {
+        self.bytes_received.get()
+    }
+
+    /// Position where the next char will be printed
+    pub fn get_cursor(&self) -> Point {
+        self.cursor.get()
+    }
+
+    /// Bitmap stored in the given CGRAM slot, `None` if it was never written
+    pub fn get_custom_char(&self, slot: u8) -> Option<[u8; 8]> {
+        self.custom_chars
+            .borrow()
+            .get(slot as usize)
+            .copied()
+            .flatten()
+    }
+
+    /// Text of the given line, the glyphs are shown as similar unicode chars
+    pub fn get_line(&self, row: u8) -> String {
+        self.lines.borrow()[row as usize]
+            .iter()
+            .map(|byte| Self::to_char(*byte))
+            .collect()
+    }
+
+    /// Text of all the lines, from the top to the bottom
+    pub fn get_lines(&self) -> Vec<String> {
+        (0..NUMBER_OF_LINES).map(|row| self.get_line(row)).collect()
+    }
+
+    /// Char shown for the given lcd char, the glyphs are shown as similar unicode chars
+    pub fn to_char(byte: u8) -> char {
+        match Glyph::from_u8(byte) {
+            Some(Glyph::ArrowUp) => '↑',
+            Some(Glyph::ArrowDown) => '↓',
+            Some(Glyph::ArrowRight) => '→',
+            Some(Glyph::ArrowLeft) => '←',
+            Some(Glyph::Lock) => '■',
+            Some(Glyph::AxisInMotion) => '⇄',
+            Some(Glyph::Error) => '!',
+            None => byte as char,
+        }
+    }
+}
+
+impl Lcd for LcdFramebuffer {
+    fn clear(&self) {
+        self.count_byte();
+        *self.lines.borrow_mut() = [[b' '; NUMBER_OF_COLS as usize]; NUMBER_OF_LINES as usize];
+        self.cursor.set(Point::new(0, 0));
+    }
+
+    fn print_u8(&self, byte: u8) {
+        self.count_byte();
+        let Point { x: col, y: row } = self.cursor.get();
+        if col < NUMBER_OF_COLS {
+            self.lines.borrow_mut()[row as usize][col as usize] = byte;
+        }
+        self.cursor.set(Point::new(col.saturating_add(1), row));
+    }
+
+    fn set_cursor(&self, point: Point) {
+        self.count_byte();
+        let Point { x: col, y: row } = point;
+        // as the hardware driver, the line is clamped to the last one
+        self.cursor
+            .set(Point::new(col, row.min(NUMBER_OF_LINES - 1)));
+    }
+
+    /// The bitmaps are just recorded, the glyphs are shown as unicode chars (see `get_line`)
+    fn create_char(&self, slot: u8, bitmap: &[u8; 8]) {
+        // as the hardware, one command to address the CGRAM and one byte per row of the bitmap
+        for _ in 0..=bitmap.len() {
+            self.count_byte();
+        }
+        // as the hardware driver, only the lower bits of the slot are used
+        let slot = slot % NUMBER_OF_CUSTOM_CHARS;
+        self.custom_chars.borrow_mut()[slot as usize] = Some(*bitmap);
+    }
+}
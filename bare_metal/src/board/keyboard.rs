@@ -1,117 +1,341 @@
+#[cfg(target_arch = "avr")]
 use crate::board::input_expander::InputExpander;
 use crate::board::keypad::KeyCode;
+#[cfg(target_arch = "avr")]
 use crate::board::keypad::Keypad;
+#[cfg(target_arch = "avr")]
 use crate::board::output_expander::OutputExpander;
-use crate::microcontroler::delay::delay_ms;
-use crate::microcontroler::timer::now;
+use crate::microcontroler::timer::now_instant;
+use cross_platform::types::time::{Duration, Instant};
 
 /// Keyboard abstraction
 /// TODO: Move to a better place
 pub trait Keyboard {
-    fn get_key(&mut self) -> Option<KeyCode>;
+    fn get_key(&mut self) -> Option<KeyEvent>;
+}
+
+/// What the user did with the keys
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum KeyEvent {
+    /// The key was pressed. If it is held and it repeats (see [`KeyCode::repeats`]) it is sent
+    /// again and again, faster and faster.
+    Pressed(KeyCode),
+    /// The key which does not repeat has been held for a while ([`RepeatConfig::long_press_time`]),
+    /// it is sent once after the [`KeyEvent::Pressed`] of the key. Ie: hold `STOP` to halt all the
+    /// axes.
+    LongPress(KeyCode),
+    /// The key was pressed while `KEY_CTRL` was held. It does not repeat.
+    Ctrl(KeyCode),
+}
+
+/// Keys held down in a scan of the keyboard
+#[derive(Copy, Clone, PartialEq)]
+pub struct KeyScan {
+    /// `NO_KEY` if none
+    pub key: KeyCode,
+    /// If the `KEY_CTRL` is held too
+    pub ctrl: bool,
+}
+
+impl KeyScan {
+    pub const NONE: Self = Self::new(KeyCode::NO_KEY, false);
+
+    pub const fn new(key: KeyCode, ctrl: bool) -> Self {
+        Self { key, ctrl }
+    }
+}
+
+impl KeyCode {
+    /// The arrows and the digits repeat while they are held, the other keys are sent once
+    pub fn repeats(&self) -> bool {
+        self.is_navigation() || self.is_numeral()
+    }
 }
 //
 
-type TimePoint = u16;
+/// Timing of the repetition of the keys held
+#[derive(Copy, Clone)]
+pub struct RepeatConfig {
+    /// Time the scan must stay the same to be accepted, the contacts of the keys bounce for a few
+    /// milisecs when they are pressed and released
+    pub stable_time: Duration,
+    /// Time held before the first repetition
+    pub initial_delay: Duration,
+    /// Time between the first and the second repetitions, the next ones are faster
+    pub initial_interval: Duration,
+    /// How much the time between the repetitions decreases after each one
+    pub acceleration: Duration,
+    /// Shortest time between the repetitions
+    pub minimum_interval: Duration,
+    /// Time held to send the [`KeyEvent::LongPress`] of the keys which do not repeat
+    pub long_press_time: Duration,
+}
+
+impl RepeatConfig {
+    pub const DEFAULT: Self = Self {
+        stable_time: Duration::from_millis(5),
+        initial_delay: Duration::from_millis(500),
+        initial_interval: Duration::from_millis(250),
+        acceleration: Duration::from_millis(25),
+        minimum_interval: Duration::from_millis(50),
+        long_press_time: Duration::from_millis(1500),
+    };
+}
 
-const DEBOUNCE_TIME: TimePoint = 250; // miliseconds
+impl Default for RepeatConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
 
+/// Turns the keys held in each scan into [`KeyEvent`]s, the scan is accepted after it stays the same
+/// for the [`RepeatConfig::stable_time`].
+///
+/// Only one key is considered at a time: other key pressed while a key is held is ignored until all
+/// the keys are released, except the `KEY_CTRL` which makes a chord with the key.
 pub struct Debounce {
-    last_key_time: TimePoint,
+    config: RepeatConfig,
+    /// Keys held in the last scan, before its debounce
+    last_scan: KeyScan,
+    /// Time the last scan started to be the same
+    last_scan_time: Instant,
+    /// Key held in the last scan, `NO_KEY` if none
     last_key: KeyCode,
+    /// Time the key was pressed or repeated for the last time
+    last_key_time: Instant,
+    /// Time until the next repetition
+    interval: Duration,
+    /// True after the first repetition of the key
+    is_repeating: bool,
+    /// True if the key was pressed with the `KEY_CTRL` or if its long press was already sent, in
+    /// both cases nothing else is sent until the key is released
+    is_done: bool,
 }
 
 impl Debounce {
     pub fn new() -> Self {
+        Self::with_config(RepeatConfig::DEFAULT)
+    }
+
+    pub fn with_config(config: RepeatConfig) -> Self {
         Self {
-            last_key_time: now() as TimePoint,
+            config,
+            last_scan: KeyScan::NONE,
+            last_scan_time: now_instant(),
             last_key: KeyCode::NO_KEY,
+            last_key_time: now_instant(),
+            interval: config.initial_delay,
+            is_repeating: false,
+            is_done: false,
         }
     }
 
-    /// This routine avoid that key repetition perform too fast
-    ///
-    /// TODO: Explain why we need KeyCode::NO_KEY if we can just return Option::None when
-    /// no key was been pressed. Or else just remove this ambiguity from code.
-    pub fn debounce_key(&mut self, current_key: KeyCode) -> Option<KeyCode> {
-        let last_key_was_none = self.last_key == KeyCode::NO_KEY;
-        let current_key_is_some = current_key != KeyCode::NO_KEY;
-        if last_key_was_none {
-            if current_key_is_some {
-                // new key detected
-                // register it
-                self.last_key = current_key;
-                self.last_key_time = now() as TimePoint;
-                // initial point
-                return Some(current_key);
+    /// Given the keys held in the current scan returns the event happened, if any
+    pub fn debounce_key(&mut self, scan: KeyScan) -> Option<KeyEvent> {
+        let KeyScan { key, ctrl } = scan;
+        let now = now_instant();
+
+        if scan != self.last_scan {
+            self.last_scan = scan;
+            self.last_scan_time = now;
+        }
+        if now.duration_since(self.last_scan_time) < self.config.stable_time {
+            // the keys are bouncing, keeps the last stable scan until it settles
+            return None;
+        }
+
+        if key == KeyCode::NO_KEY {
+            // key released (the `KEY_CTRL` alone does nothing)
+            self.last_key = KeyCode::NO_KEY;
+            return None;
+        }
+
+        if self.last_key == KeyCode::NO_KEY {
+            // new key detected
+            self.last_key = key;
+            self.last_key_time = now;
+            self.interval = self.config.initial_delay;
+            self.is_repeating = false;
+            self.is_done = ctrl;
+            return Some(if ctrl {
+                KeyEvent::Ctrl(key)
             } else {
-                // waiting key, no key has been pressed yet
+                KeyEvent::Pressed(key)
+            });
+        }
+
+        if key != self.last_key || self.is_done {
+            // other key pressed at the same time, or nothing else to send until the key is released
+            return None;
+        }
+
+        // the same key is still held
+        let elapsed = now.duration_since(self.last_key_time);
+        if key.repeats() {
+            if elapsed < self.interval {
                 return None;
             }
-        } else {
-            // last key was some
-            let current_and_last_key_are_equal = self.last_key == current_key;
-            let current_key_is_none = current_key == KeyCode::NO_KEY;
-            if current_key_is_none {
-                // key unpressed
-                // then reset debounce state
-                self.last_key = current_key;
-                self.last_key_time = now() as TimePoint;
-                return None;
+            self.last_key_time = now;
+            self.interval = if self.is_repeating {
+                self.interval
+                    .saturating_sub(self.config.acceleration)
+                    .max(self.config.minimum_interval)
             } else {
-                // last and current key are some
-                if current_and_last_key_are_equal {
-                    let has_debounce_time_been_passed =
-                        now() as TimePoint > (self.last_key_time + DEBOUNCE_TIME);
-                    if has_debounce_time_been_passed {
-                        //TODO: PERFORM repetition code
-                        self.last_key = current_key;
-                        self.last_key_time = now() as TimePoint;
-                        return Some(current_key);
-                    } else {
-                        return None;
-                    }
-                } else {
-                    // last and current key are some, but they are different
-                    // two keys pressed at same time
-                    // TODO: Implement 'ctrl + key' code
-                    return None;
-                }
-            }
+                self.config.initial_interval
+            };
+            self.is_repeating = true;
+            Some(KeyEvent::Pressed(key))
+        } else if elapsed >= self.config.long_press_time {
+            self.is_done = true;
+            Some(KeyEvent::LongPress(key))
+        } else {
+            None
         }
     }
 }
 
-/// High level function to control keyboard key strokes
+/// High level function to control keyboard key strokes. It does not beep, the keys are clicked by
+/// the main loop (see [`super::panel_patterns`]).
+#[cfg(target_arch = "avr")]
 pub struct KeyboardAvrDriver<'a> {
     pub keypad: Keypad<'a>,
     pub debouncer: Debounce,
-    // TODO: I'm just using one signal (the beep of the buzzer on-board) from the OutputExpander
-    // would be better to simplify this code being more specific.
-    output: &'a OutputExpander,
 }
 
+#[cfg(target_arch = "avr")]
 impl<'a> KeyboardAvrDriver<'a> {
     pub fn new(output: &'a OutputExpander, input: &'a InputExpander) -> Self {
         Self {
             keypad: Keypad::new(output, input),
             debouncer: Debounce::new(),
-            output,
         }
     }
 }
 
+#[cfg(target_arch = "avr")]
 impl<'a> Keyboard for KeyboardAvrDriver<'a> {
-    fn get_key(&mut self) -> Option<KeyCode> {
-        //TODO: put this beep code in a better place and make its timeing non-halting
-        let beep = |key| {
-            self.output.BUZZER(true).commit();
-            delay_ms(20);
-            self.output.BUZZER(false).commit();
-            key
+    fn get_key(&mut self) -> Option<KeyEvent> {
+        let scan = self.keypad.scan();
+        self.debouncer.debounce_key(scan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::microcontroler::timer::advance_virtual_clock;
+
+    /// Scans the key held during the given time (one scan per milisec) and returns the time of each
+    /// event, counted from the first scan
+    fn hold(debounce: &mut Debounce, scan: KeyScan, milisecs: u64) -> Vec<(u64, KeyEvent)> {
+        let mut events = Vec::new();
+        for time in 0..milisecs {
+            if let Some(event) = debounce.debounce_key(scan) {
+                events.push((time, event));
+            }
+            advance_virtual_clock(1);
+        }
+        events
+    }
+
+    fn press(key: KeyCode) -> KeyScan {
+        KeyScan::new(key, false)
+    }
+
+    #[test]
+    fn it_sends_the_key_once_when_it_does_not_repeat() {
+        let mut debounce = Debounce::new();
+        let events = hold(&mut debounce, press(KeyCode::KEY_ENTER), 1000);
+        assert_eq!(events, [(5, KeyEvent::Pressed(KeyCode::KEY_ENTER))]);
+        // the key is sent again after it is released
+        assert!(hold(&mut debounce, KeyScan::NONE, 10).is_empty());
+        let events = hold(&mut debounce, press(KeyCode::KEY_ENTER), 10);
+        assert_eq!(events, [(5, KeyEvent::Pressed(KeyCode::KEY_ENTER))]);
+    }
+
+    #[test]
+    fn it_sends_the_key_once_when_its_contacts_bounce() {
+        let mut debounce = Debounce::new();
+        let key = press(KeyCode::KEY_ENTER);
+        let mut events = Vec::new();
+        for scan in [key, KeyScan::NONE, key, KeyScan::NONE, key] {
+            events.extend(hold(&mut debounce, scan, 1));
+        }
+        events.extend(hold(&mut debounce, key, 100));
+        for scan in [KeyScan::NONE, key, KeyScan::NONE, key, KeyScan::NONE] {
+            events.extend(hold(&mut debounce, scan, 2));
+        }
+        events.extend(hold(&mut debounce, KeyScan::NONE, 100));
+        // sent 5 ms after the last bounce (the time is counted from the first scan of each step)
+        assert_eq!(events, [(4, KeyEvent::Pressed(KeyCode::KEY_ENTER))]);
+        // the key is sent again after it is released, but not after a bounce while it is held
+        let events = hold(&mut debounce, key, 100);
+        assert_eq!(events, [(5, KeyEvent::Pressed(KeyCode::KEY_ENTER))]);
+        hold(&mut debounce, KeyScan::NONE, 1);
+        assert!(hold(&mut debounce, key, 100).is_empty());
+    }
+
+    #[test]
+    fn it_repeats_the_arrows_and_the_digits_faster_and_faster() {
+        let config = RepeatConfig {
+            stable_time: Duration::from_millis(5),
+            initial_delay: Duration::from_millis(500),
+            initial_interval: Duration::from_millis(200),
+            acceleration: Duration::from_millis(50),
+            minimum_interval: Duration::from_millis(100),
+            long_press_time: Duration::from_millis(1500),
         };
+        let mut debounce = Debounce::with_config(config);
+        let key = KeyCode::KEY_DIRECIONAL_PARA_BAIXO;
+        let times: Vec<u64> = hold(&mut debounce, press(key), 1300)
+            .iter()
+            .map(|(time, event)| {
+                assert_eq!(*event, KeyEvent::Pressed(key));
+                *time
+            })
+            .collect();
+        assert_eq!(times, [5, 505, 705, 855, 955, 1055, 1155, 1255]);
+    }
+
+    #[test]
+    fn it_sends_the_long_press_once() {
+        let mut debounce = Debounce::new();
+        let events = hold(&mut debounce, press(KeyCode::KEY_STOP), 5000);
+        assert_eq!(
+            events,
+            [
+                (5, KeyEvent::Pressed(KeyCode::KEY_STOP)),
+                (1505, KeyEvent::LongPress(KeyCode::KEY_STOP)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_sends_the_long_press_across_the_wrap_of_the_clock() {
+        advance_virtual_clock(u32::MAX as u64 - 1000);
+        let mut debounce = Debounce::new();
+        let events = hold(&mut debounce, press(KeyCode::KEY_STOP), 2000);
+        assert_eq!(
+            events,
+            [
+                (5, KeyEvent::Pressed(KeyCode::KEY_STOP)),
+                (1505, KeyEvent::LongPress(KeyCode::KEY_STOP)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_sends_the_chords_with_ctrl() {
+        let mut debounce = Debounce::new();
+        let events = hold(&mut debounce, KeyScan::new(KeyCode::KEY_1, true), 3000);
+        assert_eq!(events, [(5, KeyEvent::Ctrl(KeyCode::KEY_1))]);
+    }
 
-        let current_key = self.keypad.scan();
-        self.debouncer.debounce_key(current_key).map(beep)
+    #[test]
+    fn it_ignores_other_key_while_a_key_is_held() {
+        let mut debounce = Debounce::new();
+        hold(&mut debounce, press(KeyCode::KEY_ENTER), 10);
+        let events = hold(&mut debounce, press(KeyCode::KEY_ESC), 3000);
+        assert!(events.is_empty());
     }
 }
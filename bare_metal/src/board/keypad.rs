@@ -1,16 +1,20 @@
 //! Low-level driver for keypad
 //!
 
-use super::{input_expander::InputExpander, output_expander::OutputExpander};
+#[cfg(target_arch = "avr")]
+use super::{input_expander::InputExpander, keyboard::KeyScan, output_expander::OutputExpander};
+#[cfg(target_arch = "avr")]
 use avr_progmem::progmem;
 
+#[cfg(target_arch = "avr")]
 const ACTIVATED: bool = false; //low level
+#[cfg(target_arch = "avr")]
 const DEACTIVATE: bool = true; //true level
 
 //
 
 #[allow(non_camel_case_types)]
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub enum KeyCode {
     //Some key codes try to imitate ASCII table codes (ie: ESC, Enter and numerals digitis )
     //Other codes on this table was arbitrary assigned.
@@ -161,12 +165,14 @@ impl KeyCode {
 
 //constants
 
+#[cfg(target_arch = "avr")]
 pub struct Keypad<'a> {
     /// NOTE: output is a pointer, because it must be shared with the
     output: &'a OutputExpander,
     input: &'a InputExpander,
 }
 
+#[cfg(target_arch = "avr")]
 progmem! {
     static progmem KEYMAP: [[KeyCode; 8]; 4] = [
         [
@@ -212,6 +218,7 @@ progmem! {
     ];
 }
 
+#[cfg(target_arch = "avr")]
 impl<'a> Keypad<'a> {
     pub fn new(output: &'a OutputExpander, input: &'a InputExpander) -> Self {
         Keypad { output, input }
@@ -247,20 +254,26 @@ impl<'a> Keypad<'a> {
         }
     }
 
-    /// Performs a hardware scan of the keypad signals and returns the key stroke
-    pub fn scan(&self) -> KeyCode {
-        let mut key_code: KeyCode = KeyCode::NO_KEY;
+    /// Performs a hardware scan of the keypad signals and returns the keys held. If many keys are
+    /// held the last one scanned is returned, the `KEY_CTRL` is returned apart.
+    ///
+    /// NOTE: The `KEY_CTRL` is not in the keymap of the current hardware.
+    pub fn scan(&self) -> KeyScan {
+        let mut scan = KeyScan::NONE;
         for collumn in 0..=7 {
             self.set_output(collumn, ACTIVATED);
             for row in 0..=3 {
                 let bit = self.get_input(row);
                 if bit == ACTIVATED {
                     let cols = KEYMAP.load_at(row as usize);
-                    key_code = *cols.get(collumn as usize).unwrap();
+                    match *cols.get(collumn as usize).unwrap() {
+                        KeyCode::KEY_CTRL => scan.ctrl = true,
+                        key_code => scan.key = key_code,
+                    }
                 }
             }
             self.set_output(collumn, DEACTIVATE);
         }
-        key_code
+        scan
     }
 }
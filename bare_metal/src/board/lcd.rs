@@ -1,8 +1,15 @@
+#[cfg(target_arch = "avr")]
 pub mod adapter;
 // TODO: Check in future if is possible to make the driver private
+#[cfg(target_arch = "avr")]
 pub mod driver;
+pub mod glyph;
 pub mod interface;
+pub mod size;
 //
 
+pub use size::{NUMBER_OF_COLS, NUMBER_OF_LINES};
+
 /// TODO: Remove the exposition of the driver, use the adapter instead.
-pub use driver::{clear, lcd_initialize, print, print_u8, set_cursor, NUMBER_OF_LINES};
+#[cfg(target_arch = "avr")]
+pub use driver::{clear, lcd_initialize, print, print_u8, set_cursor};
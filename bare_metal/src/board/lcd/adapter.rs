@@ -4,20 +4,18 @@ use crate::geometry::point::Point;
 
 use super::{driver, interface::Lcd};
 
-/// Basically a wrapper over the hitach hardware lcd [`driver`]
-pub struct LcdHardware40x2;
+/// Basically a wrapper over the hitach hardware lcd [`driver`], its size is defined by the driver
+pub struct LcdHardware;
 
-impl LcdHardware40x2 {
+impl LcdHardware {
     pub fn new() -> Self {
         // initialize hardware lcd
         driver::lcd_initialize();
-        LcdHardware40x2
+        LcdHardware
     }
 }
 
-impl Lcd for LcdHardware40x2 {
-    //const MAX_COLS: u8 = 40;
-    //const MAX_ROWS: u8 = 2;
+impl Lcd for LcdHardware {
     fn clear(&self) {
         driver::clear();
     }
@@ -29,4 +27,8 @@ impl Lcd for LcdHardware40x2 {
         let Point { x: col, y: row } = point;
         driver::set_cursor(col, row);
     }
+
+    fn create_char(&self, slot: u8, bitmap: &[u8; 8]) {
+        driver::create_char(slot, bitmap);
+    }
 }
@@ -43,9 +43,9 @@ use cross_platform::utils::{
 const HIGH: bool = true;
 const LOW: bool = false;
 
-/// LCD CONFIGURATION: in this case 40x2
-const NUMBER_OF_COLS: u8 = 40;
-pub const NUMBER_OF_LINES: u8 = 2;
+use super::interface::NUMBER_OF_CUSTOM_CHARS;
+use super::size::LINE_OFFSETS;
+pub use super::size::{NUMBER_OF_COLS, NUMBER_OF_LINES};
 
 fn init_lcd_pins() {
     port::B4::set_output(); // lcd_rs = PB4
@@ -219,21 +219,29 @@ pub fn clear() {
 
 pub fn set_cursor(col: u8, row: u8) {
     const LCD_SETDDRAMADDR: u8 = 0x80;
-    const LINE_0_OFFSET: u8 = 0x00;
-    const LINE1_OFFSET: u8 = 0x00 + NUMBER_OF_COLS;
+    const LAST_LINE: u8 = NUMBER_OF_LINES - 1;
 
-    let line_offset = match row {
-        0 => LINE_0_OFFSET,
-        _ => LINE1_OFFSET,
-    };
+    let line_offset = LINE_OFFSETS[row.min(LAST_LINE) as usize];
 
     command(LCD_SETDDRAMADDR | (col + line_offset));
 }
 
+/// Stores the bitmap of a custom char (5x8 dots, one byte per row using the five least significant
+/// bits) in the given CGRAM slot. After that the char is printed sending the slot number as data.
+///
+/// NOTE: The cursor position is lost, call [`set_cursor`] before print again.
+pub fn create_char(slot: u8, bitmap: &[u8; 8]) {
+    const LCD_SETCGRAMADDR: u8 = 0x40;
+    let slot = slot % NUMBER_OF_CUSTOM_CHARS;
+    command(LCD_SETCGRAMADDR | (slot << 3));
+    for row in bitmap {
+        write_u8(*row);
+    }
+}
+
 // other commands
 // const LCD_RETURNHOME: u8 = 0x02;
 // const LCD_CURSORSHIFT: u8 = 0x10;
-// const LCD_SETCGRAMADDR: u8 = 0x40;
 
 // flags for display entry mode
 // const LCD_ENTRYRIGHT: u8 = 0x00;
@@ -292,7 +300,8 @@ fn initialization_protocol() {
 /// Initializes LCD given the number of collumns and lines
 ///
 /// NOTE: This function was just tested in LCD 40x2, but my work with little or no
-/// modification in other LCD sizes.
+/// modification in other LCD sizes. The 20x4 and 16x2 displays are also initialized in two
+/// lines mode, which is the mode their controllers use.
 /// NOTE: I made this function still more specialized in LCD 40x2 display, to try to
 /// save some bytes of memory state. But the more generic function can be acessed
 /// in the repository. See commit: 1cf9c0efe402afa2cfe61b67e3fff476cf1b9f01
@@ -0,0 +1,132 @@
+//! Custom chars (glyphs) stored in the lcd CGRAM, ie: icons and arrows.
+//!
+//! The lcd has room for just [`NUMBER_OF_CUSTOM_CHARS`] glyphs. Each [`Glyph`] is stored in the
+//! slot of its own number, so after [`upload_glyphs`] it is printed as any other char:
+//!
+//! ```
+//! region.print(Glyph::ArrowDown);
+//! ```
+
+use avr_progmem::progmem;
+
+use super::interface::{Lcd, NUMBER_OF_CUSTOM_CHARS};
+
+/// Registry of the glyphs used in the app, the value of each variant is its CGRAM slot
+#[repr(u8)]
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Glyph {
+    ArrowUp = 0,
+    ArrowDown = 1,
+    ArrowRight = 2,
+    ArrowLeft = 3,
+    /// Marks the values the user has not access to edit
+    Lock = 4,
+    AxisInMotion = 5,
+    Error = 6,
+}
+
+const NUMBER_OF_GLYPHS: usize = 7;
+
+// ERROR DESCRIPTION: The lcd does not have room for more glyphs.
+const _: () = assert!(NUMBER_OF_GLYPHS <= NUMBER_OF_CUSTOM_CHARS as usize);
+
+progmem! {
+    /// Bitmaps of the glyphs (5x8 dots), indexed by the slot of the glyph
+    static progmem BITMAPS: [[u8; 8]; NUMBER_OF_GLYPHS] = [
+        // ArrowUp
+        [0b00100, 0b01110, 0b10101, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000],
+        // ArrowDown
+        [0b00100, 0b00100, 0b00100, 0b00100, 0b10101, 0b01110, 0b00100, 0b00000],
+        // ArrowRight
+        [0b00000, 0b00100, 0b00010, 0b11111, 0b00010, 0b00100, 0b00000, 0b00000],
+        // ArrowLeft
+        [0b00000, 0b00100, 0b01000, 0b11111, 0b01000, 0b00100, 0b00000, 0b00000],
+        // Lock
+        [0b01110, 0b10001, 0b10001, 0b11111, 0b11011, 0b11011, 0b11111, 0b00000],
+        // AxisInMotion
+        [0b00010, 0b11111, 0b00010, 0b00000, 0b01000, 0b11111, 0b01000, 0b00000],
+        // Error
+        [0b00100, 0b01010, 0b01010, 0b10101, 0b10101, 0b10001, 0b10101, 0b11111],
+    ];
+}
+
+impl Glyph {
+    pub const ALL: [Glyph; NUMBER_OF_GLYPHS] = [
+        Glyph::ArrowUp,
+        Glyph::ArrowDown,
+        Glyph::ArrowRight,
+        Glyph::ArrowLeft,
+        Glyph::Lock,
+        Glyph::AxisInMotion,
+        Glyph::Error,
+    ];
+
+    /// Char code which prints the glyph
+    pub const fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Glyph printed by the given char code, `None` if it is not a glyph
+    pub fn from_u8(code: u8) -> Option<Glyph> {
+        Self::ALL.get(code as usize).copied()
+    }
+
+    fn get_bitmap(self) -> [u8; 8] {
+        BITMAPS.load_at(self.as_u8() as usize)
+    }
+}
+
+/// Makes the glyph [`Printable`](crate::printable::Printable)
+impl IntoIterator for Glyph {
+    type Item = u8;
+    type IntoIter = core::iter::Once<u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        core::iter::once(self.as_u8())
+    }
+}
+
+/// Stores all the glyphs in the lcd CGRAM, it must be called once after the lcd initialization.
+/// NOTE: The cursor position is lost, set it before print again.
+pub fn upload_glyphs(lcd: &dyn Lcd) {
+    for glyph in Glyph::ALL {
+        lcd.create_char(glyph.as_u8(), &glyph.get_bitmap());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::host::lcd::LcdFramebuffer;
+
+    #[test]
+    fn it_converts_the_char_codes_to_glyphs() {
+        for glyph in Glyph::ALL {
+            assert_eq!(Glyph::from_u8(glyph.as_u8()), Some(glyph));
+        }
+        assert_eq!(Glyph::from_u8(NUMBER_OF_GLYPHS as u8), None);
+        assert_eq!(Glyph::from_u8(b'A'), None);
+    }
+
+    #[test]
+    fn it_uploads_each_glyph_to_its_slot() {
+        let lcd = LcdFramebuffer::new();
+        upload_glyphs(&lcd);
+        for glyph in Glyph::ALL {
+            assert_eq!(
+                lcd.get_custom_char(glyph.as_u8()),
+                Some(glyph.get_bitmap()),
+                "{:?}",
+                glyph
+            );
+        }
+        assert_eq!(
+            lcd.get_custom_char(Glyph::Lock.as_u8()),
+            Some([0b01110, 0b10001, 0b10001, 0b11111, 0b11011, 0b11011, 0b11111, 0b00000])
+        );
+        // the free slots are not written
+        for slot in NUMBER_OF_GLYPHS as u8..NUMBER_OF_CUSTOM_CHARS {
+            assert_eq!(lcd.get_custom_char(slot), None);
+        }
+    }
+}
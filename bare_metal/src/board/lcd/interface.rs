@@ -1,5 +1,8 @@
 use crate::geometry::point::Point;
 
+/// Number of custom chars which can be stored in the lcd CGRAM
+pub const NUMBER_OF_CUSTOM_CHARS: u8 = 8;
+
 /// NOTE: It is assumed that any given [`Lcd`] instance is already correctly initialized.
 pub trait Lcd {
     fn clear(&self);
@@ -10,4 +13,8 @@ pub trait Lcd {
     /// NOTE: Assuming (first_line, first_collum) = (0,0) => (col, line) = (x, y);
     /// TODO: Consider to make this function infalible (ie: returning perhaps `Option` or `Result` )
     fn set_cursor(&self, point: Point);
+
+    /// Stores the bitmap of a custom char in the given CGRAM slot (0..8), see [`super::glyph`].
+    /// NOTE: The cursor position is lost, set it before print again.
+    fn create_char(&self, slot: u8, bitmap: &[u8; 8]);
 }
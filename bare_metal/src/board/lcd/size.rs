@@ -0,0 +1,34 @@
+//! Size of the lcd display, it is independent of the platform so the widgets can be laid out (and
+//! tested) in any of them.
+
+// LCD CONFIGURATION: 40x2 by default, other sizes are selected through the cargo features
+#[cfg(all(feature = "lcd_20x4", feature = "lcd_16x2"))]
+compile_error!("Select just one lcd size feature");
+
+#[cfg(not(any(feature = "lcd_20x4", feature = "lcd_16x2")))]
+mod selected {
+    pub const NUMBER_OF_COLS: u8 = 40;
+    pub const NUMBER_OF_LINES: u8 = 2;
+    /// DDRAM address of the first char of each line
+    pub const LINE_OFFSETS: [u8; NUMBER_OF_LINES as usize] = [0x00, NUMBER_OF_COLS];
+}
+
+#[cfg(feature = "lcd_20x4")]
+mod selected {
+    pub const NUMBER_OF_COLS: u8 = 20;
+    pub const NUMBER_OF_LINES: u8 = 4;
+    /// DDRAM address of the first char of each line
+    pub const LINE_OFFSETS: [u8; NUMBER_OF_LINES as usize] = [0x00, 0x40, 0x14, 0x54];
+}
+
+#[cfg(feature = "lcd_16x2")]
+mod selected {
+    pub const NUMBER_OF_COLS: u8 = 16;
+    pub const NUMBER_OF_LINES: u8 = 2;
+    /// DDRAM address of the first char of each line
+    pub const LINE_OFFSETS: [u8; NUMBER_OF_LINES as usize] = [0x00, 0x40];
+}
+
+#[cfg(target_arch = "avr")]
+pub use selected::LINE_OFFSETS;
+pub use selected::{NUMBER_OF_COLS, NUMBER_OF_LINES};
@@ -0,0 +1,295 @@
+//! Patterns of the leds and of the buzzer of the front panel (beeps, blinks, sirens) played
+//! without blocking the main loop
+//!
+//! Each output of the panel has a steady state (ie: the led of the current mode is on) and may play
+//! a pattern over it. A pattern is a sequence of times in which the output stays on and off. When a
+//! pattern which does not repeat ends, the output goes back to its steady state.
+//!
+//! The patterns are just requested by the widgets, the outputs are changed in
+//! [`PanelPatterns::update`] which must be called frequently by the main loop. It is not called in
+//! the interrupt of the timer because the panel shares the shift register with the keypad scan.
+//!
+//! # Example
+//!
+//! ```
+//! let patterns = PanelPatterns::new();
+//! patterns.set(PanelOutput::LedPrograma, true);
+//! patterns.play(PanelOutput::Buzzer, Pattern::KEY_CLICK);
+//! loop {
+//!     patterns.update(&mut front_panel);
+//! }
+//! ```
+
+use core::cell::Cell;
+
+use super::front_panel::FrontPanel;
+use crate::microcontroler::timer::now_instant;
+use cross_platform::types::time::{Duration, Instant};
+
+/// The leds and the buzzer of the front panel
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PanelOutput {
+    LedErro,
+    LedPosAlc,
+    Buzzer,
+    LedManual,
+    LedExecucao,
+    LedPrograma,
+}
+
+impl PanelOutput {
+    const ALL: [PanelOutput; 6] = [
+        PanelOutput::LedErro,
+        PanelOutput::LedPosAlc,
+        PanelOutput::Buzzer,
+        PanelOutput::LedManual,
+        PanelOutput::LedExecucao,
+        PanelOutput::LedPrograma,
+    ];
+
+    fn write(&self, front_panel: &mut impl FrontPanel, on: bool) {
+        match self {
+            PanelOutput::LedErro => front_panel.LED_ERRO(on),
+            PanelOutput::LedPosAlc => front_panel.LED_POS_ALC(on),
+            PanelOutput::Buzzer => front_panel.BUZZER(on),
+            PanelOutput::LedManual => front_panel.LED_MANUAL(on),
+            PanelOutput::LedExecucao => front_panel.LED_EXECUCAO(on),
+            PanelOutput::LedPrograma => front_panel.LED_PROGRAMA(on),
+        }
+    }
+}
+
+/// Times in milisecs in which the output stays on and off alternately, starting on
+///
+/// NOTE: The times are counted between the calls of [`PanelPatterns::update`], so they are as
+/// precise as the main loop is fast.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Pattern {
+    steps: &'static [u16],
+    /// If `true` the pattern plays until it is stopped
+    repeat: bool,
+}
+
+impl Pattern {
+    /// Short beep given on each key
+    pub const KEY_CLICK: Pattern = Pattern::once(&[20]);
+    /// Beep to confirm an action
+    pub const BEEP: Pattern = Pattern::once(&[150]);
+    /// Three long beeps to inform an error
+    pub const ERROR: Pattern = Pattern::once(&[300, 150, 300, 150, 300]);
+    /// Alarm which sounds until it is stopped
+    pub const SIREN: Pattern = Pattern::repeat(&[100, 100, 100, 700]);
+    pub const BLINK: Pattern = Pattern::repeat(&[500, 500]);
+    pub const FAST_BLINK: Pattern = Pattern::repeat(&[100, 100]);
+
+    /// Pattern played one time
+    pub const fn once(steps: &'static [u16]) -> Self {
+        Self {
+            steps,
+            repeat: false,
+        }
+    }
+
+    /// Pattern played until it is stopped
+    pub const fn repeat(steps: &'static [u16]) -> Self {
+        Self {
+            steps,
+            repeat: true,
+        }
+    }
+
+    /// Duration of the step
+    const fn duration(step: u16) -> Duration {
+        Duration::from_millis(step as u32)
+    }
+}
+
+/// State of each output
+#[derive(Copy, Clone)]
+struct Slot {
+    steady: bool,
+    pattern: Option<Pattern>,
+    /// Index of the current step of the pattern
+    step: usize,
+    /// Time in which the current step ends
+    step_end: Instant,
+    /// Last state written to the front panel, `None` before the first write
+    written: Option<bool>,
+}
+
+impl Slot {
+    const OFF: Slot = Slot {
+        steady: false,
+        pattern: None,
+        step: 0,
+        step_end: Instant::from_millis(0),
+        written: None,
+    };
+
+    /// Goes to the next step if the current one has ended. At most one step is advanced by call,
+    /// so a late call delays the rest of the pattern instead of skipping steps.
+    fn advance(&mut self, now: Instant) {
+        let Some(pattern) = self.pattern else {
+            return;
+        };
+        if !now.has_reached(self.step_end) {
+            return;
+        }
+        self.step += 1;
+        if self.step >= pattern.steps.len() {
+            if !pattern.repeat {
+                self.pattern = None;
+                return;
+            }
+            self.step = 0;
+        }
+        self.step_end = now + Pattern::duration(pattern.steps[self.step]);
+    }
+
+    fn state(&self) -> bool {
+        match self.pattern {
+            // the even steps are on
+            Some(_) => self.step % 2 == 0,
+            None => self.steady,
+        }
+    }
+}
+
+/// Schedules the patterns of all the outputs of the front panel. It is shared by reference, so
+/// any widget may request a pattern.
+pub struct PanelPatterns {
+    slots: [Cell<Slot>; PanelOutput::ALL.len()],
+}
+
+impl PanelPatterns {
+    /// All the outputs are off
+    pub fn new() -> Self {
+        Self {
+            slots: [(); PanelOutput::ALL.len()].map(|_| Cell::new(Slot::OFF)),
+        }
+    }
+
+    fn change(&self, output: PanelOutput, change: impl FnOnce(&mut Slot)) {
+        let cell = &self.slots[output as usize];
+        let mut slot = cell.get();
+        change(&mut slot);
+        cell.set(slot);
+    }
+
+    /// Sets the state of the output when no pattern is playing on it
+    pub fn set(&self, output: PanelOutput, on: bool) {
+        self.change(output, |slot| slot.steady = on)
+    }
+
+    /// Starts the pattern in the output, replacing the one which is playing
+    pub fn play(&self, output: PanelOutput, pattern: Pattern) {
+        let now = now_instant();
+        self.change(output, |slot| match pattern.steps.first() {
+            Some(&first_step) => {
+                slot.pattern = Some(pattern);
+                slot.step = 0;
+                slot.step_end = now + Pattern::duration(first_step);
+            }
+            None => slot.pattern = None,
+        })
+    }
+
+    /// Stops the pattern of the output, it goes back to its steady state
+    pub fn stop(&self, output: PanelOutput) {
+        self.change(output, |slot| slot.pattern = None)
+    }
+
+    pub fn is_playing(&self, output: PanelOutput) -> bool {
+        self.slots[output as usize].get().pattern.is_some()
+    }
+
+    /// Advances the patterns and writes to the front panel the outputs which have changed
+    pub fn update(&self, front_panel: &mut impl FrontPanel) {
+        let now = now_instant();
+        for output in PanelOutput::ALL {
+            self.change(output, |slot| {
+                slot.advance(now);
+                let state = slot.state();
+                if slot.written != Some(state) {
+                    output.write(front_panel, state);
+                    slot.written = Some(state);
+                }
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::host::front_panel::FrontPanelRecorder, microcontroler::timer::advance_virtual_clock,
+    };
+
+    /// Updates the patterns each milisec and returns the times in which the buzzer changed
+    fn buzzer_changes(patterns: &PanelPatterns, milisecs: u64) -> Vec<(u64, bool)> {
+        let mut front_panel = FrontPanelRecorder::new();
+        let mut changes = Vec::new();
+        for time in 0..milisecs {
+            let before = front_panel.buzzer;
+            patterns.update(&mut front_panel);
+            if front_panel.buzzer != before {
+                changes.push((time, front_panel.buzzer));
+            }
+            advance_virtual_clock(1);
+        }
+        changes
+    }
+
+    #[test]
+    fn it_plays_a_pattern_once() {
+        let patterns = PanelPatterns::new();
+        patterns.play(PanelOutput::Buzzer, Pattern::once(&[20, 10, 30]));
+        let changes = buzzer_changes(&patterns, 100);
+        assert_eq!(changes, [(0, true), (20, false), (30, true), (60, false)]);
+        assert!(!patterns.is_playing(PanelOutput::Buzzer));
+    }
+
+    #[test]
+    fn it_repeats_a_pattern_until_it_is_stopped() {
+        let patterns = PanelPatterns::new();
+        patterns.play(PanelOutput::Buzzer, Pattern::repeat(&[10, 20]));
+        let changes = buzzer_changes(&patterns, 65);
+        assert_eq!(
+            changes,
+            [(0, true), (10, false), (30, true), (40, false), (60, true)]
+        );
+        patterns.stop(PanelOutput::Buzzer);
+        assert!(!patterns.is_playing(PanelOutput::Buzzer));
+    }
+
+    #[test]
+    fn it_goes_back_to_the_steady_state() {
+        let patterns = PanelPatterns::new();
+        let mut front_panel = FrontPanelRecorder::new();
+        patterns.set(PanelOutput::LedErro, true);
+        patterns.play(PanelOutput::LedErro, Pattern::once(&[10, 10]));
+        patterns.update(&mut front_panel);
+        assert!(front_panel.led_erro);
+        advance_virtual_clock(10);
+        patterns.update(&mut front_panel);
+        assert!(!front_panel.led_erro);
+        advance_virtual_clock(10);
+        patterns.update(&mut front_panel);
+        assert!(front_panel.led_erro);
+        assert!(!patterns.is_playing(PanelOutput::LedErro));
+    }
+
+    #[test]
+    fn it_writes_only_the_outputs_which_have_changed() {
+        let patterns = PanelPatterns::new();
+        let mut front_panel = FrontPanelRecorder::new();
+        patterns.update(&mut front_panel);
+        // the front panel is changed behind the patterns, the next update must not overwrite it
+        front_panel.led_manual = true;
+        patterns.set(PanelOutput::LedPrograma, true);
+        patterns.update(&mut front_panel);
+        assert!(front_panel.led_manual);
+        assert!(front_panel.led_programa);
+    }
+}
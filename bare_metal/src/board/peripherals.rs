@@ -8,14 +8,17 @@
 //!   * Canvas
 //!
 //! NOTE: We are initializing serial port in other place, not here.
-use crate::{menu::screen_buffer::ScreenBuffer, microcontroler::timer::init_timer};
+//!
+//! In the host the peripherals are given by the simulator.
+use crate::menu::screen_buffer::ScreenBuffer;
+#[cfg(target_arch = "avr")]
+use crate::microcontroler::timer::init_timer;
 
+use super::{front_panel::FrontPanel, keyboard::Keyboard};
+#[cfg(target_arch = "avr")]
 use super::{
-    front_panel::{FrontPanel, FrontPanelAvrHardware},
-    input_expander::InputExpander,
-    keyboard::{Keyboard, KeyboardAvrDriver},
-    lcd::adapter::LcdHardware40x2,
-    output_expander::OutputExpander,
+    front_panel::FrontPanelAvrHardware, input_expander::InputExpander, keyboard::KeyboardAvrDriver,
+    lcd::adapter::LcdHardware, output_expander::OutputExpander,
 };
 
 /// Abstraction over the platform specific hardware instantiation
@@ -27,12 +30,14 @@ pub trait Peripherals {
 }
 
 /// On board peripherals initialization
+#[cfg(target_arch = "avr")]
 pub struct PeripheralsAvrHardware {
     output_expander: OutputExpander,
     input_expander: InputExpander,
-    hardware_lcd: LcdHardware40x2,
+    hardware_lcd: LcdHardware,
 }
 
+#[cfg(target_arch = "avr")]
 impl PeripheralsAvrHardware {
     /// Initialize peripherals
     ///
@@ -49,11 +54,12 @@ impl PeripheralsAvrHardware {
         Self {
             output_expander: OutputExpander::new(),
             input_expander: InputExpander::new(),
-            hardware_lcd: LcdHardware40x2::new(),
+            hardware_lcd: LcdHardware::new(),
         }
     }
 }
 
+#[cfg(target_arch = "avr")]
 impl Peripherals for PeripheralsAvrHardware {
     fn get_keyboard(&self) -> impl Keyboard + '_ {
         let keyboard = KeyboardAvrDriver::new(&self.output_expander, &self.input_expander);
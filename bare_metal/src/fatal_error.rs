@@ -1,5 +1,7 @@
+#[cfg(target_arch = "avr")]
 use crate::board::lcd::driver;
 
+#[cfg(target_arch = "avr")]
 pub fn print_error_code_and_panics(error_code: u8) -> ! {
     // Assumes that lcd has already been initialized.
     driver::clear();
@@ -10,6 +12,12 @@ pub fn print_error_code_and_panics(error_code: u8) -> ! {
     panic!()
 }
 
+/// In the host there is no lcd to show the error, the code goes in the panic message
+#[cfg(not(target_arch = "avr"))]
+pub fn print_error_code_and_panics(error_code: u8) -> ! {
+    panic!("Err:{:02X}", error_code)
+}
+
 /// TODO: Place this module inside the [`panic.rs`] module
 /// TODO: When possible subs `error_code` argument to a enumeration that can be mapped to an u8.
 #[macro_export]
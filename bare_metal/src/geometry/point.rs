@@ -1,6 +1,6 @@
 // **********************************
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Point<T = u8> {
     pub x: T,
     pub y: T,
@@ -1,45 +1,87 @@
-use core::cell::Cell;
-
 use crate::geometry::point::Point;
 
-/// Rectangle internal spec
+/// Represents a geometric rectangle
+///
 /// X increases from left to right, Y increases from up to  bottom.
-struct RectInternal {
+#[derive(Copy, Clone, PartialEq)]
+pub struct Rectangle {
     // uppper-left corner (inclusive)
-    x_min: u8,
-    y_max: u8,
+    left_upper: Point,
     // bottom-right corner (exclusive)
-    x_max: u8,
-    y_min: u8,
-}
-
-/// Represents a geometric rectangle
-pub struct Rectangle {
-    coords: Cell<RectInternal>,
+    right_bottom: Point,
 }
 
 impl Rectangle {
     /// X increases from left to right, Y increases from up to  bottom.
-    pub fn new_from_raw(x_min: u8, y_max: u8, x_max: u8, y_min: u8) -> Self {
+    ///
+    /// If the `right_bottom` corner is before the `left_upper` corner the rectangle is empty.
+    pub fn new(left_upper: Point, right_bottom: Point) -> Self {
+        let right_bottom = Point::new(
+            right_bottom.x.max(left_upper.x),
+            right_bottom.y.max(left_upper.y),
+        );
         Self {
-            coords: Cell::new(RectInternal {
-                x_min,
-                y_min,
-                x_max,
-                y_max,
-            }),
+            left_upper,
+            right_bottom,
         }
     }
 
-    /// X increases from left to right, Y increases from up to  bottom.
-    pub fn new(left_upper: Point, right_bottom: Point) -> Self {
-        let Point { x: x_min, y: y_max } = left_upper;
-        let Point { x: x_max, y: y_min } = right_bottom;
-        Self::new_from_raw(x_min, y_max, x_max, y_min)
+    /// Creates a rectangle from its upper-left corner and its size
+    pub fn from_size(left_upper: Point, width: u8, height: u8) -> Self {
+        let right_bottom = Point::new(
+            left_upper.x.saturating_add(width),
+            left_upper.y.saturating_add(height),
+        );
+        Self::new(left_upper, right_bottom)
     }
 
     /// Crates a rectangle with zero_area positioned in origin
     pub fn new_empty() -> Self {
-        Self::new_from_raw(0, 0, 0, 0)
+        Self::new(Point::new(0, 0), Point::new(0, 0))
+    }
+
+    pub fn get_left_upper(&self) -> Point {
+        self.left_upper
+    }
+
+    pub fn width(&self) -> u8 {
+        self.right_bottom.x - self.left_upper.x
+    }
+
+    pub fn height(&self) -> u8 {
+        self.right_bottom.y - self.left_upper.y
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        (self.left_upper.x..self.right_bottom.x).contains(&point.x)
+            && (self.left_upper.y..self.right_bottom.y).contains(&point.y)
+    }
+
+    /// Area common to both rectangles, it is empty if they do not overlap
+    pub fn intersection(&self, other: &Rectangle) -> Rectangle {
+        let left_upper = Point::new(
+            self.left_upper.x.max(other.left_upper.x),
+            self.left_upper.y.max(other.left_upper.y),
+        );
+        let right_bottom = Point::new(
+            self.right_bottom.x.min(other.right_bottom.x),
+            self.right_bottom.y.min(other.right_bottom.y),
+        );
+        Self::new(left_upper, right_bottom)
+    }
+
+    /// Converts a rectangle given in coordinates relative to the upper-left corner of this
+    /// rectangle into absolute coordinates. The part of it outside this rectangle is clipped.
+    pub fn sub_rectangle(&self, relative: Rectangle) -> Rectangle {
+        let left_upper = Point::new(
+            self.left_upper.x.saturating_add(relative.left_upper.x),
+            self.left_upper.y.saturating_add(relative.left_upper.y),
+        );
+        let absolute = Self::from_size(left_upper, relative.width(), relative.height());
+        self.intersection(&absolute)
     }
 }
@@ -1,5 +1,7 @@
-#![no_std]
-#![no_main]
+// In the host (ie: `cargo test` and the simulator) the std is used and the hardware drivers are not
+// compiled, see `board::host`, `microcontroler::timer` and `simulator`.
+#![cfg_attr(target_arch = "avr", no_std)]
+#![cfg_attr(target_arch = "avr", no_main)]
 #![feature(abi_avr_interrupt)]
 #![feature(alloc_error_handler)] // necessary to execute file ./custom_alloc/alloc_error_handler.rs
 #![feature(exclusive_range_pattern)]
@@ -10,8 +12,10 @@
 #![feature(cell_update)] // used to update davigation state // TODO: check if this feature is stable enough
                          //
 extern crate alloc;
+#[cfg(target_arch = "avr")]
 #[lang = "eh_personality"]
 extern "C" fn eh_personality() {}
+#[cfg(target_arch = "avr")]
 mod custom_alloc;
 
 // Load main modules
@@ -22,14 +26,25 @@ mod fatal_error;
 mod geometry;
 mod menu;
 mod microcontroler;
+#[cfg(target_arch = "avr")]
 mod panic;
 mod printable;
+mod scheduler;
+#[cfg(not(target_arch = "avr"))]
+mod simulator;
 mod string;
 
 // Initialize app
+#[cfg(target_arch = "avr")]
 #[no_mangle] // TODO: it seems this line is not necessary remove it if possible.
 #[arduino_hal::entry]
 fn main() -> ! {
     // Application entry point
-    app::run()
+    app::run(board::peripherals::PeripheralsAvrHardware::new())
+}
+
+// In the host the application runs in the terminal, see `simulator`
+#[cfg(all(not(target_arch = "avr"), not(test)))]
+fn main() {
+    simulator::run()
 }
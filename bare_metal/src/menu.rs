@@ -1,3 +1,5 @@
 pub mod model;
 pub mod screen_buffer;
+#[cfg(test)]
+pub mod snapshot;
 pub mod widget;
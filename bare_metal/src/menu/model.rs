@@ -1,14 +1,9 @@
 use core::cell::Cell;
 
-use cross_platform::{
-    protocol::{
-        datalink::datalink::Status,
-        transport::transport_layer::{TLError, TransportLayer},
-    },
-    utils::cursor::Cursor,
-};
+use cross_platform::utils::cursor::Cursor;
 
-use crate::{fatal_error, microcontroler::eeprom::EepromAddress};
+use crate::fatal_error;
+use crate::microcontroler::eeprom::{EepromAddress, EepromWriter, IncrementalWriter, EEPROM_SIZE};
 
 ///
 
@@ -21,17 +16,41 @@ trait EepromStorable {
     /// When microcontroler is flashed first time, the eeprom is erased and is in an invalid state
     /// we use this signature to inform that the block of eeprom data is initialized
     const SIGNATURE: u16;
+    /// Serializes the data into the writer and returns the writer of the next byte
+    fn save_into<W: EepromWriter>(&self, writer: W) -> W;
     /// Given initial address, write data and return next available address and size written in bytes
-    /// TODO: KNOWN-ISSUES: EepromAddress currently only address first 255 bytes of eeprom
-    fn save_into_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8);
+    fn save_into_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
+        let next = self.save_into(initial_address);
+        let size_of_bytes_written = (next.0 - initial_address.0) as u8;
+        (next, size_of_bytes_written)
+    }
     /// Given an initial address load data from eeprom in itself and return next address available
     /// and the size of bytes read
-    /// /// TODO: KNOWN-ISSUES: only address first 255 bytes of eeprom
-    fn load_from_eeprom(&mut self, initial_address: EepromAddress) -> (EepromAddress, u8);
+    ///
+    /// NOTE: Takes `&self` because all the data is stored in `Cell`s, this allows to lazy load data
+    /// while the model is being shared with the menus.
+    fn load_from_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8);
+    /// True if the data in ram is different from the data stored in eeprom at the given address
+    ///
+    /// NOTE: It only reads the eeprom, a block not initialized yet is different from any data
+    /// (`load_from_eeprom` would initialize it).
+    fn differs_from_eeprom(&self, initial_address: EepromAddress) -> bool
+    where
+        Self: Default + PartialEq,
+    {
+        let (signature, _) = initial_address.read_u16();
+        if signature != Self::SIGNATURE {
+            return true;
+        }
+        let saved = Self::default();
+        saved.load_from_eeprom(initial_address);
+        *self != saved
+    }
 }
 
 ///
 
+#[derive(PartialEq)]
 pub struct ArquivoDeEixo {
     // PARAMETROS DE MOVIMENTO
     pub posicao_inicial: Cell<u16>,
@@ -74,8 +93,8 @@ pub struct ArquivoDeEixo {
 impl EepromStorable for ArquivoDeEixo {
     const SIGNATURE: u16 = 0xA000;
 
-    fn save_into_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
-        let next = initial_address
+    fn save_into<W: EepromWriter>(&self, writer: W) -> W {
+        writer
             .write_u16(Self::SIGNATURE)
             .write_u16(self.posicao_inicial.get())
             .write_u16(self.posicao_final.get())
@@ -107,16 +126,12 @@ impl EepromStorable for ArquivoDeEixo {
             .write_u16(self.retardo_no_sinal_de_impressao.get())
             .write_u16(self.retardo_no_start_passo_a_passo.get())
             .write_cursor(self.start_automatico_passo_a_passo.get())
-            .write_cursor(self.saida_de_start_passo_a_passo.get());
-
-        let size_of_bytes_written = next.0 - initial_address.0;
-        (next, size_of_bytes_written)
+            .write_cursor(self.saida_de_start_passo_a_passo.get())
     }
 
     /// Given an initial address load data from eeprom in itself and return next address available
     /// and the size of bytes read
-    /// /// TODO: KNOWN-ISSUES: only address first 255 bytes of eeprom
-    fn load_from_eeprom(&mut self, initial_address: EepromAddress) -> (EepromAddress, u8) {
+    fn load_from_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
         let next = initial_address;
         let (signature, next) = next.read_u16();
 
@@ -217,7 +232,7 @@ impl EepromStorable for ArquivoDeEixo {
             self.saida_de_start_passo_a_passo.set(value);
 
             //
-            let size_of_bytes_loadded = next.0 - initial_address.0;
+            let size_of_bytes_loadded = (next.0 - initial_address.0) as u8;
             (next, size_of_bytes_loadded)
         } else {
             // EEPROM is not initialized yet
@@ -267,8 +282,99 @@ impl Default for ArquivoDeEixo {
     }
 }
 
+/// Rules relating the parameters of an `ArquivoDeEixo` to each other, they are verified beyond the
+/// valid range of each parameter.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RegraDeValidacao {
+    /// `posicao_final` must be greater than `posicao_inicial`
+    PosicaoFinalMaiorQueInicial,
+    /// The messages must be printed inside the stroke (from `posicao_inicial` to `posicao_final`)
+    PrimeiraMensagemNoAvancoDentroDoCurso,
+    UltimaMensagemNoAvancoDentroDoCurso,
+    PrimeiraMensagemNoRetornoDentroDoCurso,
+    UltimaMensagemNoRetornoDentroDoCurso,
+    /// In the advance the positions grow, so the last message can not be before the first one
+    UltimaMensagemNoAvancoAposPrimeira,
+    /// In the return the positions decrease, so the last message can not be after the first one
+    UltimaMensagemNoRetornoAntesDaPrimeira,
+    /// There must be at least 1mm between two consecutive messages
+    MensagensNoAvancoCabemEntrePrimeiraEUltima,
+    MensagensNoRetornoCabemEntrePrimeiraEUltima,
+}
+
+impl RegraDeValidacao {
+    /// All the rules, in the order they are verified
+    pub const TODAS: [RegraDeValidacao; 9] = [
+        RegraDeValidacao::PosicaoFinalMaiorQueInicial,
+        RegraDeValidacao::PrimeiraMensagemNoAvancoDentroDoCurso,
+        RegraDeValidacao::UltimaMensagemNoAvancoDentroDoCurso,
+        RegraDeValidacao::PrimeiraMensagemNoRetornoDentroDoCurso,
+        RegraDeValidacao::UltimaMensagemNoRetornoDentroDoCurso,
+        RegraDeValidacao::UltimaMensagemNoAvancoAposPrimeira,
+        RegraDeValidacao::UltimaMensagemNoRetornoAntesDaPrimeira,
+        RegraDeValidacao::MensagensNoAvancoCabemEntrePrimeiraEUltima,
+        RegraDeValidacao::MensagensNoRetornoCabemEntrePrimeiraEUltima,
+    ];
+}
+
+impl ArquivoDeEixo {
+    /// True if the given rule is satisfied by the current parameters
+    pub fn verificar(&self, regra: RegraDeValidacao) -> bool {
+        let inicial = self.posicao_inicial.get();
+        let final_ = self.posicao_final.get();
+        let dentro_do_curso = |posicao: u16| posicao >= inicial && posicao <= final_;
+        // at least 1mm between each message
+        let mensagens_cabem = |numero_de_mensagens: u16, primeira: u16, ultima: u16| {
+            numero_de_mensagens.saturating_sub(1) <= primeira.abs_diff(ultima)
+        };
+        match regra {
+            RegraDeValidacao::PosicaoFinalMaiorQueInicial => final_ > inicial,
+            RegraDeValidacao::PrimeiraMensagemNoAvancoDentroDoCurso => {
+                dentro_do_curso(self.primeira_mensagem_no_avanco.get())
+            }
+            RegraDeValidacao::UltimaMensagemNoAvancoDentroDoCurso => {
+                dentro_do_curso(self.ultima_mensagem_no_avanco.get())
+            }
+            RegraDeValidacao::PrimeiraMensagemNoRetornoDentroDoCurso => {
+                dentro_do_curso(self.primeira_mensagem_no_retorno.get())
+            }
+            RegraDeValidacao::UltimaMensagemNoRetornoDentroDoCurso => {
+                dentro_do_curso(self.ultima_mensagem_no_retorno.get())
+            }
+            RegraDeValidacao::UltimaMensagemNoAvancoAposPrimeira => {
+                self.ultima_mensagem_no_avanco.get() >= self.primeira_mensagem_no_avanco.get()
+            }
+            RegraDeValidacao::UltimaMensagemNoRetornoAntesDaPrimeira => {
+                self.ultima_mensagem_no_retorno.get() <= self.primeira_mensagem_no_retorno.get()
+            }
+            RegraDeValidacao::MensagensNoAvancoCabemEntrePrimeiraEUltima => mensagens_cabem(
+                self.numero_de_mensagem_no_avanco.get(),
+                self.primeira_mensagem_no_avanco.get(),
+                self.ultima_mensagem_no_avanco.get(),
+            ),
+            RegraDeValidacao::MensagensNoRetornoCabemEntrePrimeiraEUltima => mensagens_cabem(
+                self.numero_de_mensagem_no_retorno.get(),
+                self.primeira_mensagem_no_retorno.get(),
+                self.ultima_mensagem_no_retorno.get(),
+            ),
+        }
+    }
+
+    /// Verifies all the rules, returns the first rule violated if any.
+    pub fn validar(&self) -> Result<(), RegraDeValidacao> {
+        match RegraDeValidacao::TODAS
+            .into_iter()
+            .find(|regra| !self.verificar(*regra))
+        {
+            Some(regra) => Err(regra),
+            None => Ok(()),
+        }
+    }
+}
+
 // ********************************************************
 
+#[derive(PartialEq)]
 pub struct ConfiguracaoDoEixo {
     pub numero_do_canal: Cell<u16>,
     pub numero_de_pulso_do_giro: Cell<u16>,
@@ -308,8 +414,8 @@ impl Default for ConfiguracaoDoEixo {
 impl EepromStorable for ConfiguracaoDoEixo {
     const SIGNATURE: u16 = 0xB000;
 
-    fn save_into_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
-        let next = initial_address
+    fn save_into<W: EepromWriter>(&self, writer: W) -> W {
+        writer
             .write_u16(Self::SIGNATURE)
             .write_u16(self.numero_do_canal.get())
             .write_u16(self.numero_de_pulso_do_giro.get())
@@ -323,13 +429,10 @@ impl EepromStorable for ConfiguracaoDoEixo {
             .write_u16(self.aceleracao_para_referencia.get())
             .write_cursor(self.reducao_da_corrente_em_repouso.get())
             .write_cursor(self.referencia_pelo_start_externo.get())
-            .write_cursor(self.modo_turbo.get());
-
-        let size_of_bytes_written = next.0 - initial_address.0;
-        (next, size_of_bytes_written)
+            .write_cursor(self.modo_turbo.get())
     }
 
-    fn load_from_eeprom(&mut self, initial_address: EepromAddress) -> (EepromAddress, u8) {
+    fn load_from_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
         let next = initial_address;
         let (signature, next) = next.read_u16();
 
@@ -376,7 +479,7 @@ impl EepromStorable for ConfiguracaoDoEixo {
             self.modo_turbo.set(value);
 
             //
-            let size_of_bytes_loadded = next.0 - initial_address.0;
+            let size_of_bytes_loadded = (next.0 - initial_address.0) as u8;
             (next, size_of_bytes_loadded)
         } else {
             // EEPROM is not initialized yet
@@ -390,6 +493,7 @@ impl EepromStorable for ConfiguracaoDoEixo {
 
 ///
 
+#[derive(PartialEq)]
 pub struct ConfiguracaoDoEquipamento {
     pub velocidade_de_comunicacao: Cell<Cursor>, // 0 => 2400, 1 => 9600
 }
@@ -405,16 +509,13 @@ impl Default for ConfiguracaoDoEquipamento {
 impl EepromStorable for ConfiguracaoDoEquipamento {
     const SIGNATURE: u16 = 0x0C00;
 
-    fn save_into_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
-        let next = initial_address
+    fn save_into<W: EepromWriter>(&self, writer: W) -> W {
+        writer
             .write_u16(Self::SIGNATURE)
-            .write_cursor(self.velocidade_de_comunicacao.get());
-
-        let size_of_bytes_written = next.0 - initial_address.0;
-        (next, size_of_bytes_written)
+            .write_cursor(self.velocidade_de_comunicacao.get())
     }
 
-    fn load_from_eeprom(&mut self, initial_address: EepromAddress) -> (EepromAddress, u8) {
+    fn load_from_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
         let next = initial_address;
         let (signature, next) = next.read_u16();
 
@@ -425,7 +526,205 @@ impl EepromStorable for ConfiguracaoDoEquipamento {
             self.velocidade_de_comunicacao.set(value);
 
             //
-            let size_of_bytes_loaded = next.0 - initial_address.0;
+            let size_of_bytes_loaded = (next.0 - initial_address.0) as u8;
+            (next, size_of_bytes_loaded)
+        } else {
+            // EEPROM is not initialized yet
+            // Then initialize it.
+
+            Self::default().save_into_eeprom(initial_address);
+            self.load_from_eeprom(initial_address)
+        }
+    }
+}
+
+/// Each one of the blocks of data stored in eeprom which can be edited by the user in the menu
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Registro {
+    /// The program loaded in ram for the axis
+    ArquivoDeEixo(Eixo),
+    ConfiguracaoDoEixo(Eixo),
+    ConfiguracaoDoEquipamento,
+}
+
+impl Registro {
+    pub const TODOS: [Registro; 5] = [
+        Registro::ArquivoDeEixo(Eixo::X),
+        Registro::ArquivoDeEixo(Eixo::Y),
+        Registro::ConfiguracaoDoEixo(Eixo::X),
+        Registro::ConfiguracaoDoEixo(Eixo::Y),
+        Registro::ConfiguracaoDoEquipamento,
+    ];
+
+    fn mask(&self) -> u8 {
+        match self {
+            Registro::ArquivoDeEixo(Eixo::X) => 1 << 0,
+            Registro::ArquivoDeEixo(Eixo::Y) => 1 << 1,
+            Registro::ConfiguracaoDoEixo(Eixo::X) => 1 << 2,
+            Registro::ConfiguracaoDoEixo(Eixo::Y) => 1 << 3,
+            Registro::ConfiguracaoDoEquipamento => 1 << 4,
+        }
+    }
+}
+
+/// Set of the [`Registro`]s changed in ram which were not saved in eeprom yet (the dirty set).
+#[derive(Copy, Clone, Default, PartialEq)]
+pub struct Alteracoes(u8);
+
+impl Alteracoes {
+    pub fn incluir(&mut self, registro: Registro) {
+        self.0 |= registro.mask();
+    }
+
+    pub fn excluir(&mut self, registro: Registro) {
+        self.0 &= !registro.mask();
+    }
+
+    pub fn contem(&self, registro: Registro) -> bool {
+        self.0 & registro.mask() != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterates over the records in the set
+    pub fn iter(&self) -> impl Iterator<Item = Registro> {
+        let alteracoes = *self;
+        Registro::TODOS
+            .into_iter()
+            .filter(move |registro| alteracoes.contem(*registro))
+    }
+}
+
+/// Identifies each one of the axis of the equipment
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Eixo {
+    X,
+    Y,
+}
+
+impl Eixo {
+    /// Options to select an axis in the menu (0 => X, 1 => Y)
+    pub fn from_cursor(cursor: Cursor) -> Self {
+        match cursor.get_current() {
+            0 => Eixo::X,
+            _ => Eixo::Y,
+        }
+    }
+}
+
+/// Scope of the restoration of the factory default values
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EscopoDaRestauracao {
+    /// Just one program of one axis
+    Programa,
+    /// All programs and the configuration of one axis
+    Eixo,
+    /// All programs, all axis configurations and the configuration of the equipment
+    Tudo,
+}
+
+impl EscopoDaRestauracao {
+    /// Options to select the scope in the menu (0 => Programa, 1 => Eixo, 2 => Tudo)
+    pub fn from_cursor(cursor: Cursor) -> Self {
+        match cursor.get_current() {
+            0 => EscopoDaRestauracao::Programa,
+            1 => EscopoDaRestauracao::Eixo,
+            _ => EscopoDaRestauracao::Tudo,
+        }
+    }
+}
+
+/// Access levels of the user interface (`NSENHA` in the original TTC3100), ordered from the most
+/// privileged to the most protected one.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub enum NivelDeAcesso {
+    Supervisor = 0,
+    Manutencao = 1,
+    Operador = 2,
+    Protegido = 3,
+}
+
+impl NivelDeAcesso {
+    /// Options to select the access level in the menu (0 => Supervisor, .., 3 => Protegido)
+    pub fn from_cursor(cursor: Cursor) -> Self {
+        match cursor.get_current() {
+            0 => NivelDeAcesso::Supervisor,
+            1 => NivelDeAcesso::Manutencao,
+            2 => NivelDeAcesso::Operador,
+            _ => NivelDeAcesso::Protegido,
+        }
+    }
+
+    pub fn to_cursor(&self) -> Cursor {
+        Cursor::new(0, 4, *self as u8)
+    }
+
+    /// True if a user in this level is allowed to change what requires the `requerido` level
+    pub fn permite(&self, requerido: NivelDeAcesso) -> bool {
+        *self <= requerido
+    }
+}
+
+/// Passwords of the access levels, typed by the user in the keypad.
+///
+/// NOTE: The level `Protegido` has no password because anyone can go to it.
+pub struct Senhas {
+    pub supervisor: Cell<u16>,
+    pub manutencao: Cell<u16>,
+    pub operador: Cell<u16>,
+}
+
+impl Senhas {
+    /// Password of the given level, `None` if the level is not protected by password
+    pub fn get(&self, nivel: NivelDeAcesso) -> Option<&Cell<u16>> {
+        match nivel {
+            NivelDeAcesso::Supervisor => Some(&self.supervisor),
+            NivelDeAcesso::Manutencao => Some(&self.manutencao),
+            NivelDeAcesso::Operador => Some(&self.operador),
+            NivelDeAcesso::Protegido => None,
+        }
+    }
+}
+
+impl Default for Senhas {
+    fn default() -> Self {
+        Self {
+            supervisor: Cell::new(3000),
+            manutencao: Cell::new(2000),
+            operador: Cell::new(1000),
+        }
+    }
+}
+
+impl EepromStorable for Senhas {
+    const SIGNATURE: u16 = 0x5E00;
+
+    fn save_into<W: EepromWriter>(&self, writer: W) -> W {
+        writer
+            .write_u16(Self::SIGNATURE)
+            .write_u16(self.supervisor.get())
+            .write_u16(self.manutencao.get())
+            .write_u16(self.operador.get())
+    }
+
+    fn load_from_eeprom(&self, initial_address: EepromAddress) -> (EepromAddress, u8) {
+        let next = initial_address;
+        let (signature, next) = next.read_u16();
+
+        let signature_is_valid = signature == Self::SIGNATURE;
+
+        if signature_is_valid {
+            let (value, next) = next.read_u16();
+            self.supervisor.set(value);
+            let (value, next) = next.read_u16();
+            self.manutencao.set(value);
+            let (value, next) = next.read_u16();
+            self.operador.set(value);
+
+            //
+            let size_of_bytes_loaded = (next.0 - initial_address.0) as u8;
             (next, size_of_bytes_loaded)
         } else {
             // EEPROM is not initialized yet
@@ -437,102 +736,661 @@ impl EepromStorable for ConfiguracaoDoEquipamento {
     }
 }
 
+/// Status of an axis read from its cmpp board. The board is polled by the main loop while some
+/// screen shows its status.
+#[derive(Default)]
+pub struct StatusDoEixo {
+    /// True while some screen shows the status, the board is not polled otherwise
+    pub monitorado: Cell<bool>,
+    /// Current position of the axis, `None` if the board did not answer the last poll
+    pub posicao_atual: Cell<Option<u16>>,
+}
+
 pub struct GuiState {
+    /// Program number selected for the execution of the axis X (`XPROG` in the original TTC3100)
     /// TODO: Should use u8 instead of u16 here.
     pub numero_do_programa_do_eixo_x: Cell<u16>,
+    /// Program number selected for the execution of the axis Y (`YPROG` in the original TTC3100)
+    pub numero_do_programa_do_eixo_y: Cell<u16>,
+    /// Axis which the `Arquivo de Eixo` is currently being edited by the user
+    pub eixo_em_edicao: Cell<Eixo>,
+    /// Axis which the `Configuracao do Eixo` is currently being edited by the user
+    pub eixo_em_configuracao: Cell<Eixo>,
+    // Parameters of the program copy (`COPIPRG` in the original TTC3100)
+    pub eixo_de_origem_da_copia: Cell<Cursor>, // 0 => X, 1 => Y
+    pub programa_de_origem_da_copia: Cell<u16>,
+    pub eixo_de_destino_da_copia: Cell<Cursor>, // 0 => X, 1 => Y
+    pub programa_de_destino_da_copia: Cell<u16>,
+    // Parameters of the restoration of factory default values
+    pub escopo_da_restauracao: Cell<Cursor>, // 0 => Programa, 1 => Eixo, 2 => Tudo
+    pub eixo_da_restauracao: Cell<Cursor>,   // 0 => X, 1 => Y
+    pub programa_da_restauracao: Cell<u16>,
+    /// Current access level of the user
+    pub nivel_de_acesso: Cell<NivelDeAcesso>,
+    // Parameters of the change of access level (`TROCNIV` in the original TTC3100)
+    pub nivel_de_acesso_selecionado: Cell<Cursor>, // See `NivelDeAcesso::from_cursor`
+    pub senha_digitada: Cell<u16>,
+    // Parameters of the change of password (`TROCSEN` in the original TTC3100)
+    pub nivel_da_troca_de_senha: Cell<Cursor>, // See `NivelDeAcesso::from_cursor`
+    pub nova_senha: Cell<u16>,
+}
+
+impl GuiState {
+    pub fn numero_do_programa(&self, eixo: Eixo) -> &Cell<u16> {
+        match eixo {
+            Eixo::X => &self.numero_do_programa_do_eixo_x,
+            Eixo::Y => &self.numero_do_programa_do_eixo_y,
+        }
+    }
 }
 
 impl Default for GuiState {
     fn default() -> Self {
         Self {
             numero_do_programa_do_eixo_x: Cell::new(0),
+            numero_do_programa_do_eixo_y: Cell::new(0),
+            eixo_em_edicao: Cell::new(Eixo::X),
+            eixo_em_configuracao: Cell::new(Eixo::X),
+            eixo_de_origem_da_copia: Cell::new(Cursor::new(0, 2, 0)),
+            programa_de_origem_da_copia: Cell::new(0),
+            eixo_de_destino_da_copia: Cell::new(Cursor::new(0, 2, 0)),
+            programa_de_destino_da_copia: Cell::new(1),
+            escopo_da_restauracao: Cell::new(Cursor::new(0, 3, 0)),
+            eixo_da_restauracao: Cell::new(Cursor::new(0, 2, 0)),
+            programa_da_restauracao: Cell::new(0),
+            nivel_de_acesso: Cell::new(NivelDeAcesso::Operador),
+            nivel_de_acesso_selecionado: Cell::new(NivelDeAcesso::Operador.to_cursor()),
+            senha_digitada: Cell::new(0),
+            nivel_da_troca_de_senha: Cell::new(NivelDeAcesso::Operador.to_cursor()),
+            nova_senha: Cell::new(0),
         }
     }
 }
 
 ///
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ModelError {
+    /// The program number is out of the range of programs that can be stored in the eeprom.
+    InvalidProgramNumber(u16),
+    /// The password typed by the user does not match the password of the access level.
+    InvalidPassword,
+    /// The current access level does not allow the operation.
+    AccessDenied,
+    /// The operation would replace the program loaded in ram, which has changes not saved yet.
+    UnsavedChanges,
+}
+
+// -----------------------------------------------------------
+//           EEPROM LAYOUT
+// -----------------------------------------------------------
+//
+//  0x000 ..= 0x07F  -> Configurations (equipamento, eixo x, eixo y) and passwords
+//  0x080 ..= 0x3FF  -> Program slots of axis X followed by the program slots of axis Y
+//                      (one `ArquivoDeEixo` per slot)
+
+/// Start address of the configurations area in eeprom
+const ENDERECO_DA_CONFIGURACAO_DO_EQUIPAMENTO: EepromAddress = EepromAddress(0x000);
+/// NOTE: `ConfiguracaoDoEquipamento` uses 5 bytes and `ConfiguracaoDoEixo` uses 34 bytes.
+const ENDERECO_DA_CONFIGURACAO_DO_EIXO_X: EepromAddress = EepromAddress(0x008);
+const ENDERECO_DA_CONFIGURACAO_DO_EIXO_Y: EepromAddress = EepromAddress(0x030);
+/// NOTE: `Senhas` uses 8 bytes.
+const ENDERECO_DAS_SENHAS: EepromAddress = EepromAddress(0x058);
+/// Start address of the first program slot in eeprom.
+const ENDERECO_INICIAL_DOS_PROGRAMAS: u16 = 0x080;
+/// Size in bytes reserved for each program slot.
+/// NOTE: `ArquivoDeEixo` uses 76 bytes, remaining bytes are reserved for future parameters.
+const TAMANHO_DO_SLOT_DE_PROGRAMA: u16 = 80;
+/// Number of axis which has its own programs stored in eeprom
+const NUMERO_DE_EIXOS: u16 = 2;
+
+/// Bytes written into eeprom in each step of the write-behind (see
+/// [`DataModel::gravar_proxima_alteracao`]). The eeprom writes each byte in background in ~3.4ms,
+/// so one byte per step does not block the main loop if the steps are at least this time apart.
+pub const BYTES_POR_GRAVACAO: u8 = 1;
+
+/// Maximum number of programs per axis that can be stored, it is bounded by the size of the eeprom.
+/// The original TTC3100 accepted up to 99 programs.
+pub const NUMERO_MAXIMO_DE_PROGRAMAS: u16 = {
+    const ORIGINAL_TTC3100: u16 = 99;
+    const CAPACIDADE: u16 = (EEPROM_SIZE - ENDERECO_INICIAL_DOS_PROGRAMAS)
+        / (TAMANHO_DO_SLOT_DE_PROGRAMA * NUMERO_DE_EIXOS);
+    if CAPACIDADE < ORIGINAL_TTC3100 {
+        CAPACIDADE
+    } else {
+        ORIGINAL_TTC3100
+    }
+};
+
+///
+
 pub struct DataModel {
-    pub arquivo_de_eixo_00: ArquivoDeEixo,
-    pub arquivo_de_eixo_01: ArquivoDeEixo,
-    //pub arquivo_de_eixo_y: ArquivoDeEixo,
+    /// `Arquivo de eixo` of each axis currently loaded in ram, they are lazy loaded from eeprom
+    /// when the user selects other program number.
+    arquivo_de_eixo_x: ArquivoDeEixo,
+    arquivo_de_eixo_y: ArquivoDeEixo,
+    /// Number of the program that is currently loaded in `arquivo_de_eixo_x` and `arquivo_de_eixo_y`
+    programa_carregado_no_eixo_x: Cell<u16>,
+    programa_carregado_no_eixo_y: Cell<u16>,
     pub configuracao_do_eixo_x: ConfiguracaoDoEixo,
     pub configuracao_do_eixo_y: ConfiguracaoDoEixo,
     pub configuracao_do_equipamento: ConfiguracaoDoEquipamento,
+    senhas: Senhas,
+    /// Records which may have been changed by the user in this session of the menu (the dirty set),
+    /// they are only written into eeprom when the user confirms it when leaving the menu.
+    alteracoes: Cell<Alteracoes>,
+    /// Records saved by the user which were not written into eeprom yet (see:
+    /// [`DataModel::salvar_alteracoes`]).
+    gravacoes_pendentes: Cell<Alteracoes>,
+    /// Bytes of the first record of `gravacoes_pendentes` already written into eeprom
+    bytes_gravados: Cell<u16>,
+    /// Status of the axis X read from its cmpp board
+    pub status_do_eixo_x: StatusDoEixo,
+    /// If true the data in ram was changed after the last time it was sent to the cmpp boards
+    envio_pendente: Cell<bool>,
     // parametros the GUI (graphical user interface)
     pub gui_state: GuiState,
 }
 
 impl DataModel {
-    const ADDR_LOW: u8 = 0x00;
-    const ADDR_HIGH: u8 = 0x01;
-
     pub fn new() -> Self {
         Self {
-            arquivo_de_eixo_00: ArquivoDeEixo::default(),
-            arquivo_de_eixo_01: ArquivoDeEixo::default(),
-            //arquivo_de_eixo_y: ArquivoDeEixo::default(),
+            arquivo_de_eixo_x: ArquivoDeEixo::default(),
+            arquivo_de_eixo_y: ArquivoDeEixo::default(),
+            programa_carregado_no_eixo_x: Cell::new(0),
+            programa_carregado_no_eixo_y: Cell::new(0),
             configuracao_do_eixo_x: ConfiguracaoDoEixo::default(),
             configuracao_do_eixo_y: ConfiguracaoDoEixo::default(),
             configuracao_do_equipamento: ConfiguracaoDoEquipamento::default(),
+            senhas: Senhas::default(),
+            alteracoes: Cell::new(Alteracoes::default()),
+            gravacoes_pendentes: Cell::new(Alteracoes::default()),
+            bytes_gravados: Cell::new(0),
+            status_do_eixo_x: StatusDoEixo::default(),
+            // the data was never sent to the cmpp boards
+            envio_pendente: Cell::new(true),
             gui_state: GuiState::default(),
         }
     }
 
-    /// Start address to store `Arquivo de Eixo` data in eeprom
-    fn get_initial_eeprom_address(&self) -> EepromAddress {
-        match self.numero_do_programa_atual() {
-            // TODO: Currently addresses is arbitrarily defined, change this for a more
-            // formal and efficient approuch;
-            0 => EepromAddress(0x00),
-            1 => EepromAddress(100),
-            // TODO: Make this error not being a Fatal Error but a recoverable error
-            _ => fatal_error!(0x7F), // Invalid number of arquivo_de_eixo
+    /// Start address to store the `Arquivo de Eixo` of the given axis and program number in eeprom
+    fn get_eeprom_address_of_programa(
+        eixo: Eixo,
+        numero_do_programa: u16,
+    ) -> Result<EepromAddress, ModelError> {
+        if numero_do_programa < NUMERO_MAXIMO_DE_PROGRAMAS {
+            let slot = match eixo {
+                Eixo::X => numero_do_programa,
+                Eixo::Y => NUMERO_MAXIMO_DE_PROGRAMAS + numero_do_programa,
+            };
+            let offset = slot * TAMANHO_DO_SLOT_DE_PROGRAMA;
+            Ok(EepromAddress(ENDERECO_INICIAL_DOS_PROGRAMAS + offset))
+        } else {
+            Err(ModelError::InvalidProgramNumber(numero_do_programa))
         }
     }
 
-    fn numero_do_programa_atual(&self) -> u16 {
-        self.gui_state.numero_do_programa_do_eixo_x.get()
+    fn programa_carregado(&self, eixo: Eixo) -> &Cell<u16> {
+        match eixo {
+            Eixo::X => &self.programa_carregado_no_eixo_x,
+            Eixo::Y => &self.programa_carregado_no_eixo_y,
+        }
+    }
+
+    /// Returns the `Arquivo de Eixo` currently loaded for the given axis
+    pub fn get_arquivo_de_eixo(&self, eixo: Eixo) -> &ArquivoDeEixo {
+        match eixo {
+            Eixo::X => &self.arquivo_de_eixo_x,
+            Eixo::Y => &self.arquivo_de_eixo_y,
+        }
     }
 
+    /// Returns the `Arquivo de Eixo` currently being edited by the user
     pub fn get_arquivo_de_eixo_by_ref(&self) -> &ArquivoDeEixo {
-        match self.numero_do_programa_atual() {
-            0 => &self.arquivo_de_eixo_00,
-            1 => &self.arquivo_de_eixo_01,
-            // TODO: Make this error not being a Fatal Error but a recoverable error
-            _ => fatal_error!(0x7F), // Invalid number of arquivo_de_eixo
+        self.get_arquivo_de_eixo(self.gui_state.eixo_em_edicao.get())
+    }
+
+    fn get_eeprom_address_of_configuracao_do_eixo(eixo: Eixo) -> EepromAddress {
+        match eixo {
+            Eixo::X => ENDERECO_DA_CONFIGURACAO_DO_EIXO_X,
+            Eixo::Y => ENDERECO_DA_CONFIGURACAO_DO_EIXO_Y,
+        }
+    }
+
+    pub fn get_configuracao_do_eixo(&self, eixo: Eixo) -> &ConfiguracaoDoEixo {
+        match eixo {
+            Eixo::X => &self.configuracao_do_eixo_x,
+            Eixo::Y => &self.configuracao_do_eixo_y,
+        }
+    }
+
+    /// Returns the `Configuracao do Eixo` currently being edited by the user
+    pub fn get_configuracao_do_eixo_by_ref(&self) -> &ConfiguracaoDoEixo {
+        self.get_configuracao_do_eixo(self.gui_state.eixo_em_configuracao.get())
+    }
+
+    /// If the program number selected by the user for the given axis is different from the one
+    /// loaded in ram, then loads the selected one.
+    ///
+    /// Nothing is written into eeprom: if the program loaded has changes not saved yet, they must be
+    /// saved or discarded when leaving the menu before, so the selection is reverted to the program
+    /// currently loaded and an error is returned. The same is done if the selected program number
+    /// is invalid.
+    pub fn carregar_programa_selecionado(&self, eixo: Eixo) -> Result<(), ModelError> {
+        let selecionado = self.gui_state.numero_do_programa(eixo).get();
+        let carregado = self.programa_carregado(eixo).get();
+        if selecionado == carregado {
+            return Ok(());
+        }
+        let result = Self::get_eeprom_address_of_programa(eixo, selecionado)
+            .and_then(|address| self.carregar_programa(eixo, selecionado, address));
+        if result.is_err() {
+            self.gui_state.numero_do_programa(eixo).set(carregado);
+        }
+        result
+    }
+
+    /// Replaces the program loaded in ram for the given axis, if it has no changes to be saved.
+    fn carregar_programa(
+        &self,
+        eixo: Eixo,
+        numero_do_programa: u16,
+        address: EepromAddress,
+    ) -> Result<(), ModelError> {
+        let registro = Registro::ArquivoDeEixo(eixo);
+        if self.get_alteracoes().contem(registro) {
+            return Err(ModelError::UnsavedChanges);
+        }
+        self.get_arquivo_de_eixo(eixo).load_from_eeprom(address);
+        self.programa_carregado(eixo).set(numero_do_programa);
+        self.excluir_alteracao(registro);
+        self.envio_pendente.set(true);
+        Ok(())
+    }
+
+    /// Loads the program selected for the given axis and makes it the `Arquivo de Eixo` being edited.
+    pub fn editar_programa_do_eixo(&self, eixo: Eixo) -> Result<(), ModelError> {
+        self.carregar_programa_selecionado(eixo)?;
+        self.gui_state.eixo_em_edicao.set(eixo);
+        Ok(())
+    }
+
+    /// Makes the `Configuracao do Eixo` of the given axis the one being edited
+    pub fn editar_configuracao_do_eixo(&self, eixo: Eixo) -> Result<(), ModelError> {
+        self.gui_state.eixo_em_configuracao.set(eixo);
+        Ok(())
+    }
+
+    /// Copies the program `origem` into the program `destino` (they may be of different axis), the
+    /// previous content of `destino` is overwritten.
+    ///
+    /// The copy is done in ram: `destino` becomes the program loaded (and selected) in its axis and
+    /// receives the content of `origem`, so it is saved or discarded with the other changes when
+    /// the user leaves the menu. It fails if `origem` or the program loaded in the axis of `destino`
+    /// has changes not saved yet.
+    ///
+    /// NOTE: An `origem` which was never initialized in eeprom is initialized with the factory
+    /// default values.
+    pub fn copiar_programa(
+        &self,
+        origem: (Eixo, u16),
+        destino: (Eixo, u16),
+    ) -> Result<(), ModelError> {
+        let (eixo_de_origem, programa_de_origem) = origem;
+        let (eixo_de_destino, programa_de_destino) = destino;
+        let endereco_de_origem =
+            Self::get_eeprom_address_of_programa(eixo_de_origem, programa_de_origem)?;
+        // validates the number of the destination program
+        Self::get_eeprom_address_of_programa(eixo_de_destino, programa_de_destino)?;
+        if origem == destino {
+            // Copy to itself, nothing to do.
+            return Ok(());
+        }
+        // the content of `origem` is read from eeprom, so its changes must be saved before
+        let alteracoes = self.get_alteracoes();
+        let origem_foi_alterada = self.programa_carregado(eixo_de_origem).get()
+            == programa_de_origem
+            && alteracoes.contem(Registro::ArquivoDeEixo(eixo_de_origem));
+        let registro = Registro::ArquivoDeEixo(eixo_de_destino);
+        if origem_foi_alterada || alteracoes.contem(registro) {
+            return Err(ModelError::UnsavedChanges);
+        }
+        self.get_arquivo_de_eixo(eixo_de_destino)
+            .load_from_eeprom(endereco_de_origem);
+        self.programa_carregado(eixo_de_destino)
+            .set(programa_de_destino);
+        self.gui_state
+            .numero_do_programa(eixo_de_destino)
+            .set(programa_de_destino);
+        self.registrar_alteracao(registro);
+        Ok(())
+    }
+
+    /// Copies the program using the parameters selected by the user in the menu
+    pub fn copiar_programa_selecionado(&self) -> Result<(), ModelError> {
+        let gui = &self.gui_state;
+        let origem = (
+            Eixo::from_cursor(gui.eixo_de_origem_da_copia.get()),
+            gui.programa_de_origem_da_copia.get(),
+        );
+        let destino = (
+            Eixo::from_cursor(gui.eixo_de_destino_da_copia.get()),
+            gui.programa_de_destino_da_copia.get(),
+        );
+        self.copiar_programa(origem, destino)
+    }
+
+    /// Restores the factory default values of one program. If it is the program loaded in its axis,
+    /// the changes made by the user and not saved yet are discarded.
+    fn restaurar_programa(&self, eixo: Eixo, numero_do_programa: u16) -> Result<(), ModelError> {
+        let address = Self::get_eeprom_address_of_programa(eixo, numero_do_programa)?;
+        ArquivoDeEixo::default().save_into_eeprom(address);
+        if self.programa_carregado(eixo).get() == numero_do_programa {
+            self.get_arquivo_de_eixo(eixo).load_from_eeprom(address);
+            self.envio_pendente.set(true);
         }
+        Ok(())
     }
 
-    pub fn get_arquivo_de_eixo_by_ref_mut(&mut self) -> &mut ArquivoDeEixo {
-        let numero_do_programa = self.gui_state.numero_do_programa_do_eixo_x.get();
-        match numero_do_programa {
-            0 => &mut self.arquivo_de_eixo_00,
-            1 => &mut self.arquivo_de_eixo_01,
-            // TODO: Make this error not being a Fatal Error but a recoverable error
-            _ => fatal_error!(0x7F), // Invalid number of arquivo_de_eixo
+    /// Restores the factory default values of all programs and of the configuration of one axis.
+    fn restaurar_eixo(&self, eixo: Eixo) {
+        for numero_do_programa in 0..NUMERO_MAXIMO_DE_PROGRAMAS {
+            // NOTE: All program numbers in this range are valid
+            let _ = self.restaurar_programa(eixo, numero_do_programa);
         }
+        let address = Self::get_eeprom_address_of_configuracao_do_eixo(eixo);
+        ConfiguracaoDoEixo::default().save_into_eeprom(address);
+        self.get_configuracao_do_eixo(eixo)
+            .load_from_eeprom(address);
+        self.envio_pendente.set(true);
+    }
+
+    /// Restores the factory default values (`Default::default()`) in the given scope, for the
+    /// `Tudo` scope the `eixo` and `numero_do_programa` arguments are ignored.
+    pub fn restaurar_padrao_de_fabrica(
+        &self,
+        escopo: EscopoDaRestauracao,
+        eixo: Eixo,
+        numero_do_programa: u16,
+    ) -> Result<(), ModelError> {
+        match escopo {
+            EscopoDaRestauracao::Programa => self.restaurar_programa(eixo, numero_do_programa),
+            EscopoDaRestauracao::Eixo => {
+                self.restaurar_eixo(eixo);
+                Ok(())
+            }
+            EscopoDaRestauracao::Tudo => {
+                self.restaurar_eixo(Eixo::X);
+                self.restaurar_eixo(Eixo::Y);
+                let address = ENDERECO_DA_CONFIGURACAO_DO_EQUIPAMENTO;
+                ConfiguracaoDoEquipamento::default().save_into_eeprom(address);
+                self.configuracao_do_equipamento.load_from_eeprom(address);
+                self.envio_pendente.set(true);
+                Ok(())
+            }
+        }
+    }
+
+    /// Restores the factory default values using the parameters selected by the user in the menu
+    pub fn restaurar_padrao_selecionado(&self) -> Result<(), ModelError> {
+        let gui = &self.gui_state;
+        let escopo = EscopoDaRestauracao::from_cursor(gui.escopo_da_restauracao.get());
+        let eixo = Eixo::from_cursor(gui.eixo_da_restauracao.get());
+        let numero_do_programa = gui.programa_da_restauracao.get();
+        self.restaurar_padrao_de_fabrica(escopo, eixo, numero_do_programa)
     }
 
-    /// Saves data to EEPROM
-    pub fn save_to_eeprom(&self) {
-        let initial_address = self.get_initial_eeprom_address();
-        let (next, _size) = self
-            .get_arquivo_de_eixo_by_ref()
-            .save_into_eeprom(initial_address);
+    /// True if the current access level allows to change what requires the `requerido` level
+    pub fn tem_acesso(&self, requerido: NivelDeAcesso) -> bool {
+        self.gui_state.nivel_de_acesso.get().permite(requerido)
+    }
 
-        let (next, _size) = self.configuracao_do_eixo_x.save_into_eeprom(next);
-        let (_next, _size) = self.configuracao_do_equipamento.save_into_eeprom(next);
+    /// Changes the current access level to the `novo` level. Going to a more protected level is
+    /// always allowed, going to a more privileged one requires the password of the `novo` level.
+    pub fn trocar_nivel_de_acesso(
+        &self,
+        novo: NivelDeAcesso,
+        senha: u16,
+    ) -> Result<(), ModelError> {
+        let atual = self.gui_state.nivel_de_acesso.get();
+        let senha_confere = match self.senhas.get(novo) {
+            Some(senha_do_nivel) => senha_do_nivel.get() == senha,
+            None => true,
+        };
+        if atual.permite(novo) || senha_confere {
+            self.gui_state.nivel_de_acesso.set(novo);
+            Ok(())
+        } else {
+            Err(ModelError::InvalidPassword)
+        }
+    }
+
+    /// Changes the access level using the parameters selected by the user in the menu. The typed
+    /// password is always cleared and, on failure, the selection is reverted to the current level.
+    pub fn trocar_nivel_de_acesso_selecionado(&self) -> Result<(), ModelError> {
+        let gui = &self.gui_state;
+        let novo = NivelDeAcesso::from_cursor(gui.nivel_de_acesso_selecionado.get());
+        let result = self.trocar_nivel_de_acesso(novo, gui.senha_digitada.get());
+        gui.senha_digitada.set(0);
+        gui.nivel_de_acesso_selecionado
+            .set(gui.nivel_de_acesso.get().to_cursor());
+        result
+    }
+
+    /// Changes the password of the given level, it is only allowed for the users which have access
+    /// to this level.
+    pub fn trocar_senha(&self, nivel: NivelDeAcesso, nova_senha: u16) -> Result<(), ModelError> {
+        match self.senhas.get(nivel) {
+            Some(senha) if self.tem_acesso(nivel) => {
+                senha.set(nova_senha);
+                self.senhas.save_into_eeprom(ENDERECO_DAS_SENHAS);
+                Ok(())
+            }
+            _ => Err(ModelError::AccessDenied),
+        }
+    }
+
+    /// Changes the password using the parameters selected by the user in the menu. The typed
+    /// password is always cleared.
+    pub fn trocar_senha_selecionada(&self) -> Result<(), ModelError> {
+        let gui = &self.gui_state;
+        let nivel = NivelDeAcesso::from_cursor(gui.nivel_da_troca_de_senha.get());
+        let result = self.trocar_senha(nivel, gui.nova_senha.get());
+        gui.nova_senha.set(0);
+        result
+    }
+
+    /// Returns to the `Operador` level if the user is in a more privileged level. Used when the
+    /// keypad is not used for some time.
+    pub fn retornar_ao_nivel_de_operador(&self) {
+        let gui = &self.gui_state;
+        if gui.nivel_de_acesso.get() < NivelDeAcesso::Operador {
+            gui.nivel_de_acesso.set(NivelDeAcesso::Operador);
+            gui.nivel_de_acesso_selecionado
+                .set(NivelDeAcesso::Operador.to_cursor());
+        }
+    }
+
+    /// Verifies the rules of the programs loaded in both axis, returns the first rule violated if any.
+    pub fn validar_programas_carregados(&self) -> Result<(), (Eixo, RegraDeValidacao)> {
+        for eixo in [Eixo::X, Eixo::Y] {
+            self.get_arquivo_de_eixo(eixo)
+                .validar()
+                .map_err(|regra| (eixo, regra))?;
+        }
+        Ok(())
+    }
+
+    /// Loads the program selected for the given axis, at startup.
+    fn load_programa_selecionado(&self, eixo: Eixo) {
+        let selecionado = self.gui_state.numero_do_programa(eixo).get();
+        let selecionado = match Self::get_eeprom_address_of_programa(eixo, selecionado) {
+            Ok(_) => selecionado,
+            Err(_) => 0,
+        };
+        if let Ok(address) = Self::get_eeprom_address_of_programa(eixo, selecionado) {
+            self.get_arquivo_de_eixo(eixo).load_from_eeprom(address);
+        }
+        self.programa_carregado(eixo).set(selecionado);
+        self.gui_state.numero_do_programa(eixo).set(selecionado);
+    }
+
+    /// Address in eeprom where the given record is stored
+    fn get_eeprom_address_of_registro(&self, registro: Registro) -> EepromAddress {
+        match registro {
+            Registro::ArquivoDeEixo(eixo) => {
+                // NOTE: The loaded program number is always valid, because it was validated before the load
+                match Self::get_eeprom_address_of_programa(
+                    eixo,
+                    self.programa_carregado(eixo).get(),
+                ) {
+                    Ok(address) => address,
+                    Err(_) => fatal_error!(110),
+                }
+            }
+            Registro::ConfiguracaoDoEixo(eixo) => {
+                Self::get_eeprom_address_of_configuracao_do_eixo(eixo)
+            }
+            Registro::ConfiguracaoDoEquipamento => ENDERECO_DA_CONFIGURACAO_DO_EQUIPAMENTO,
+        }
+    }
+
+    /// True if the given record in ram is different from the one saved in eeprom
+    fn foi_alterado(&self, registro: Registro) -> bool {
+        let address = self.get_eeprom_address_of_registro(registro);
+        match registro {
+            Registro::ArquivoDeEixo(eixo) => {
+                self.get_arquivo_de_eixo(eixo).differs_from_eeprom(address)
+            }
+            Registro::ConfiguracaoDoEixo(eixo) => self
+                .get_configuracao_do_eixo(eixo)
+                .differs_from_eeprom(address),
+            Registro::ConfiguracaoDoEquipamento => self
+                .configuracao_do_equipamento
+                .differs_from_eeprom(address),
+        }
+    }
+
+    /// Includes the given record in the dirty set, informing that the user may have changed it in ram.
+    pub fn registrar_alteracao(&self, registro: Registro) {
+        let mut alteracoes = self.alteracoes.get();
+        alteracoes.incluir(registro);
+        self.alteracoes.set(alteracoes);
+    }
+
+    fn excluir_alteracao(&self, registro: Registro) {
+        let mut alteracoes = self.alteracoes.get();
+        alteracoes.excluir(registro);
+        self.alteracoes.set(alteracoes);
+    }
+
+    /// Records of the dirty set which are really different in ram from the ones saved in eeprom,
+    /// (ie: a value edited back to the saved one is not a change).
+    ///
+    /// A record saved and still being written into eeprom is not compared with it, the saved
+    /// values are the ones in ram (see [`DataModel::salvar_alteracoes`]), so it is not a change.
+    pub fn get_alteracoes(&self) -> Alteracoes {
+        let pendentes = self.gravacoes_pendentes.get();
+        let mut alteracoes = Alteracoes::default();
+        for registro in self.alteracoes.get().iter() {
+            if !pendentes.contem(registro) && self.foi_alterado(registro) {
+                alteracoes.incluir(registro);
+            }
+        }
+        alteracoes
+    }
+
+    /// Saves the records changed in ram, they must be sent to the cmpp boards after it. It is the
+    /// only place where the changes of the user are saved.
+    ///
+    /// The records are not written into eeprom here, because it is slow, they are written a few
+    /// bytes at a time later (write-behind) through [`DataModel::gravar_proxima_alteracao`]. So
+    /// the records must not be changed in ram before [`DataModel::gravar_alteracoes_pendentes`]
+    /// (ie: when the menu is opened again, which is rarely before the writing ends).
+    pub fn salvar_alteracoes(&self) {
+        let mut pendentes = self.gravacoes_pendentes.get();
+        for registro in self.get_alteracoes().iter() {
+            pendentes.incluir(registro);
+            self.envio_pendente.set(true);
+        }
+        self.gravacoes_pendentes.set(pendentes);
+        self.alteracoes.set(Alteracoes::default());
+    }
+
+    /// Writes into eeprom the next bytes of the records saved and not written yet, at most
+    /// [`BYTES_POR_GRAVACAO`] bytes are really written in each call. Returns `false` if there was
+    /// nothing to write.
+    pub fn gravar_proxima_alteracao(&self) -> bool {
+        let mut pendentes = self.gravacoes_pendentes.get();
+        let Some(registro) = pendentes.iter().next() else {
+            return false;
+        };
+        let address = self.get_eeprom_address_of_registro(registro);
+        let budget = Cell::new(BYTES_POR_GRAVACAO);
+        let writer = IncrementalWriter::new(address, &self.bytes_gravados, &budget);
+        let writer = match registro {
+            Registro::ArquivoDeEixo(eixo) => self.get_arquivo_de_eixo(eixo).save_into(writer),
+            Registro::ConfiguracaoDoEixo(eixo) => {
+                self.get_configuracao_do_eixo(eixo).save_into(writer)
+            }
+            Registro::ConfiguracaoDoEquipamento => {
+                self.configuracao_do_equipamento.save_into(writer)
+            }
+        };
+        if writer.is_finished() {
+            pendentes.excluir(registro);
+            self.gravacoes_pendentes.set(pendentes);
+            self.bytes_gravados.set(0);
+        }
+        true
+    }
+
+    /// Writes into eeprom all the records saved and not written yet
+    pub fn gravar_alteracoes_pendentes(&self) {
+        while self.gravar_proxima_alteracao() {}
+    }
+
+    /// Discards the records changed in ram, reloading the values saved in eeprom
+    pub fn descartar_alteracoes(&self) {
+        for registro in self.get_alteracoes().iter() {
+            let address = self.get_eeprom_address_of_registro(registro);
+            match registro {
+                Registro::ArquivoDeEixo(eixo) => {
+                    self.get_arquivo_de_eixo(eixo).load_from_eeprom(address);
+                }
+                Registro::ConfiguracaoDoEixo(eixo) => {
+                    self.get_configuracao_do_eixo(eixo)
+                        .load_from_eeprom(address);
+                }
+                Registro::ConfiguracaoDoEquipamento => {
+                    self.configuracao_do_equipamento.load_from_eeprom(address);
+                }
+            }
+        }
+        self.alteracoes.set(Alteracoes::default());
+    }
+
+    /// True if the data must be sent to the cmpp boards, because it changed after the last send
+    pub fn tem_envio_pendente(&self) -> bool {
+        self.envio_pendente.get()
+    }
+
+    /// Informs that the data in ram was sent to the cmpp boards
+    pub fn concluir_envio(&self) {
+        self.envio_pendente.set(false);
     }
 
     /// loads data from EEPROM
     pub fn load_from_eeprom(&mut self) {
-        let initial_address = self.get_initial_eeprom_address();
-        let (next, _address) = self
-            .get_arquivo_de_eixo_by_ref_mut()
-            .load_from_eeprom(initial_address);
-        let (next, _size) = self.configuracao_do_eixo_x.load_from_eeprom(next);
-        let (_next, _size) = self.configuracao_do_equipamento.load_from_eeprom(next);
+        self.configuracao_do_equipamento
+            .load_from_eeprom(ENDERECO_DA_CONFIGURACAO_DO_EQUIPAMENTO);
+        self.configuracao_do_eixo_x
+            .load_from_eeprom(ENDERECO_DA_CONFIGURACAO_DO_EIXO_X);
+        self.configuracao_do_eixo_y
+            .load_from_eeprom(ENDERECO_DA_CONFIGURACAO_DO_EIXO_Y);
+        self.senhas.load_from_eeprom(ENDERECO_DAS_SENHAS);
+        self.load_programa_selecionado(Eixo::X);
+        self.load_programa_selecionado(Eixo::Y);
     }
 }
 
@@ -543,189 +1401,100 @@ pub struct CmppData<'a> {
     pub configuracao_de_eixo: &'a ConfiguracaoDoEixo,
 }
 
-/// TODO: Eventually this function should be place in a better location instead of in this module.
-pub fn send_all(transport: &TransportLayer, data: &CmppData) {
-    let CmppData {
-        arquivo_de_eixo,
-        configuracao_de_eixo,
-    } = data;
-
-    transport
-        .posicao_inicial()
-        .set(arquivo_de_eixo.posicao_inicial.get().into());
-
-    transport
-        .posicao_final()
-        .set(arquivo_de_eixo.posicao_final.get().into());
-
-    transport
-        .aceleracao_de_avanco()
-        .set(arquivo_de_eixo.aceleracao_de_avanco.get().into());
-
-    transport
-        .aceleracao_de_retorno()
-        .set(arquivo_de_eixo.aceleracao_de_retorno.get().into());
-
-    transport
-        .velocidade_de_avanco()
-        .set(arquivo_de_eixo.velocidade_de_avanco.get().into());
-
-    transport
-        .velocidade_de_retorno()
-        .set(arquivo_de_eixo.velocidade_de_retorno.get().into());
-
-    transport
-        .numero_de_mensagem_no_avanco()
-        .set(arquivo_de_eixo.numero_de_mensagem_no_avanco.get().into());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Model loaded from a new eeprom and with the values of the program of the axis X changed
+    fn make_model_with_program_changed() -> DataModel {
+        let mut model = DataModel::new();
+        model.load_from_eeprom();
+        model.get_arquivo_de_eixo(Eixo::X).posicao_inicial.set(123);
+        model.registrar_alteracao(Registro::ArquivoDeEixo(Eixo::X));
+        model
+    }
 
-    transport
-        .numero_de_mensagem_no_retorno()
-        .set(arquivo_de_eixo.numero_de_mensagem_no_retorno.get().into());
+    #[test]
+    fn it_compares_with_the_eeprom_without_writing_it() {
+        let address = ENDERECO_DA_CONFIGURACAO_DO_EQUIPAMENTO;
+        assert!(ConfiguracaoDoEquipamento::default().differs_from_eeprom(address));
+        // the eeprom is still erased
+        assert_eq!(address.read_u16().0, 0xFFFF);
+    }
 
-    transport
-        .primeira_mensagem_no_avanco()
-        .set(arquivo_de_eixo.primeira_mensagem_no_avanco.get().into());
+    #[test]
+    fn it_keeps_the_changed_program_until_it_is_saved_or_discarded() {
+        let model = make_model_with_program_changed();
+        model.gui_state.numero_do_programa(Eixo::X).set(1);
+        assert_eq!(
+            model.carregar_programa_selecionado(Eixo::X),
+            Err(ModelError::UnsavedChanges)
+        );
+        assert_eq!(model.gui_state.numero_do_programa(Eixo::X).get(), 0);
+        // the answer `Nao` of the exit confirmation
+        model.descartar_alteracoes();
+        assert_eq!(model.get_arquivo_de_eixo(Eixo::X).posicao_inicial.get(), 50);
+        assert!(model.get_alteracoes().is_empty());
+        // now the other program can be loaded
+        model.gui_state.numero_do_programa(Eixo::X).set(1);
+        assert_eq!(model.carregar_programa_selecionado(Eixo::X), Ok(()));
+    }
 
-    transport
-        .ultima_mensagem_no_avanco()
-        .set(arquivo_de_eixo.ultima_mensagem_no_avanco.get().into());
+    #[test]
+    fn it_does_not_wait_the_eeprom_to_compare_the_saved_changes() {
+        let model = make_model_with_program_changed();
+        model.salvar_alteracoes();
+        // the saved program is not written yet, but it is not a change anymore
+        assert!(model.get_alteracoes().is_empty());
+        model.descartar_alteracoes();
+        assert_eq!(
+            model.get_arquivo_de_eixo(Eixo::X).posicao_inicial.get(),
+            123
+        );
+        let address = DataModel::get_eeprom_address_of_programa(Eixo::X, 0).unwrap();
+        assert!(model
+            .get_arquivo_de_eixo(Eixo::X)
+            .differs_from_eeprom(address));
+        model.gravar_alteracoes_pendentes();
+        assert!(!model
+            .get_arquivo_de_eixo(Eixo::X)
+            .differs_from_eeprom(address));
+    }
 
-    transport
-        .primeira_mensagem_no_retorno()
-        .set(arquivo_de_eixo.primeira_mensagem_no_retorno.get().into());
-
-    transport
-        .ultima_mensagem_no_retorno()
-        .set(arquivo_de_eixo.ultima_mensagem_no_retorno.get().into());
-
-    transport
-        .logica_do_sinal_de_impressao()
-        .set(arquivo_de_eixo.logica_do_sinal_de_impressao.get().into());
-
-    transport
-        .largura_do_sinal_de_impressao()
-        .set(arquivo_de_eixo.largura_do_sinal_de_impressao.get().into());
-
-    transport
-        .reversao_de_mensagem_via_serial()
-        .set(arquivo_de_eixo.reversao_de_mensagem_via_serial.get().into());
-
-    transport
-        .selecao_de_mensagem_via_serial()
-        .set(arquivo_de_eixo.selecao_de_mensagem_via_serial.get().into());
-
-    transport
-        .retardo_no_start_automatico()
-        .set(arquivo_de_eixo.retardo_no_start_automatico.get().into());
-
-    transport
-        .retardo_no_start_externo()
-        .set(arquivo_de_eixo.retardo_no_start_externo.get().into());
-
-    transport
-        .start_automatico_no_avanco()
-        .set(arquivo_de_eixo.start_automatico_no_avanco.get().into());
-
-    transport
-        .start_automatico_no_retorno()
-        .set(arquivo_de_eixo.start_automatico_no_retorno.get().into());
-
-    transport
-        .modo_de_trabalho_do_eixo()
-        .set(arquivo_de_eixo.modo_de_trabalho_do_eixo.get().into());
-
-    transport
-        .antecipacao_da_saida_de_start()
-        .set(arquivo_de_eixo.antecipacao_da_saida_de_start.get().into());
-
-    transport
-        .saida_de_start_no_avaco()
-        .set(arquivo_de_eixo.saida_de_start_no_avaco.get().into());
-
-    transport
-        .saida_de_start_no_retorno()
-        .set(arquivo_de_eixo.saida_de_start_no_retorno.get().into());
-
-    transport
-        .entrada_de_start_entre_eixos()
-        .set(arquivo_de_eixo.entrada_de_start_entre_eixos.get().into());
-
-    transport
-        .retardo_do_start_entre_eixos()
-        .set(arquivo_de_eixo.retardo_do_start_entre_eixos.get().into());
-
-    transport
-        .start_pelo_teclado_e_externo()
-        .set(arquivo_de_eixo.start_pelo_teclado_e_externo.get().into());
-
-    transport
-        .retardo_no_sinal_de_impressao()
-        .set(arquivo_de_eixo.retardo_no_sinal_de_impressao.get().into());
-
-    transport
-        .retardo_no_start_passo_a_passo()
-        .set(arquivo_de_eixo.retardo_no_start_passo_a_passo.get().into());
-
-    transport
-        .start_automatico_passo_a_passo()
-        .set(arquivo_de_eixo.start_automatico_passo_a_passo.get().into());
-
-    transport
-        .saida_de_start_passo_a_passo()
-        .set(arquivo_de_eixo.saida_de_start_passo_a_passo.get().into());
-
-    transport
-        .janela_de_protecao_do_giro()
-        .set(configuracao_de_eixo.janela_de_protecao_do_giro.get().into());
-
-    transport
-        .deslocamento_giro_do_motor()
-        .set(configuracao_de_eixo.deslocamento_giro_do_motor.get().into());
-
-    transport.giro_com_funcao_de_protecao().set(
-        configuracao_de_eixo
-            .giro_com_funcao_de_protecao
-            .get()
-            .into(),
-    );
-
-    transport.giro_com_funcao_de_correcao().set(
-        configuracao_de_eixo
-            .giro_com_funcao_de_correcao
-            .get()
-            .into(),
-    );
-
-    transport
-        .logica_do_start_externo()
-        .set(configuracao_de_eixo.logica_do_start_externo.get().into());
-
-    transport.valor_da_posicao_de_referencia().set(
-        configuracao_de_eixo
-            .valor_da_posicao_de_referencia
-            .get()
-            .into(),
-    );
-
-    transport
-        .velocidade_para_referencia()
-        .set(configuracao_de_eixo.velocidade_para_referencia.get().into());
-
-    transport
-        .aceleracao_para_referencia()
-        .set(configuracao_de_eixo.aceleracao_para_referencia.get().into());
-
-    transport.reducao_da_corrente_em_repouso().set(
-        configuracao_de_eixo
-            .reducao_da_corrente_em_repouso
-            .get()
-            .into(),
-    );
-
-    transport.referencia_pelo_start_externo().set(
-        configuracao_de_eixo
-            .referencia_pelo_start_externo
-            .get()
-            .into(),
-    );
+    #[test]
+    fn it_copies_the_program_in_ram_until_the_changes_are_saved() {
+        let model = make_model_with_program_changed();
+        // the changed program must be saved before being copied
+        assert_eq!(
+            model.copiar_programa((Eixo::X, 0), (Eixo::Y, 2)),
+            Err(ModelError::UnsavedChanges)
+        );
+        model.salvar_alteracoes();
+        // the program is copied from the eeprom, it is up to date when the menu is opened again
+        model.gravar_alteracoes_pendentes();
+        assert_eq!(model.copiar_programa((Eixo::X, 0), (Eixo::Y, 2)), Ok(()));
+        assert_eq!(model.gui_state.numero_do_programa(Eixo::Y).get(), 2);
+        assert_eq!(
+            model.get_arquivo_de_eixo(Eixo::Y).posicao_inicial.get(),
+            123
+        );
+        let address = DataModel::get_eeprom_address_of_programa(Eixo::Y, 2).unwrap();
+        assert_eq!(address.read_u16().0, 0xFFFF);
+        // the answer `Sim` of the exit confirmation
+        assert!(model
+            .get_alteracoes()
+            .contem(Registro::ArquivoDeEixo(Eixo::Y)));
+        model.salvar_alteracoes();
+        // the eeprom is written later, a few bytes at a time
+        assert_eq!(address.read_u16().0, 0xFFFF);
+        let mut passos = 0;
+        while model.gravar_proxima_alteracao() {
+            passos += 1;
+        }
+        // the whole program is different from the erased eeprom
+        assert_eq!(passos, 76 / BYTES_POR_GRAVACAO as usize);
+        let saved = ArquivoDeEixo::default();
+        saved.load_from_eeprom(address);
+        assert_eq!(saved.posicao_inicial.get(), 123);
+    }
 }
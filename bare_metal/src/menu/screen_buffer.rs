@@ -1,88 +1,193 @@
 use cross_platform::utils::numerical::convert_u16_to_string_decimal;
 
-use crate::board::lcd::interface::Lcd;
+use crate::board::lcd::{self, glyph::upload_glyphs, interface::Lcd};
 use crate::geometry::point::Point;
+use crate::geometry::rectangle::Rectangle;
 use crate::printable::Printable;
 
-///TODO: Reimplement it using [`Cursor`]'s type
-///TODO: Test this type because maybe something in it is not working
-struct CursorPosition {
-    point: Point,
+/// Number of chars of the LCD display
+const NUMBER_OF_CHARS: usize = lcd::NUMBER_OF_COLS as usize * lcd::NUMBER_OF_LINES as usize;
+
+/// A memory representation of the LCD display.
+///
+/// Its function is to make possible do cache displayed information reducing
+/// screen flickering. You decide how many frames per second you want to send this to screen through the method [`Self::render`]
+///
+/// The widgets do not write directly in it but in a [`ScreenRegion`], see [`Self::full_region`].
+pub struct ScreenBuffer<'a> {
+    /// desired state of the lcd, written by the widgets
+    screen_buffer_input: [u8; NUMBER_OF_CHARS],
+    /// last state sent to the lcd
+    screen_buffer_output: [u8; NUMBER_OF_CHARS],
+    /// if true the next render sends all the chars, whatever the state of the lcd
+    must_refresh: bool,
+    lcd: &'a dyn Lcd,
 }
 
-impl CursorPosition {
-    fn new(point: Point) -> Self {
-        Self { point }
+impl<'a> ScreenBuffer<'a> {
+    /// Number of columns of the LCD display
+    pub const WIDTH: u8 = lcd::NUMBER_OF_COLS;
+    /// Number of lines of the LCD display
+    pub const HEIGHT: u8 = lcd::NUMBER_OF_LINES;
+
+    /// The glyphs are stored in the lcd, so they can be printed as any other char
+    pub fn new(lcd: &'a dyn Lcd) -> Self {
+        upload_glyphs(lcd);
+        Self {
+            screen_buffer_input: [' ' as u8; NUMBER_OF_CHARS],
+            screen_buffer_output: [' ' as u8; NUMBER_OF_CHARS],
+            must_refresh: true, // the content and the cursor of the lcd are unknown
+            lcd,
+        }
+    }
+
+    /// The whole display
+    pub fn get_area() -> Rectangle {
+        Rectangle::from_size(Point::new(0, 0), Self::WIDTH, Self::HEIGHT)
+    }
+
+    /// Region which covers the whole display
+    pub fn full_region(&mut self) -> ScreenRegion<'_, 'a> {
+        ScreenRegion::new(self, Self::get_area())
     }
 
-    fn from_index(index: u8) -> Self {
-        let col: u8 = index % 40;
-        let row: u8 = index / 40;
-        let point = Point::new(col, row);
-        Self { point }
+    /// Region which covers just the given area of the display (in absolute coordinates)
+    pub fn region(&mut self, area: Rectangle) -> ScreenRegion<'_, 'a> {
+        let area = Self::get_area().intersection(&area);
+        ScreenRegion::new(self, area)
     }
 
-    fn get_index(&self) -> u8 {
-        let Point { x: col, y: row } = self.point;
-        let index = col + (40 * row);
-        index
+    fn get_index(point: Point) -> usize {
+        let Point { x: col, y: row } = point;
+        col as usize + (Self::WIDTH as usize * row as usize)
     }
 
-    /// Set cursor in the specified point position
-    fn set(&mut self, point: Point) -> &mut Self {
-        self.point = point;
-        self
+    /// Writes one char in the given absolute position, if the position is outside the display
+    /// nothing is written.
+    fn write(&mut self, point: Point, byte: u8) {
+        if Self::get_area().contains(point) {
+            self.screen_buffer_input[Self::get_index(point)] = byte;
+        }
     }
 
-    fn increment(&mut self) -> &mut Self {
-        let mut index = self.get_index();
-        index += 1;
-        let new_point = Self::from_index(index.clamp(0, 79)).point;
-        self.point = new_point;
-        self
+    fn read(&self, point: Point) -> Option<u8> {
+        if Self::get_area().contains(point) {
+            Some(self.screen_buffer_input[Self::get_index(point)])
+        } else {
+            None
+        }
+    }
+
+    // output part
+
+    /// Makes the next [`Self::render`] send the whole screen, ie: if the lcd content may have been
+    /// changed by other means than this buffer.
+    pub fn force_full_refresh(&mut self) {
+        self.must_refresh = true;
+    }
+
+    /// The purpose of this routine is to avoid unecessary writings to LCD.
+    /// It compares two lcd buffers: The output_buffer represents current state of lcd and
+    /// input_buffer represent the desired state of lcd, and sends only the runs of chars which
+    /// changed. The cursor is moved only at the start of each run, because the lcd increments it
+    /// after each char written.
+    ///
+    /// Returns the number of bytes (chars and cursor commands) written to the lcd, for profiling.
+    pub fn render(&mut self) -> u16 {
+        let lcd_driver = self.lcd;
+        let mut bytes_written = 0;
+        for row in 0..Self::HEIGHT {
+            // column where the lcd cursor is, it is unknown until the first cursor move in the line
+            // NOTE: the cursor does not go from the end of a line to the start of the next one
+            let mut lcd_cursor = None;
+            for col in 0..Self::WIDTH {
+                let point = Point::new(col, row);
+                let index = Self::get_index(point);
+                let byte = self.screen_buffer_input[index];
+                if !self.must_refresh && byte == self.screen_buffer_output[index] {
+                    continue; // unchanged char
+                }
+                if lcd_cursor != Some(col) {
+                    lcd_driver.set_cursor(point);
+                    bytes_written += 1;
+                }
+                lcd_driver.print_u8(byte);
+                bytes_written += 1;
+                self.screen_buffer_output[index] = byte;
+                lcd_cursor = Some(col + 1);
+            }
+        }
+        self.must_refresh = false;
+        bytes_written
     }
 }
 
-/// A memory representation of the LCD display.
+/// A clipped rectangular view of the [`ScreenBuffer`] where a widget draws itself.
 ///
-/// Its function is to make possible do cache displayed information reducing
-/// screen flickering. You decide how many frames per second you want to send this to screen through the method [`Self::render`]
-pub struct ScreenBuffer<'a> {
-    cursor_position: CursorPosition, // for screen_buffer_input
-    screen_buffer_input: [u8; 80],
-    lcd: &'a dyn Lcd,
+/// The coordinates are relative to the upper-left corner of the region and anything printed
+/// outside of it is discarded, so a widget cannot overwrite its neighbours. The owner of the
+/// widget is responsible to define where the region is placed on the screen, ie: splitting its
+/// own region through [`Self::sub_region`].
+pub struct ScreenRegion<'s, 'a> {
+    screen_buffer: &'s mut ScreenBuffer<'a>,
+    /// absolute coordinates of the region in the screen buffer
+    area: Rectangle,
+    /// relative to the upper-left corner of the region
+    cursor: Point,
 }
 
-impl<'a> ScreenBuffer<'a> {
-    pub fn new(lcd: &'a dyn Lcd) -> Self {
+impl<'s, 'a> ScreenRegion<'s, 'a> {
+    fn new(screen_buffer: &'s mut ScreenBuffer<'a>, area: Rectangle) -> Self {
         Self {
-            cursor_position: CursorPosition::new(Point::new(0, 0)),
-            screen_buffer_input: [' ' as u8; 80],
-            lcd,
+            screen_buffer,
+            area,
+            cursor: Point::new(0, 0),
         }
     }
 
-    /// Prints one single char on screen and increments cursor to the right (wrapping)
-    pub fn print_char(&mut self, char: char) {
-        let index = self.cursor_position.get_index();
-        self.screen_buffer_input[index as usize] = char as u8; //TODO: check if this convertion is safe
-        self.cursor_position.increment();
+    pub fn width(&self) -> u8 {
+        self.area.width()
     }
 
-    pub fn print_u8(&mut self, data: u8) {
-        self.print_char(data as char)
+    pub fn height(&self) -> u8 {
+        self.area.height()
+    }
+
+    /// Column where a text of the given length starts to be centered in the region
+    pub fn get_centered_column(&self, text_length: u8) -> u8 {
+        self.width().saturating_sub(text_length) / 2
+    }
+
+    /// Region inside this one, the `area` is relative to this region and it is clipped by it.
+    pub fn sub_region(&mut self, area: Rectangle) -> ScreenRegion<'_, 'a> {
+        let area = self.area.sub_rectangle(area);
+        ScreenRegion::new(self.screen_buffer, area)
+    }
+
+    /// Converts a point relative to the region into absolute coordinates, returns `None` if the
+    /// point is outside the region
+    fn to_absolute(&self, point: Point) -> Option<Point> {
+        let is_inside = point.x < self.width() && point.y < self.height();
+        let left_upper = self.area.get_left_upper();
+        is_inside.then(|| Point::new(left_upper.x + point.x, left_upper.y + point.y))
     }
 
     /// Places cursor at the given position
     pub fn set_cursor(&mut self, point: Point) {
-        self.cursor_position.set(point);
+        self.cursor = point;
     }
 
-    /// Clear screen buffer completelly
-    pub fn clear(&mut self) {
-        const CLEARING_CHAR: u8 = ' ' as u8;
-        self.screen_buffer_input.fill(CLEARING_CHAR);
-        self.cursor_position.set(Point::new(0, 0));
+    /// Prints one single char and increments cursor to the right, chars beyond the right border
+    /// of the region are clipped.
+    pub fn print_char(&mut self, char: char) {
+        if let Some(point) = self.to_absolute(self.cursor) {
+            self.screen_buffer.write(point, char as u8); //TODO: check if this convertion is safe
+        }
+        self.cursor.x = self.cursor.x.saturating_add(1);
+    }
+
+    pub fn print_u8(&mut self, data: u8) {
+        self.print_char(data as char)
     }
 
     pub fn print(&mut self, data_to_print: impl Printable) {
@@ -98,17 +203,62 @@ impl<'a> ScreenBuffer<'a> {
         }
     }
 
-    // output part
-
-    /// The purpose of this routine is to avoid unecessary writings to LCD.
-    /// It swaps two lcd buffers: The output_buffer represents current state of lcd and
-    /// input_buffer represent the desired state of lcd
-    pub fn render(&mut self) {
-        // The current implementation of this function is very! very! simplified, it may be improved later
-        let lcd_driver = self.lcd;
-        lcd_driver.set_cursor(Point::new(0, 0));
-        for byte in self.screen_buffer_input {
-            lcd_driver.print_u8(byte);
+    /// Clears the region (but not the rest of the screen) and moves the cursor to its origin
+    pub fn clear(&mut self) {
+        const CLEARING_CHAR: u8 = ' ' as u8;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if let Some(point) = self.to_absolute(Point::new(x, y)) {
+                    self.screen_buffer.write(point, CLEARING_CHAR);
+                }
+            }
         }
+        self.cursor = Point::new(0, 0);
+    }
+
+    /// Char in the given position (relative to the region), `None` if it is outside the region
+    pub fn get_char(&self, point: Point) -> Option<u8> {
+        self.to_absolute(point)
+            .and_then(|point| self.screen_buffer.read(point))
+    }
+}
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::lcd::LcdFramebuffer;
+
+    #[test]
+    fn it_renders_only_the_changed_chars() {
+        let lcd = LcdFramebuffer::new();
+        let mut screen_buffer = ScreenBuffer::new(&lcd);
+        screen_buffer.full_region().print("Julia".bytes());
+        // the whole screen is sent in the first render: one cursor command and 40 chars per line
+        assert_eq!(screen_buffer.render(), 2 * (1 + 40));
+        assert_eq!(screen_buffer.render(), 0);
+        // one cursor command and the two changed chars
+        let mut region = screen_buffer.full_region();
+        region.set_cursor(Point::new(3, 1));
+        region.print("ok".bytes());
+        assert_eq!(screen_buffer.render(), 1 + 2);
+        assert_eq!(
+            lcd.get_lines(),
+            [format!("{:<40}", "Julia"), format!("{:<40}", "   ok")]
+        );
+    }
+
+    #[test]
+    fn it_clips_the_region() {
+        let lcd = LcdFramebuffer::new();
+        let mut screen_buffer = ScreenBuffer::new(&lcd);
+        let mut region = screen_buffer.region(Rectangle::from_size(Point::new(2, 0), 3, 1));
+        region.print("Julia".bytes());
+        region.set_cursor(Point::new(0, 1));
+        region.print("outside".bytes());
+        screen_buffer.render();
+        assert_eq!(
+            lcd.get_lines(),
+            [format!("{:<40}", "  Jul"), format!("{:<40}", "")]
+        );
     }
 }
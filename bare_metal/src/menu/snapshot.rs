@@ -0,0 +1,130 @@
+//! Snapshot tests of the widgets in the host.
+//!
+//! The widget runs as in the main loop of the app: the keys typed by a [`ScriptedKeyboard`] are
+//! sent to it and it is updated at each milisec of the virtual clock. When the script is finished
+//! the widget is drawn into a [`LcdFramebuffer`] and the text shown is compared with the expected
+//! one, line by line (the trailing spaces of the expected lines may be omitted):
+//!
+//! ```
+//! let keyboard = ScriptedKeyboard::new().key(KeyCode::KEY_PROGRAMA);
+//! assert_screen(&mut main_menu, keyboard, &[
+//!     " Selecione modo de programacao desejado",
+//!     "     MANUAL    EXECUCAO    PROGRAMA",
+//! ]);
+//! ```
+//!
+//! NOTE: The expected texts are written for the default lcd (40x2).
+
+use core::cell::Cell;
+
+use cross_platform::{
+    protocol::{
+        datalink::datalink::Datalink,
+        transport::{channel::Channel, transport_layer::cmpp_value::MechanicalProperties},
+    },
+    types::time::Instant,
+};
+
+use crate::{
+    board::{
+        host::{keyboard::ScriptedKeyboard, lcd::LcdFramebuffer},
+        keyboard::Keyboard,
+        lcd::size::NUMBER_OF_COLS,
+    },
+    menu::{screen_buffer::ScreenBuffer, widget::widget::Widget},
+    microcontroler::{
+        serial,
+        timer::{advance_virtual_clock, now_instant},
+    },
+    simulator::cmpp::EmulatedCmpp,
+};
+
+/// Runs the widget until all the keys of the script are typed and returns the text shown in the lcd
+pub fn run(widget: &mut dyn Widget, mut keyboard: ScriptedKeyboard) -> Vec<String> {
+    while !keyboard.is_finished() {
+        if let Some(event) = keyboard.get_key() {
+            widget.send_key_event(event);
+        }
+        widget.update();
+        advance_virtual_clock(1);
+    }
+    widget.update();
+    draw(widget)
+}
+
+/// Text shown in the lcd by the widget in its current state
+pub fn draw(widget: &dyn Widget) -> Vec<String> {
+    let lcd = LcdFramebuffer::new();
+    let mut screen_buffer = ScreenBuffer::new(&lcd);
+    widget.draw(&mut screen_buffer.full_region());
+    screen_buffer.render();
+    lcd.get_lines()
+}
+
+/// Runs the widget with the keys of the script and asserts the text shown in the lcd
+#[track_caller]
+pub fn assert_screen(widget: &mut dyn Widget, keyboard: ScriptedKeyboard, expected: &[&str]) {
+    let shown = run(widget, keyboard);
+    let expected: Vec<String> = expected
+        .iter()
+        .map(|line| format!("{:<width$}", line, width = NUMBER_OF_COLS as usize))
+        .collect();
+    assert_eq!(
+        shown,
+        expected,
+        "\nshown:\n{}\nexpected:\n{}\n",
+        shown.join("\n"),
+        expected.join("\n")
+    );
+}
+
+/// Datalink to a cmpp board which is not connected: nothing is received and each time the datalink
+/// reads the clock it advances, so the timeouts elapse.
+pub fn make_disconnected_datalink() -> Datalink {
+    fn now__() -> Instant {
+        advance_virtual_clock(1);
+        now_instant()
+    }
+    Datalink {
+        channel: Channel::default(),
+        now: now__,
+        timeout_ms: 1000,
+        try_rx: || Ok(None),
+        try_tx: |_byte| Some(()),
+        debug_reception: None,
+        half_duplex: None,
+        resynchronize: false,
+        discarded_bytes: Cell::new(0),
+    }
+}
+
+/// Datalink to the cmpp boards emulated in the serial (see: [`EmulatedCmpp`]), which answer all the
+/// frames. It plugs a new emulated board in the serial of the test.
+pub fn make_emulated_cmpp_datalink() -> Datalink {
+    fn try_rx() -> Result<Option<u8>, ()> {
+        Ok(serial::try_receive())
+    }
+    fn try_tx(byte: u8) -> Option<()> {
+        serial::try_transmit(byte).ok()
+    }
+    serial::connect(EmulatedCmpp::new());
+    Datalink {
+        channel: Channel::default(),
+        now: now_instant,
+        timeout_ms: 1000,
+        try_rx,
+        try_tx,
+        debug_reception: None,
+        half_duplex: None,
+        resynchronize: false,
+        discarded_bytes: Cell::new(0),
+    }
+}
+
+pub fn make_mechanical_properties() -> MechanicalProperties {
+    MechanicalProperties {
+        pulses_per_motor_revolution: 400,
+        linear_displacement_per_tooth_belt_mult_by_100: 508,
+        number_of_tooths_of_motor_pulley: 16,
+    }
+}
@@ -3,10 +3,13 @@ pub mod widget;
 
 // widgets implementations
 pub mod caption;
+pub mod dialog;
 pub mod execucao;
 pub mod field;
 pub mod main_menu;
 pub mod manual_mode;
 pub mod menu_item;
+pub mod navigation;
 pub mod splash;
 pub mod submenu_programa;
+pub mod upload;
@@ -2,15 +2,22 @@ use crate::geometry::point::Point;
 use crate::string::flash::FlashString;
 use crate::{
     board::keypad::KeyCode,
-    menu::{screen_buffer::ScreenBuffer, widget::widget::Widget},
+    menu::{screen_buffer::ScreenRegion, widget::widget::Widget},
 };
 pub struct Caption {
     text: FlashString,
+    /// Printed after the text separated by a blank, ie: the letter of the axis
+    suffix: Option<FlashString>,
 }
 
 impl Caption {
     pub fn new(text: FlashString) -> Self {
-        Self { text }
+        Self { text, suffix: None }
+    }
+
+    pub fn with_suffix(mut self, suffix: FlashString) -> Self {
+        self.suffix = Some(suffix);
+        self
     }
 
     pub fn set_caption(&mut self, text: FlashString) {
@@ -27,10 +34,16 @@ impl Widget for Caption {
         // do nothing
     }
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, start_point: Point) {
-        screen_buffer.set_cursor(start_point);
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.set_cursor(Point::new(0, 0));
         for byte in self.text.into_iter() {
-            screen_buffer.print_char(byte as char);
+            region.print_char(byte as char);
+        }
+        if let Some(suffix) = self.suffix {
+            region.print_char(' ');
+            for byte in suffix.into_iter() {
+                region.print_char(byte as char);
+            }
         }
     }
 }
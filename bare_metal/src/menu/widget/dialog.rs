@@ -0,0 +1,433 @@
+//! Modal dialogs which can be opened on top of any screen: message box, confirmation box and
+//! progress bar.
+//!
+//! The dialogs are non-blocking [`Widget`]s. The screen which opens a dialog stores it (normally
+//! in a [`ModalDialog`]) and, while it is open, delegates to it the keys, the update and the draw.
+//! After each key or update the screen reads [`Dialog::get_result`], when it returns some result
+//! the dialog is closed and the screen takes the result into account.
+
+use crate::{
+    board::keypad::KeyCode, geometry::point::Point, menu::screen_buffer::ScreenRegion,
+    microcontroler::timer::now_instant, string::flash::FlashString,
+};
+use cross_platform::types::time::{Duration, Instant};
+
+use super::widget::Widget;
+
+/// Result of a dialog when it is closed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DialogResult {
+    /// `ENTER` or `1` pressed in a confirmation box
+    Yes,
+    /// `0` pressed in a confirmation box
+    No,
+    /// `ESC` pressed in a confirmation box
+    Canceled,
+    /// Message box closed by a key or by its timeout, or progress bar completed
+    Dismissed,
+}
+
+/// A [`Widget`] which is closed returning a result to the screen which opened it.
+pub trait Dialog: Widget {
+    /// Returns `None` while the dialog is open
+    fn get_result(&self) -> Option<DialogResult>;
+}
+
+/// Column where the texts of the dialogs are printed
+const TEXT_COLUMN: u8 = 1;
+
+fn print_line(region: &mut ScreenRegion, line: u8, text: FlashString) {
+    region.set_cursor(Point::new(TEXT_COLUMN, line));
+    region.print(text);
+}
+
+// -----------------------------------------------------------
+//            MESSAGE BOX
+// -----------------------------------------------------------
+
+/// Shows a message of two lines until the user presses any key or the timeout elapses
+pub struct MessageBox {
+    lines: [FlashString; 2],
+    /// Optional number printed after the text of the second line
+    value: Option<u16>,
+    /// Time when the message is closed automatically
+    deadline: Instant,
+    is_dismissed: bool,
+}
+
+impl MessageBox {
+    /// Default time the message stays on screen if no key is pressed
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(2000);
+
+    pub fn new(line0: FlashString, line1: FlashString) -> Self {
+        Self {
+            lines: [line0, line1],
+            value: None,
+            deadline: now_instant() + Self::DEFAULT_TIMEOUT,
+            is_dismissed: false,
+        }
+    }
+
+    /// Prints the given value after the text of the second line
+    pub fn with_value(mut self, value: u16) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Changes the time the message stays on screen if no key is pressed
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = now_instant() + timeout;
+        self
+    }
+}
+
+impl Widget for MessageBox {
+    fn send_key(&mut self, _key: KeyCode) {
+        self.is_dismissed = true;
+    }
+
+    fn update(&mut self) {
+        if now_instant().has_reached(self.deadline) {
+            self.is_dismissed = true;
+        }
+    }
+
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.clear();
+        let [line0, line1] = self.lines;
+        print_line(region, 0, line0);
+        print_line(region, 1, line1);
+        if let Some(value) = self.value {
+            region.print_u16(value);
+        }
+    }
+}
+
+impl Dialog for MessageBox {
+    fn get_result(&self) -> Option<DialogResult> {
+        self.is_dismissed.then_some(DialogResult::Dismissed)
+    }
+}
+
+// -----------------------------------------------------------
+//            CONFIRMATION BOX
+// -----------------------------------------------------------
+
+/// Asks a question to the user. The `hint` tells which keys answer it, see [`DialogResult`].
+pub struct ConfirmationBox {
+    question: FlashString,
+    hint: FlashString,
+    answer: Option<DialogResult>,
+}
+
+impl ConfirmationBox {
+    pub fn new(question: FlashString, hint: FlashString) -> Self {
+        Self {
+            question,
+            hint,
+            answer: None,
+        }
+    }
+}
+
+impl Widget for ConfirmationBox {
+    fn send_key(&mut self, key: KeyCode) {
+        self.answer = match key {
+            KeyCode::KEY_ENTER | KeyCode::KEY_1 => Some(DialogResult::Yes),
+            KeyCode::KEY_0 => Some(DialogResult::No),
+            KeyCode::KEY_ESC => Some(DialogResult::Canceled),
+            _ => self.answer, // ignore other keys
+        }
+    }
+
+    fn update(&mut self) {
+        // nothing to update
+    }
+
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.clear();
+        print_line(region, 0, self.question);
+        print_line(region, 1, self.hint);
+    }
+}
+
+impl Dialog for ConfirmationBox {
+    fn get_result(&self) -> Option<DialogResult> {
+        self.answer
+    }
+}
+
+// -----------------------------------------------------------
+//            PROGRESS BAR
+// -----------------------------------------------------------
+
+/// Shows the progress of a long task, the owner of the task informs the progress through
+/// [`ProgressBar::set_progress`]. It is dismissed when the task is completed.
+pub struct ProgressBar {
+    caption: FlashString,
+    done: u16,
+    total: u16,
+}
+
+impl ProgressBar {
+    /// Maximum number of chars of the bar, in narrow displays it is shortened to fit the line
+    const MAX_WIDTH: u8 = 30;
+    /// Chars after the bar: space, percentage and '%'
+    const PERCENTAGE_WIDTH: u8 = 5;
+    const FILLED_CHAR: u8 = b'#';
+    const EMPTY_CHAR: u8 = b'.';
+
+    pub fn new(caption: FlashString, total: u16) -> Self {
+        Self {
+            caption,
+            done: 0,
+            total,
+        }
+    }
+
+    /// Changes the caption, ie: to show the step of the task being executed
+    pub fn set_caption(&mut self, caption: FlashString) {
+        self.caption = caption;
+    }
+
+    pub fn set_progress(&mut self, done: u16) {
+        self.done = done.min(self.total);
+    }
+
+    pub fn is_completed(&self) -> bool {
+        self.done >= self.total
+    }
+
+    /// Progress from 0 to 100
+    fn get_percentage(&self) -> u16 {
+        if self.total == 0 {
+            100
+        } else {
+            (self.done as u32 * 100 / self.total as u32) as u16
+        }
+    }
+}
+
+impl Widget for ProgressBar {
+    fn send_key(&mut self, _key: KeyCode) {
+        // the task cannot be interrupted by the user
+    }
+
+    fn update(&mut self) {
+        // nothing to update, the progress is informed by the owner of the task
+    }
+
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.clear();
+        print_line(region, 0, self.caption);
+        region.set_cursor(Point::new(TEXT_COLUMN, 1));
+        let percentage = self.get_percentage();
+        let width = region
+            .width()
+            .saturating_sub(TEXT_COLUMN + Self::PERCENTAGE_WIDTH)
+            .min(Self::MAX_WIDTH) as u16;
+        let filled = percentage * width / 100;
+        for position in 0..width {
+            if position < filled {
+                region.print_u8(Self::FILLED_CHAR);
+            } else {
+                region.print_u8(Self::EMPTY_CHAR);
+            }
+        }
+        region.print_u8(b' ');
+        region.print_u16(percentage);
+        region.print_u8(b'%');
+    }
+}
+
+impl Dialog for ProgressBar {
+    fn get_result(&self) -> Option<DialogResult> {
+        self.is_completed().then_some(DialogResult::Dismissed)
+    }
+}
+
+// -----------------------------------------------------------
+//            MODAL DIALOG
+// -----------------------------------------------------------
+
+/// Any of the dialogs, so the screen can store the dialog which is currently open in one field.
+pub enum ModalDialog {
+    MessageBox(MessageBox),
+    ConfirmationBox(ConfirmationBox),
+    ProgressBar(ProgressBar),
+}
+
+impl ModalDialog {
+    fn as_dialog(&self) -> &dyn Dialog {
+        match self {
+            ModalDialog::MessageBox(dialog) => dialog,
+            ModalDialog::ConfirmationBox(dialog) => dialog,
+            ModalDialog::ProgressBar(dialog) => dialog,
+        }
+    }
+
+    fn as_dialog_mut(&mut self) -> &mut dyn Dialog {
+        match self {
+            ModalDialog::MessageBox(dialog) => dialog,
+            ModalDialog::ConfirmationBox(dialog) => dialog,
+            ModalDialog::ProgressBar(dialog) => dialog,
+        }
+    }
+}
+
+impl Widget for ModalDialog {
+    fn send_key(&mut self, key: KeyCode) {
+        self.as_dialog_mut().send_key(key)
+    }
+
+    fn update(&mut self) {
+        self.as_dialog_mut().update()
+    }
+
+    fn draw(&self, region: &mut ScreenRegion) {
+        self.as_dialog().draw(region)
+    }
+}
+
+impl Dialog for ModalDialog {
+    fn get_result(&self) -> Option<DialogResult> {
+        self.as_dialog().get_result()
+    }
+}
+
+impl From<MessageBox> for ModalDialog {
+    fn from(dialog: MessageBox) -> Self {
+        ModalDialog::MessageBox(dialog)
+    }
+}
+
+impl From<ConfirmationBox> for ModalDialog {
+    fn from(dialog: ConfirmationBox) -> Self {
+        ModalDialog::ConfirmationBox(dialog)
+    }
+}
+
+impl From<ProgressBar> for ModalDialog {
+    fn from(dialog: ProgressBar) -> Self {
+        ModalDialog::ProgressBar(dialog)
+    }
+}
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::snapshot::assert_screen;
+    use crate::microcontroler::timer::advance_virtual_clock;
+    use avr_progmem::progmem;
+
+    progmem! {
+        static progmem string CARGA_CONCLUIDA = "Carga do programa concluida";
+        static progmem string PARAMETROS_NAO_ENVIADOS = "Parametros nao enviados: ";
+        static progmem string SALVAR_ALTERACOES = "Salvar alteracoes?";
+        static progmem string SIM_NAO_CANCELAR = "ENTER=Sim 0=Nao ESC=Cancelar";
+        static progmem string ENVIANDO = "Enviando";
+    }
+
+    fn make_message_box() -> MessageBox {
+        MessageBox::new(
+            FlashString::new(&CARGA_CONCLUIDA),
+            FlashString::new(&PARAMETROS_NAO_ENVIADOS),
+        )
+    }
+
+    fn make_confirmation_box() -> ConfirmationBox {
+        ConfirmationBox::new(
+            FlashString::new(&SALVAR_ALTERACOES),
+            FlashString::new(&SIM_NAO_CANCELAR),
+        )
+    }
+
+    #[test]
+    fn it_shows_the_message_and_its_value() {
+        let mut message_box = make_message_box().with_value(82);
+        assert_screen(
+            &mut message_box,
+            ScriptedKeyboard::new(),
+            &[
+                " Carga do programa concluida",
+                " Parametros nao enviados: 82",
+            ],
+        );
+    }
+
+    #[test]
+    fn it_dismisses_the_message_box_after_the_timeout() {
+        let mut message_box = make_message_box();
+        advance_virtual_clock(1999);
+        message_box.update();
+        assert_eq!(message_box.get_result(), None);
+        advance_virtual_clock(1);
+        message_box.update();
+        assert_eq!(message_box.get_result(), Some(DialogResult::Dismissed));
+
+        let mut message_box = make_message_box().with_timeout(Duration::from_millis(500));
+        advance_virtual_clock(500);
+        message_box.update();
+        assert_eq!(message_box.get_result(), Some(DialogResult::Dismissed));
+    }
+
+    #[test]
+    fn it_dismisses_the_message_box_with_any_key() {
+        let mut message_box = make_message_box();
+        message_box.send_key(KeyCode::KEY_5);
+        assert_eq!(message_box.get_result(), Some(DialogResult::Dismissed));
+    }
+
+    #[test]
+    fn it_maps_the_keys_to_the_answers_of_the_confirmation_box() {
+        let answers = [
+            (KeyCode::KEY_ENTER, DialogResult::Yes),
+            (KeyCode::KEY_1, DialogResult::Yes),
+            (KeyCode::KEY_0, DialogResult::No),
+            (KeyCode::KEY_ESC, DialogResult::Canceled),
+        ];
+        for (key, answer) in answers {
+            let mut confirmation_box = make_confirmation_box();
+            confirmation_box.send_key(key);
+            assert_eq!(confirmation_box.get_result(), Some(answer), "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn it_waits_for_an_answer_in_the_confirmation_box() {
+        let mut confirmation_box = make_confirmation_box();
+        // the confirmation box has no timeout and the other keys are ignored
+        advance_virtual_clock(60_000);
+        confirmation_box.send_key(KeyCode::KEY_5);
+        confirmation_box.update();
+        assert_eq!(confirmation_box.get_result(), None);
+        assert_screen(
+            &mut confirmation_box,
+            ScriptedKeyboard::new(),
+            &[" Salvar alteracoes?", " ENTER=Sim 0=Nao ESC=Cancelar"],
+        );
+    }
+
+    #[test]
+    fn it_dismisses_the_progress_bar_when_the_task_is_completed() {
+        let mut progress_bar = ProgressBar::new(FlashString::new(&ENVIANDO), 4);
+        progress_bar.set_progress(2);
+        // the user cannot interrupt the task
+        progress_bar.send_key(KeyCode::KEY_ESC);
+        assert_eq!(progress_bar.get_result(), None);
+        assert_screen(
+            &mut progress_bar,
+            ScriptedKeyboard::new(),
+            &[" Enviando", " ###############............... 50%"],
+        );
+        progress_bar.set_progress(5);
+        assert!(progress_bar.is_completed());
+        assert_eq!(progress_bar.get_result(), Some(DialogResult::Dismissed));
+        assert_screen(
+            &mut progress_bar,
+            ScriptedKeyboard::new(),
+            &[" Enviando", " ############################## 100%"],
+        );
+    }
+}
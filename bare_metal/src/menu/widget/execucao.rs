@@ -1,14 +1,16 @@
 //menu "execucao"
 
 use crate::geometry::point::Point;
+use crate::menu::model::StatusDoEixo;
 use crate::string::flash::FlashString;
-use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenBuffer};
+use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenRegion};
 use avr_progmem::progmem;
-use cross_platform::protocol::transport::transport_layer::{
-    new_proposal::Displacement, TransportLayer,
-};
+use cross_platform::protocol::transport::transport_layer::TransportLayer;
 
-use super::widget::Widget;
+use super::{
+    navigation::{Navigation, ReturnValue, Screen},
+    widget::Widget,
+};
 
 progmem! {
     //                             1234567890123456789012345678901234567890
@@ -16,24 +18,22 @@ progmem! {
     static progmem string LINE1 = "X =       mm"; //"${nnnn}    Y=${nnnn}";
 }
 
+/// Column of the `LINE1` where the current position is printed
+const POSICAO_ATUAL_COLUMN: u8 = 4;
+
 pub struct MenuExecucaoControler<'a> {
     transport: &'a TransportLayer<'a>,
+    /// Status of the axis, polled by the main loop while this screen is shown
+    status: &'a StatusDoEixo,
+    navigation: Option<Navigation>,
 }
 
 impl<'a> MenuExecucaoControler<'a> {
-    pub fn new(transport: &'a TransportLayer<'a>) -> Self {
-        Self { transport }
-    }
-
-    fn get_line_helper(line_number: u8) -> (Point, impl IntoIterator<Item = u8>) {
-        let line0 = FlashString::new(&LINE0);
-        let line1 = FlashString::new(&LINE1);
-        let col0 = ((40 - line0.len()) / 2).try_into().unwrap_or(0);
-        let col1 = ((40 - line1.len()) / 2).try_into().unwrap_or(0);
-        if line_number == 0 {
-            (Point::new(col0, 0), line0)
-        } else {
-            (Point::new(col1, 1), line1)
+    pub fn new(transport: &'a TransportLayer<'a>, status: &'a StatusDoEixo) -> Self {
+        Self {
+            transport,
+            status,
+            navigation: None,
         }
     }
 }
@@ -47,25 +47,43 @@ impl<'a> Widget for MenuExecucaoControler<'a> {
             KeyCode::KEY_STOP => {
                 let _unwrap = self.transport.stop(); // TODO: The use of `.unwrap` method is provoking flash size explosion, check why
             }
+            KeyCode::KEY_ESC => {
+                self.status.monitorado.set(false);
+                self.navigation = Some(Navigation::Pop(ReturnValue::Nothing));
+            }
             _ => {}
         }
     }
 
     fn update(&mut self) {}
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, _start_point: Point) {
-        screen_buffer.clear();
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.clear();
         // draw screen frame
-        for line_number in 0..2 {
-            let (point, text) = Self::get_line_helper(line_number);
-            screen_buffer.set_cursor(point);
-            screen_buffer.print(text);
-        }
+        let line0 = FlashString::new(&LINE0);
+        let line1 = FlashString::new(&LINE1);
+        let col0 = region.get_centered_column(line0.len());
+        let col1 = region.get_centered_column(line1.len());
+        region.set_cursor(Point::new(col0, 0));
+        region.print(line0);
+        region.set_cursor(Point::new(col1, 1));
+        region.print(line1);
         // draw current position
-        let posicao_atual = self.transport.posicao_atual();
-        if let Ok(Displacement(posicao_atual)) = posicao_atual {
-            screen_buffer.set_cursor(Point::new(18, 1));
-            screen_buffer.print_u16(posicao_atual);
+        if let Some(posicao_atual) = self.status.posicao_atual.get() {
+            region.set_cursor(Point::new(col1 + POSICAO_ATUAL_COLUMN, 1));
+            region.print_u16(posicao_atual);
         }
     }
 }
+
+impl<'a> Screen for MenuExecucaoControler<'a> {
+    fn take_navigation(&mut self) -> Option<Navigation> {
+        self.navigation.take()
+    }
+
+    fn on_enter(&mut self) {
+        // the position shown is the one of the last poll
+        self.status.posicao_atual.set(None);
+        self.status.monitorado.set(true);
+    }
+}
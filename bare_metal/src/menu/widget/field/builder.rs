@@ -6,7 +6,7 @@ use crate::string::flash::FlashString;
 
 use super::{
     field::Field,
-    numerical::Format,
+    numerical::{Format, Radix},
     optional::{make_options_buffer_from_array, OptionsBuffer},
 };
 
@@ -16,6 +16,10 @@ pub struct NumericalFieldBuilder<'a> {
     variable: &'a Cell<u16>,
     valid_range: Option<Range<u16>>,
     initial_cursor_position: Option<u8>,
+    decimal_places: u8,
+    is_signed: bool,
+    radix: Radix,
+    is_masked: bool,
 }
 
 impl<'a> NumericalFieldBuilder<'a> {
@@ -25,6 +29,10 @@ impl<'a> NumericalFieldBuilder<'a> {
             variable,
             valid_range: None,
             initial_cursor_position: None,
+            decimal_places: 0,
+            is_signed: false,
+            radix: Radix::Decimal,
+            is_masked: false,
         }
     }
 
@@ -46,6 +54,10 @@ impl<'a> NumericalFieldBuilder<'a> {
             start,
             end,
             initial_cursor_position,
+            decimal_places: self.decimal_places,
+            is_signed: self.is_signed,
+            radix: self.radix,
+            is_masked: self.is_masked,
         }
     }
 
@@ -61,6 +73,30 @@ impl<'a> NumericalFieldBuilder<'a> {
         self
     }
 
+    /// Sets the number of digits after the decimal point (see: [`Format::decimal_places`])
+    pub fn decimal_places(&mut self, decimal_places: u8) -> &mut Self {
+        self.decimal_places = decimal_places;
+        self
+    }
+
+    /// Makes the number signed (see: [`Format::is_signed`])
+    pub fn signed(&mut self) -> &mut Self {
+        self.is_signed = true;
+        self
+    }
+
+    /// Shows and edits the number in hexadecimal
+    pub fn hexadecimal(&mut self) -> &mut Self {
+        self.radix = Radix::Hexadecimal;
+        self
+    }
+
+    /// Hides the digits of the number (see: [`Format::is_masked`])
+    pub fn masked(&mut self) -> &mut Self {
+        self.is_masked = true;
+        self
+    }
+
     /// Builds the Field
     pub fn build(&mut self) -> Field<'a> {
         let format = self.make_format();
@@ -1,10 +1,9 @@
 use core::cell::Cell;
 
-use crate::geometry::point::Point;
 use crate::{
     board::keypad::KeyCode,
     menu::{
-        screen_buffer::ScreenBuffer,
+        screen_buffer::ScreenRegion,
         widget::widget::{Editable, Saveble, Widget},
     },
 };
@@ -71,10 +70,10 @@ impl Widget for Field<'_> {
         }
     }
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, start_point: Point) {
+    fn draw(&self, region: &mut ScreenRegion) {
         match self {
-            Self::Numerical(x) => x.draw(screen_buffer, start_point),
-            Self::Optional(x) => x.draw(screen_buffer, start_point),
+            Self::Numerical(x) => x.draw(region),
+            Self::Optional(x) => x.draw(region),
         }
     }
 }
@@ -3,14 +3,17 @@ use core::cell::Cell;
 use core::ops::Range;
 use core::str::{CharIndices, Chars, FromStr};
 
-use cross_platform::utils::numerical::{convert_u16_to_string_decimal, usize_to_u8_clamper};
+use cross_platform::utils::numerical::{
+    convert_u16_to_str_hex, convert_u16_to_string_decimal, usize_to_u8_clamper,
+};
 use heapless::String;
 
 use super::super::widget::Saveble;
 use super::super::{widget::Editable, widget::Widget};
 use crate::geometry::point::Point;
 use crate::microcontroler::ratangular_wave::RectangularWave;
-use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenBuffer};
+use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenRegion};
+use cross_platform::types::time::Duration;
 
 use cross_platform::utils::cursor::Cursor;
 
@@ -51,10 +54,26 @@ impl Content {
     /// TODO: Use `number_of_digits` to represent the max digits you want for your u16 representation
     /// If the `u16` value is greater than max size that `number_of_digits` can contain, than
     /// the u16 will be clamped silently.
-    fn from_u16_formated(data: u16, number_of_digits: u8) -> Content {
+    fn from_u16_formated(data: u16, number_of_digits: u8, radix: Radix) -> Content {
         const BLACKET_CHAR: char = '0';
-        let s = convert_u16_to_string_decimal(data);
-        let base = Content::from_str(s.as_str()).unwrap();
+        let base = match radix {
+            Radix::Decimal => {
+                let s = convert_u16_to_string_decimal(data);
+                Content::from_str(s.as_str()).unwrap()
+            }
+            Radix::Hexadecimal => {
+                let (d3, d2, d1, d0) = convert_u16_to_str_hex(data);
+                let mut s = Content::new();
+                // leading zeros are added below
+                for digit in [d3, d2, d1, d0]
+                    .into_iter()
+                    .skip_while(|digit| *digit == '0')
+                {
+                    s.push(digit);
+                }
+                s
+            }
+        };
         let mut temp = Content::new();
         //leading zeros
         let len = base.len();
@@ -70,8 +89,11 @@ impl Content {
 
     /// Converts a [`Content`] that is supposed to contains an number into an [`u16`] value.
     /// If [`Content`] does not contains a number or if the convertion is not possible returns zero
-    fn to_u16(&self) -> u16 {
-        self.parse::<u16>().unwrap_or(0)
+    fn to_u16(&self, radix: Radix) -> u16 {
+        match radix {
+            Radix::Decimal => self.parse::<u16>().unwrap_or(0),
+            Radix::Hexadecimal => u16::from_str_radix(self.data.as_str(), 16).unwrap_or(0),
+        }
     }
 
     // ============== [ wrapping over heapless::String methods ] ================
@@ -160,6 +182,21 @@ impl InputEditor {
         self.change_cursor_item_to(item);
         self.move_cursor_right()
     }
+
+    /// Returns the character in place of the current cursor position
+    pub fn get_cursor_item(&self) -> Option<char> {
+        let current_cursor = self.cursor.get_current() as usize;
+        self.content.chars().nth(current_cursor)
+    }
+}
+
+/// Numerical base used to show and to edit the number
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Radix {
+    Decimal,
+    /// The digits above `9` (ie: `A..F`) are not in the keypad, so they are reached incrementing or
+    /// decrementing the digit under the cursor with the up and down keys.
+    Hexadecimal,
 }
 
 /// Format parameters for the [`NumberInputEditor`] type. Wrapper around the main parameters of the [`NumberInputEditor`]
@@ -172,15 +209,52 @@ pub struct Format {
     /// In what digit cursor should start in. (from left to right, starting at 0 and going until Self::get_number_of_digits)
     /// The total number of digits is based in the number of digits necessary to represent the `Format::end` value
     pub initial_cursor_position: u8,
+    /// Number of digits after the decimal point. The variable stores the number multiplied by
+    /// `10^decimal_places` (ie: `12.5` with one decimal place is stored as `125`).
+    pub decimal_places: u8,
+    /// If true the variable stores an `i16` (in two's complement) and the sign is shown in front of
+    /// the digits. NOTE: In this case `start` and `end` limit the absolute value of the number, so
+    /// `end` must not be greater than [`Format::MAX_SIGNED_END`].
+    pub is_signed: bool,
+    pub radix: Radix,
+    /// If true each digit is drawn as `*`, so the number typed (ie: a password) is not shown.
+    pub is_masked: bool,
 }
 
 impl Format {
-    /// Given a valid_range.END calculates the least amount of digits necessary to represent it in decimal as a string
+    /// Greatest `end` of a signed format, above it the absolute value does not fit in an `i16`
+    pub const MAX_SIGNED_END: u16 = i16::MAX as u16;
+
+    /// Format of an unsigned integer in decimal base
+    pub const fn new(start: u16, end: u16) -> Self {
+        Self {
+            start,
+            end,
+            initial_cursor_position: 0,
+            decimal_places: 0,
+            is_signed: false,
+            radix: Radix::Decimal,
+            is_masked: false,
+        }
+    }
+
+    /// Given a valid_range.END calculates the least amount of digits necessary to represent it in
+    /// the format's radix as a string. There is always at least one digit before the decimal point.
     pub fn get_number_of_digits(&self) -> u8 {
         // TODO: When possible this code may be refactored to use pure math
         let max = self.end;
-        let s = convert_u16_to_string_decimal(max);
-        usize_to_u8_clamper(s.len())
+        let number_of_digits = match self.radix {
+            Radix::Decimal => {
+                let s = convert_u16_to_string_decimal(max);
+                usize_to_u8_clamper(s.len())
+            }
+            Radix::Hexadecimal => {
+                let (d3, d2, d1, _) = convert_u16_to_str_hex(max);
+                let leading_zeros = [d3, d2, d1].iter().take_while(|d| **d == '0').count();
+                usize_to_u8_clamper(4 - leading_zeros)
+            }
+        };
+        number_of_digits.max(self.decimal_places + 1)
     }
 
     pub fn get_valid_range(&self) -> Range<u16> {
@@ -189,17 +263,32 @@ impl Format {
 }
 
 /// Just a decorator around [`InputEditor`] for deal with formated numbers
+///
+/// NOTE: Just the digits are stored in the [`InputEditor`], the sign and the decimal point are
+/// added when the number is drawn.
 struct NumberInputEditor {
     content_editor: InputEditor,
     format: Format,
+    is_negative: bool,
 }
 
 impl NumberInputEditor {
     /// Private constructor. NOTE: Use method [`Self::from_u16`] instead
-    fn new(initial_content: Content, format: Format) -> Self {
+    fn new(initial_content: Content, format: Format, is_negative: bool) -> Self {
         Self {
             content_editor: InputEditor::new(initial_content, format.initial_cursor_position),
             format,
+            is_negative,
+        }
+    }
+
+    /// Splits the value of the variable in its sign and its absolute value
+    fn split_sign(value: u16, format: &Format) -> (bool, u16) {
+        if format.is_signed {
+            let value = value as i16;
+            (value < 0, value.unsigned_abs())
+        } else {
+            (false, value)
         }
     }
 
@@ -209,9 +298,10 @@ impl NumberInputEditor {
     /// If the `u16` value is greater than max size that `number_of_digits` can contain, than than
     /// the u16 will be clamped silently.
     pub fn from_u16(initial_value: u16, format: Format) -> Self {
+        let (is_negative, absolute_value) = Self::split_sign(initial_value, &format);
         let initial_content =
-            Content::from_u16_formated(initial_value, format.get_number_of_digits());
-        Self::new(initial_content, format)
+            Content::from_u16_formated(absolute_value, format.get_number_of_digits(), format.radix);
+        Self::new(initial_content, format, is_negative)
     }
 
     /// Sets u16 value. If no format is given, it uses current format to set the value
@@ -220,17 +310,73 @@ impl NumberInputEditor {
             Some(format) => format,
             None => self.format,
         };
-        let content = Content::from_u16_formated(value, format.get_number_of_digits());
+        let (is_negative, absolute_value) = Self::split_sign(value, &format);
+        let content =
+            Content::from_u16_formated(absolute_value, format.get_number_of_digits(), format.radix);
         self.content_editor = InputEditor::new(content, format.initial_cursor_position);
+        self.is_negative = is_negative;
     }
 
     /// Copy edited value to its [`u16`] representation assuring the value is clamped to `self.format.valid_range`
     pub fn as_u16_clamped(&self) -> u16 {
-        let current_edited_value = Content::to_u16(&self.content_editor.content);
+        let current_edited_value = Content::to_u16(&self.content_editor.content, self.format.radix);
         let min = self.format.get_valid_range().start;
         let max = self.format.get_valid_range().end;
         let clamped_value = current_edited_value.clamp(min, max);
-        clamped_value
+        if self.is_negative {
+            (clamped_value as i16).wrapping_neg() as u16
+        } else {
+            clamped_value
+        }
+    }
+
+    /// Inverts the sign of the number, if the format is signed
+    pub fn toggle_sign(&mut self) {
+        if self.format.is_signed {
+            self.is_negative = !self.is_negative;
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.is_negative
+    }
+
+    /// Position of the first digit after the decimal point, None if the number has no decimal places
+    pub fn get_decimal_point_position(&self) -> Option<u8> {
+        match self.format.decimal_places {
+            0 => None,
+            decimal_places => Some(self.content_editor.content.len() - decimal_places),
+        }
+    }
+
+    /// Moves the cursor to the first digit after the decimal point
+    pub fn move_cursor_to_decimal_point(&mut self) {
+        if let Some(position) = self.get_decimal_point_position() {
+            self.content_editor.cursor.set_current(position);
+        }
+    }
+
+    /// Increments (or decrements) the digit under the cursor, wrapping around the radix.
+    pub fn step_cursor_digit(&mut self, increment: bool) {
+        let radix = match self.format.radix {
+            Radix::Decimal => 10,
+            Radix::Hexadecimal => 16,
+        };
+        if let Some(digit) = self
+            .content_editor
+            .get_cursor_item()
+            .and_then(|c| c.to_digit(radix))
+        {
+            let digit = if increment {
+                (digit + 1) % radix
+            } else {
+                (digit + radix - 1) % radix
+            };
+            if let Some(c) = char::from_digit(digit, radix) {
+                self.content_editor
+                    .change_cursor_item_to(c.to_ascii_uppercase());
+            }
+        }
     }
 
     pub fn get_current_cursor_index(&self) -> u8 {
@@ -255,7 +401,8 @@ impl NumberInputEditor {
     }
 }
 
-/// This [`Widget`] manages the edition of an number (unsigned integer) by the user using the keyboard and lcd display
+/// This [`Widget`] manages the edition of an number by the user using the keyboard and lcd display.
+/// See [`Format`] for the supported numbers (ie: decimal places, signed and hexadecimal).
 pub struct NumberInputEditorWidget<'a> {
     number_editor: NumberInputEditor,
     /// This class is responsible to generate the blinking character effect
@@ -266,8 +413,8 @@ pub struct NumberInputEditorWidget<'a> {
 
 impl<'a> NumberInputEditorWidget<'a> {
     pub fn new(variable: &'a Cell<u16>, format: Format, is_in_edit_mode_: bool) -> Self {
-        const T_ON: u16 = 600;
-        const T_OFF: u16 = 300;
+        const T_ON: Duration = Duration::from_millis(600);
+        const T_OFF: Duration = Duration::from_millis(300);
         let initial_value = variable.get();
         Self {
             number_editor: NumberInputEditor::from_u16(initial_value, format.clone()),
@@ -351,6 +498,23 @@ impl Widget for NumberInputEditorWidget<'_> {
                 KeyCode::KEY_9 => {
                     content_editor.addAndMoveRight('9');
                 }
+                // sign, decimal point and hexadecimal digits
+                KeyCode::KEY_MAIS_OU_MENOS => {
+                    self.number_editor.toggle_sign();
+                }
+                KeyCode::KEY_PONTO => {
+                    self.number_editor.move_cursor_to_decimal_point();
+                }
+                KeyCode::KEY_DIRECIONAL_PARA_CIMA => {
+                    if self.number_editor.get_format().radix == Radix::Hexadecimal {
+                        self.number_editor.step_cursor_digit(true);
+                    }
+                }
+                KeyCode::KEY_DIRECIONAL_PARA_BAIXO => {
+                    if self.number_editor.get_format().radix == Radix::Hexadecimal {
+                        self.number_editor.step_cursor_digit(false);
+                    }
+                }
                 //everything else -> do nothing
                 _ => {}
             }
@@ -363,18 +527,188 @@ impl Widget for NumberInputEditorWidget<'_> {
         self.blink.update(); // blinks cursor
     }
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, start_point: Point) {
-        screen_buffer.set_cursor(start_point);
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.set_cursor(Point::new(0, 0));
+        if self.number_editor.get_format().is_signed {
+            let sign = if self.number_editor.is_negative() {
+                '-'
+            } else {
+                '+'
+            };
+            region.print_char(sign);
+        }
+        let decimal_point_position = self.number_editor.get_decimal_point_position();
         for (position, digit) in self.number_editor.char_indices() {
+            if decimal_point_position == Some(position as u8) {
+                region.print_char('.');
+            }
             const BLINK_CHAR: char = '_';
-            let mut current_char = digit;
+            const MASK_CHAR: char = '*';
+            let mut current_char = if self.number_editor.get_format().is_masked {
+                MASK_CHAR
+            } else {
+                digit
+            };
             let is_current_char_over_cursor =
                 position == self.number_editor.get_current_cursor_index() as usize;
             let is_time_to_blink = !self.blink.read();
             if is_current_char_over_cursor && is_time_to_blink && self.is_in_edit_mode() {
                 current_char = BLINK_CHAR;
             }
-            screen_buffer.print_char(current_char);
+            region.print_char(current_char);
         }
     }
 }
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::snapshot::assert_screen;
+
+    #[test]
+    fn it_shows_the_value_with_leading_zeros() {
+        let variable = Cell::new(50);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), false);
+        assert_screen(&mut field, ScriptedKeyboard::new(), &["0050", ""]);
+    }
+
+    #[test]
+    fn it_ignores_the_keys_out_of_edit_mode() {
+        let variable = Cell::new(50);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), false);
+        let keyboard = ScriptedKeyboard::new().keys(&[KeyCode::KEY_1, KeyCode::KEY_2]);
+        assert_screen(&mut field, keyboard, &["0050", ""]);
+    }
+
+    #[test]
+    fn it_blinks_the_digit_under_the_cursor() {
+        let variable = Cell::new(50);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), true);
+        // the cursor is shown during 600ms and hidden during 300ms
+        assert_screen(&mut field, ScriptedKeyboard::new().wait(500), &["0050", ""]);
+        assert_screen(&mut field, ScriptedKeyboard::new().wait(200), &["_050", ""]);
+    }
+
+    #[test]
+    fn it_saves_the_typed_digits() {
+        let variable = Cell::new(50);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), true);
+        let keyboard = ScriptedKeyboard::new().keys(&[
+            KeyCode::KEY_0,
+            KeyCode::KEY_7,
+            KeyCode::KEY_2,
+            KeyCode::KEY_5,
+        ]);
+        assert_screen(&mut field, keyboard, &["0725", ""]);
+        field.save_value();
+        assert_eq!(variable.get(), 725);
+    }
+
+    #[test]
+    fn it_restores_the_value_if_the_edition_is_canceled() {
+        let variable = Cell::new(50);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), true);
+        let keyboard = ScriptedKeyboard::new().key(KeyCode::KEY_9);
+        assert_screen(&mut field, keyboard, &["9050", ""]);
+        field.restore_value();
+        assert_screen(&mut field, ScriptedKeyboard::new(), &["0050", ""]);
+        assert_eq!(variable.get(), 50);
+    }
+
+    #[test]
+    fn it_shows_the_sign_and_the_decimal_point() {
+        let variable = Cell::new((-125_i16) as u16);
+        let format = Format {
+            decimal_places: 1,
+            is_signed: true,
+            ..Format::new(0, 1000)
+        };
+        let mut field = NumberInputEditorWidget::new(&variable, format, false);
+        assert_screen(&mut field, ScriptedKeyboard::new(), &["-012.5", ""]);
+    }
+
+    #[test]
+    fn it_toggles_the_sign_of_signed_numbers() {
+        let variable = Cell::new(125);
+        let format = Format {
+            is_signed: true,
+            ..Format::new(0, 1000)
+        };
+        let mut field = NumberInputEditorWidget::new(&variable, format, true);
+        let keyboard = ScriptedKeyboard::new().key(KeyCode::KEY_MAIS_OU_MENOS);
+        assert_screen(&mut field, keyboard, &["-0125", ""]);
+        field.save_value();
+        assert_eq!(variable.get(), (-125_i16) as u16);
+    }
+
+    #[test]
+    fn it_ignores_the_sign_key_in_unsigned_numbers() {
+        let variable = Cell::new(125);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), true);
+        let keyboard = ScriptedKeyboard::new().key(KeyCode::KEY_MAIS_OU_MENOS);
+        assert_screen(&mut field, keyboard, &["0125", ""]);
+        field.save_value();
+        assert_eq!(variable.get(), 125);
+    }
+
+    #[test]
+    fn it_jumps_to_the_decimal_places_with_the_point_key() {
+        let variable = Cell::new(125);
+        let format = Format {
+            decimal_places: 1,
+            ..Format::new(0, 1000)
+        };
+        let mut field = NumberInputEditorWidget::new(&variable, format, true);
+        let keyboard = ScriptedKeyboard::new()
+            .keys(&[KeyCode::KEY_PONTO, KeyCode::KEY_7])
+            .wait(500);
+        assert_screen(&mut field, keyboard, &["012.7", ""]);
+        field.save_value();
+        assert_eq!(variable.get(), 127);
+    }
+
+    #[test]
+    fn it_steps_the_hexadecimal_digit_under_the_cursor() {
+        let variable = Cell::new(0x00FF);
+        let format = Format {
+            radix: Radix::Hexadecimal,
+            ..Format::new(0, 0xFFFF)
+        };
+        let mut field = NumberInputEditorWidget::new(&variable, format, true);
+        // the digit wraps around the radix
+        let keyboard = ScriptedKeyboard::new().keys(&[
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+            KeyCode::KEY_DIRECIONAL_PARA_DIREITA,
+            KeyCode::KEY_DIRECIONAL_PARA_CIMA,
+            KeyCode::KEY_DIRECIONAL_PARA_CIMA,
+        ]);
+        assert_screen(&mut field, keyboard, &["F2FF", ""]);
+        field.save_value();
+        assert_eq!(variable.get(), 0xF2FF);
+    }
+
+    #[test]
+    fn it_does_not_step_decimal_digits() {
+        let variable = Cell::new(125);
+        let mut field = NumberInputEditorWidget::new(&variable, Format::new(0, 1000), true);
+        let keyboard = ScriptedKeyboard::new().key(KeyCode::KEY_DIRECIONAL_PARA_CIMA);
+        assert_screen(&mut field, keyboard, &["0125", ""]);
+    }
+
+    #[test]
+    fn it_masks_the_typed_digits() {
+        let variable = Cell::new(0);
+        let format = Format {
+            is_masked: true,
+            ..Format::new(0, 9999)
+        };
+        let mut field = NumberInputEditorWidget::new(&variable, format, true);
+        let keyboard = ScriptedKeyboard::new()
+            .keys(&[KeyCode::KEY_1, KeyCode::KEY_2])
+            .wait(500);
+        assert_screen(&mut field, keyboard, &["****", ""]);
+        field.save_value();
+        assert_eq!(variable.get(), 1200);
+    }
+}
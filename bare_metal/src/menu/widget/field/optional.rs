@@ -8,14 +8,15 @@ use crate::board::keypad::KeyCode;
 
 use crate::fatal_error;
 use crate::geometry::point::Point;
-use crate::menu::screen_buffer::ScreenBuffer;
+use crate::menu::screen_buffer::ScreenRegion;
 use crate::menu::widget::widget::Editable;
 use crate::microcontroler::ratangular_wave::RectangularWave;
 use crate::string::flash::FlashString;
+use cross_platform::types::time::Duration;
 use cross_platform::utils::cursor::Cursor;
 
 // Variable to place the string representing the Optinal Field Parameter in Menu
-pub type OptionsBuffer = Vec<FlashString, 3>;
+pub type OptionsBuffer = Vec<FlashString, 4>;
 
 /// TODO: If possible make this function unnecessary and remove it from code. I suppose I'm using
 /// it to avoid spread of some `lifetimes`, but I'm not sure it is the best decision.
@@ -42,8 +43,8 @@ pub struct OptionEditorWidget<'a> {
 
 impl<'a> OptionEditorWidget<'a> {
     pub fn new(variable: &'a Cell<Cursor>, options: OptionsBuffer, is_in_edit_mode_: bool) -> Self {
-        const T_ON: u16 = 600;
-        const T_OFF: u16 = 300;
+        const T_ON: Duration = Duration::from_millis(600);
+        const T_OFF: Duration = Duration::from_millis(300);
         let initial_value = variable.get();
         Self {
             options: options.clone(),
@@ -57,7 +58,7 @@ impl<'a> OptionEditorWidget<'a> {
     /// helper function
     fn blinks_char_if_in_editing_mode(
         &self,
-        screen_buffer: &mut ScreenBuffer,
+        region: &mut ScreenRegion,
         char: char,
         is_in_editing_mode: bool,
     ) {
@@ -65,13 +66,13 @@ impl<'a> OptionEditorWidget<'a> {
         if is_in_editing_mode {
             //blinks
             if self.blink.read() {
-                screen_buffer.print_char(char);
+                region.print_char(char);
             } else {
-                screen_buffer.print_char(EMPTY_CHAR);
+                region.print_char(EMPTY_CHAR);
             }
         } else {
             //do not blink
-            screen_buffer.print_char(char);
+            region.print_char(char);
         }
     }
 }
@@ -124,15 +125,82 @@ impl Widget for OptionEditorWidget<'_> {
         self.blink.update();
     }
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, start_point: Point) {
-        screen_buffer.set_cursor(start_point);
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.set_cursor(Point::new(0, 0));
         const OPEN_BRACKETS: char = '[';
         const CLOSE_BRACKETS: char = ']';
         let is_in_editing_mode = self.is_in_edit_mode();
         let current_index = self.editing_selection.get_current();
-        self.blinks_char_if_in_editing_mode(screen_buffer, OPEN_BRACKETS, is_in_editing_mode);
+        self.blinks_char_if_in_editing_mode(region, OPEN_BRACKETS, is_in_editing_mode);
         let flash_string = self.options[current_index as usize];
-        screen_buffer.print(flash_string);
-        self.blinks_char_if_in_editing_mode(screen_buffer, CLOSE_BRACKETS, is_in_editing_mode);
+        region.print(flash_string);
+        self.blinks_char_if_in_editing_mode(region, CLOSE_BRACKETS, is_in_editing_mode);
+    }
+}
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::snapshot::assert_screen;
+    use avr_progmem::progmem;
+
+    progmem! {
+        static progmem string DESLIGADO = "Desligado";
+        static progmem string LIGADO = "Ligado";
+    }
+
+    fn make_options() -> OptionsBuffer {
+        make_options_buffer_from_array([FlashString::new(&DESLIGADO), FlashString::new(&LIGADO)])
+    }
+
+    #[test]
+    fn it_shows_the_selected_option() {
+        let variable = Cell::new(Cursor::new(0, 2, 1));
+        let mut field = OptionEditorWidget::new(&variable, make_options(), false);
+        assert_screen(&mut field, ScriptedKeyboard::new(), &["[Ligado]", ""]);
+    }
+
+    #[test]
+    fn it_blinks_the_brackets_in_edit_mode() {
+        let variable = Cell::new(Cursor::new(0, 2, 0));
+        let mut field = OptionEditorWidget::new(&variable, make_options(), true);
+        // the brackets are shown during 600ms and hidden during 300ms
+        assert_screen(
+            &mut field,
+            ScriptedKeyboard::new().wait(500),
+            &["[Desligado]", ""],
+        );
+        assert_screen(
+            &mut field,
+            ScriptedKeyboard::new().wait(200),
+            &[" Desligado ", ""],
+        );
+    }
+
+    #[test]
+    fn it_selects_the_options_wrapping_around() {
+        let variable = Cell::new(Cursor::new(0, 2, 0));
+        let mut field = OptionEditorWidget::new(&variable, make_options(), true);
+        // the keys are typed while the brackets are shown
+        let keyboard = ScriptedKeyboard::new().key_after(150, KeyCode::KEY_DIRECIONAL_PARA_DIREITA);
+        assert_screen(&mut field, keyboard, &["[Ligado]", ""]);
+        let keyboard = ScriptedKeyboard::new().key_after(150, KeyCode::KEY_SETA_BRANCA_DIREITA);
+        assert_screen(&mut field, keyboard, &["[Desligado]", ""]);
+        let keyboard =
+            ScriptedKeyboard::new().key_after(150, KeyCode::KEY_DIRECIONAL_PARA_ESQUERDA);
+        assert_screen(&mut field, keyboard, &["[Ligado]", ""]);
+    }
+
+    #[test]
+    fn it_saves_or_restores_the_selection() {
+        let variable = Cell::new(Cursor::new(0, 2, 0));
+        let mut field = OptionEditorWidget::new(&variable, make_options(), true);
+        field.send_key(KeyCode::KEY_DIRECIONAL_PARA_DIREITA);
+        field.restore_value();
+        assert_eq!(variable.get().get_current(), 0);
+        field.send_key(KeyCode::KEY_DIRECIONAL_PARA_DIREITA);
+        field.save_value();
+        assert_eq!(variable.get().get_current(), 1);
     }
 }
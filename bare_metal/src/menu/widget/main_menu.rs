@@ -1,21 +1,14 @@
 use super::{
-    execucao::MenuExecucaoControler,
-    manual_mode::{ManualModeMenuControler, ManualModeState},
-    splash::{send_all_and_show_user_info_on_screen, show_communication_error_message},
-    submenu_programa::menu_programa_controler::MenuProgramaControler,
+    navigation::{Navigation, ReturnValue, Screen, ScreenId},
+    upload::ProgramUpload,
     widget::Widget,
 };
-use crate::{
-    board::front_panel::FrontPanel,
-    menu::model::{send_all, CmppData},
-};
 
 use crate::geometry::point::Point;
 use crate::string::flash::FlashString;
 use crate::{
-    board::{keypad::KeyCode, lcd},
-    menu::{model::DataModel, screen_buffer::ScreenBuffer},
-    microcontroler::delay::delay_ms,
+    board::keypad::KeyCode,
+    menu::{model::DataModel, screen_buffer::ScreenRegion},
 };
 use avr_progmem::progmem;
 use cross_platform::protocol::transport::transport_layer::TransportLayer;
@@ -26,152 +19,146 @@ progmem! {
     static progmem string LINE1 = "MANUAL    EXECUCAO    PROGRAMA";
 }
 
-#[derive(PartialEq, Clone, Copy)]
-pub enum State {
-    MainMenu,
-    Manual,
-    Execucao,
-    Programa,
-}
-
-pub struct MainMenu<'a, F: FrontPanel> {
-    current_state: State,
-    menu_manual_controler: ManualModeMenuControler<'a>,
-    menu_execucao_controler: MenuExecucaoControler<'a>,
-    menu_programa_controler: MenuProgramaControler<'a>,
+/// Lets the user choose the mode: manual, execucao or programa
+pub struct MainMenu<'a> {
     transport_x: &'a TransportLayer<'a>,
     transport_y: &'a TransportLayer<'a>,
     model: &'a DataModel,
-    //TODO: We're just controling 3 Leds (Execucao, Manual, Programa), better would be to wrap
-    //the type 'FrontPanel' into an abstract class.
-    front_panel_leds: &'a mut F,
+    /// Upload of the programs to the cmpp boards, while it is running it takes the whole screen
+    upload: Option<ProgramUpload<'a>>,
+    navigation: Option<Navigation>,
 }
 
-impl<'a, F: FrontPanel> MainMenu<'a, F> {
+impl<'a> MainMenu<'a> {
     pub fn new(
-        menu_manual_controler: ManualModeMenuControler<'a>,
-        menu_execucao_controler: MenuExecucaoControler<'a>,
-        menu_programa_controler: MenuProgramaControler<'a>,
         transport_x: &'a TransportLayer<'a>,
         transport_y: &'a TransportLayer<'a>,
         model: &'a DataModel,
-        front_panel_leds: &'a mut F,
     ) -> Self {
         Self {
-            current_state: State::MainMenu,
-            menu_manual_controler,
-            menu_execucao_controler,
-            menu_programa_controler,
             transport_x,
             transport_y,
             model,
-            front_panel_leds,
+            upload: None,
+            navigation: None,
         }
     }
 
-    fn draw_main_menu(&self, screen_buffer: &mut ScreenBuffer) {
-        fn get_line_helper(line_number: u8) -> (Point, FlashString) {
-            let line0 = FlashString::new(&LINE0);
-            let line1 = FlashString::new(&LINE1);
-            let col0 = ((40 - line0.len()) / 2).try_into().unwrap_or(0);
-            let col1 = ((40 - line1.len()) / 2).try_into().unwrap_or(0);
-            if line_number == 0 {
-                (Point::new(col0, 0), line0)
-            } else {
-                (Point::new(col1, 1), line1)
-            }
-        }
-
-        screen_buffer.clear();
-        for line_number in 0..2 {
-            let (point, flash_string) = get_line_helper(line_number);
-            screen_buffer.set_cursor(point);
-            screen_buffer.print(flash_string);
+    fn draw_main_menu(&self, region: &mut ScreenRegion) {
+        region.clear();
+        let lines = [FlashString::new(&LINE0), FlashString::new(&LINE1)];
+        for (line_number, flash_string) in lines.into_iter().enumerate() {
+            let col = region.get_centered_column(flash_string.len());
+            region.set_cursor(Point::new(col, line_number as u8));
+            region.print(flash_string);
         }
     }
 }
 
-impl<'a, F: FrontPanel> Widget for MainMenu<'a, F> {
+impl<'a> Widget for MainMenu<'a> {
     fn send_key(&mut self, key: KeyCode) {
-        match self.current_state {
-            State::MainMenu => match key {
-                KeyCode::KEY_MANUAL => {
-                    self.front_panel_leds.LED_MANUAL(true);
-                    self.current_state = State::Manual;
-                }
-                KeyCode::KEY_EXECUCAO => {
-                    self.front_panel_leds.LED_EXECUCAO(true);
-                    self.current_state = State::Execucao;
-                }
-                KeyCode::KEY_PROGRAMA => {
-                    self.front_panel_leds.LED_PROGRAMA(true);
-                    self.current_state = State::Programa;
-                }
-                _ => {}
-            },
-            State::Manual => {
-                self.menu_manual_controler.send_key(key);
-            }
-            State::Execucao => {
-                if key == KeyCode::KEY_ESC {
-                    self.current_state = State::MainMenu;
-                } else {
-                    self.menu_execucao_controler.send_key(key)
-                }
-            }
-            State::Programa => {
-                self.menu_programa_controler.send_key(key) // TODO: How can I do to return from `menu programa`
-            }
+        if let Some(upload) = &mut self.upload {
+            upload.send_key(key);
+            return;
         }
+        let screen = match key {
+            KeyCode::KEY_MANUAL => ScreenId::Manual,
+            KeyCode::KEY_EXECUCAO => ScreenId::Execucao,
+            KeyCode::KEY_PROGRAMA => ScreenId::Programa,
+            _ => return,
+        };
+        self.navigation = Some(Navigation::Push(screen));
     }
 
     fn update(&mut self) {
-        match self.current_state {
-            State::MainMenu => {
-                // Reset frontend leds
-                self.front_panel_leds.LED_MANUAL(false);
-                self.front_panel_leds.LED_EXECUCAO(false);
-                self.front_panel_leds.LED_PROGRAMA(false);
+        if let Some(upload) = &mut self.upload {
+            upload.update();
+            if upload.get_result().is_some() {
+                self.upload = None;
             }
+        }
+    }
 
-            State::Manual => {
-                if self.menu_manual_controler.current_state == ManualModeState::Resting {
-                    self.menu_manual_controler.current_state = ManualModeState::FirstScreen; // reset state
-                    self.current_state = State::MainMenu
-                } else {
-                    self.menu_manual_controler.update()
-                }
-            }
-            State::Execucao => self.menu_execucao_controler.update(),
-            State::Programa => {
-                if self.menu_programa_controler.must_return_to_main_menu {
-                    self.current_state = State::MainMenu;
-                    self.menu_programa_controler.must_return_to_main_menu = false;
-                    send_all_and_show_user_info_on_screen(
-                        self.model,
-                        self.transport_x,
-                        self.transport_y,
-                    );
-
-                    // saves data into the eeprom
-                    self.model.save_to_eeprom();
-                } else {
-                    self.menu_programa_controler.update()
-                }
-            }
+    fn draw(&self, region: &mut ScreenRegion) {
+        match &self.upload {
+            Some(upload) => upload.draw(region),
+            None => self.draw_main_menu(region),
         }
     }
+}
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, start_point: Point) {
-        match self.current_state {
-            State::MainMenu => self.draw_main_menu(screen_buffer),
-            State::Manual => self.menu_manual_controler.draw(screen_buffer, start_point),
-            State::Execucao => self
-                .menu_execucao_controler
-                .draw(screen_buffer, start_point),
-            State::Programa => self
-                .menu_programa_controler
-                .draw(screen_buffer, start_point),
+impl<'a> Screen for MainMenu<'a> {
+    fn take_navigation(&mut self) -> Option<Navigation> {
+        self.navigation.take()
+    }
+
+    fn on_return(&mut self, _from: ScreenId, value: ReturnValue) {
+        // the changes were already saved into the eeprom by the menu, if nothing changed there
+        // is no need to send the data to the boards again
+        if value == ReturnValue::ProgramasAlterados {
+            self.upload = Some(ProgramUpload::new(
+                self.model,
+                self.transport_x,
+                self.transport_y,
+            ));
         }
     }
 }
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::snapshot::{
+        assert_screen, make_disconnected_datalink, make_mechanical_properties,
+    };
+
+    #[test]
+    fn it_shows_the_modes_centered() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut main_menu = MainMenu::new(&transport, &transport, &model);
+        assert_screen(
+            &mut main_menu,
+            ScriptedKeyboard::new(),
+            &[
+                " Selecione modo de programacao desejado",
+                "     MANUAL    EXECUCAO    PROGRAMA",
+            ],
+        );
+    }
+
+    #[test]
+    fn it_opens_the_mode_of_the_key() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut main_menu = MainMenu::new(&transport, &transport, &model);
+        main_menu.send_key(KeyCode::KEY_EXECUCAO);
+        assert_eq!(
+            main_menu.take_navigation(),
+            Some(Navigation::Push(ScreenId::Execucao))
+        );
+        // the navigation is requested just once
+        assert_eq!(main_menu.take_navigation(), None);
+    }
+
+    #[test]
+    fn it_ignores_the_other_keys() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut main_menu = MainMenu::new(&transport, &transport, &model);
+        let keyboard = ScriptedKeyboard::new().keys(&[KeyCode::KEY_ENTER, KeyCode::KEY_5]);
+        assert_screen(
+            &mut main_menu,
+            keyboard,
+            &[
+                " Selecione modo de programacao desejado",
+                "     MANUAL    EXECUCAO    PROGRAMA",
+            ],
+        );
+        assert_eq!(main_menu.take_navigation(), None);
+    }
+}
@@ -3,10 +3,13 @@ use cross_platform::protocol::transport::transport_layer::{
     new_proposal::ActivationState, TransportLayer,
 };
 
-use super::widget::Widget;
+use super::{
+    navigation::{Navigation, ReturnValue, Screen},
+    widget::Widget,
+};
 use crate::geometry::point::Point;
 use crate::string::flash::FlashString;
-use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenBuffer};
+use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenRegion};
 progmem! {
     //                            1234567890123456789012345678901234567890
     static progmem string LINE0 = "Aperte qualquer tecla para entrar";
@@ -18,14 +21,14 @@ progmem! {
 
 #[derive(PartialEq, Copy, Clone)]
 pub enum ManualModeState {
-    Resting,     // client is responsible to change from Rsting to FirstScreen state
     FirstScreen, // start screen
-    LastScreen,  // Server is responsible to changes to Resting state
+    LastScreen,  // any key goes back
 }
 
 pub struct ManualModeMenuControler<'a> {
-    pub current_state: ManualModeState,
+    current_state: ManualModeState,
     transport: &'a TransportLayer<'a>,
+    navigation: Option<Navigation>,
 }
 
 impl<'a> ManualModeMenuControler<'a> {
@@ -33,6 +36,7 @@ impl<'a> ManualModeMenuControler<'a> {
         Self {
             current_state: ManualModeState::FirstScreen,
             transport,
+            navigation: None,
         }
     }
 }
@@ -40,37 +44,36 @@ impl<'a> ManualModeMenuControler<'a> {
 impl Widget for ManualModeMenuControler<'_> {
     fn send_key(&mut self, key: KeyCode) {
         match self.current_state {
-            ManualModeState::Resting => {}
             ManualModeState::FirstScreen => {
                 if key == KeyCode::KEY_ESC {
                     //Esc pressed, then go back
-                    self.current_state = ManualModeState::Resting;
+                    self.navigation = Some(Navigation::Pop(ReturnValue::Nothing));
                 } else {
                     //else continue
                     self.current_state = ManualModeState::LastScreen;
                 }
             }
             ManualModeState::LastScreen => {
-                self.current_state = ManualModeState::Resting;
+                self.navigation = Some(Navigation::Pop(ReturnValue::Nothing));
             }
         }
     }
 
     fn update(&mut self) {}
 
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, _start_point: Point) {
+    fn draw(&self, region: &mut ScreenRegion) {
         if self.current_state == ManualModeState::FirstScreen {
-            screen_buffer.clear();
-            screen_buffer.set_cursor(Point::new(0, 0));
-            screen_buffer.print(FlashString::new(&LINE0));
-            screen_buffer.set_cursor(Point::new(0, 1));
-            screen_buffer.print(FlashString::new(&LINE1));
+            region.clear();
+            region.set_cursor(Point::new(0, 0));
+            region.print(FlashString::new(&LINE0));
+            region.set_cursor(Point::new(0, 1));
+            region.print(FlashString::new(&LINE1));
         } else if self.current_state == ManualModeState::LastScreen {
-            screen_buffer.clear();
-            screen_buffer.set_cursor(Point::new(0, 0));
-            screen_buffer.print(FlashString::new(&LINE2));
-            screen_buffer.set_cursor(Point::new(0, 1));
-            screen_buffer.print(FlashString::new(&LINE3));
+            region.clear();
+            region.set_cursor(Point::new(0, 0));
+            region.print(FlashString::new(&LINE2));
+            region.set_cursor(Point::new(0, 1));
+            region.print(FlashString::new(&LINE3));
             //TODO: below effect should be in `update` and not in `draw` method
             //TODO: The use of `.unwrap` method is provoking flash size explosion, check why
             let _unwrap = self.transport.stop_serial().set(ActivationState::Activated);
@@ -81,3 +84,13 @@ impl Widget for ManualModeMenuControler<'_> {
         }
     }
 }
+
+impl Screen for ManualModeMenuControler<'_> {
+    fn take_navigation(&mut self) -> Option<Navigation> {
+        self.navigation.take()
+    }
+
+    fn on_enter(&mut self) {
+        self.current_state = ManualModeState::FirstScreen;
+    }
+}
@@ -1,10 +1,13 @@
+use crate::board::lcd;
 use crate::geometry::point::Point1d;
-use crate::menu::widget::submenu_programa::spec::MenuProgramaAreanaSelector;
+use crate::menu::widget::submenu_programa::macros::{place_collunm, IS_NARROW_LAYOUT};
+use crate::menu::widget::submenu_programa::spec::{MenuProgramaAction, MenuProgramaAreanaSelector};
 use crate::string::flash::FlashString;
 
 use super::{
     super::{field::field::Field, field::numerical::Format, field::optional::OptionsBuffer},
-    menu_item::MenuItemWidget,
+    menu_item::{FieldsBuffer, MenuItemWidget},
+    string_template_constructor::{make_template_iterator, parse_field_mask, TemplateKind},
 };
 use avr_progmem::string::PmString;
 use core::{cell::Cell, ops::Range};
@@ -26,6 +29,21 @@ pub struct NumericalParameter<'a> {
     pub unit_of_measurement_text: Option<(u8, FlashString)>, // (collunm_position, text)
 }
 
+/// A numerical parameter described by a template string, ie: `"Retardo no start ${n.nnn} seg"`.
+///
+/// The caption before the token is the name of the parameter, the token is the mask of the field
+/// (see: [`FieldMask`]) and the caption after it is the unit of measurement. Each char of the
+/// template takes one collunm of the line of the menu, except the delimiters of the token. The
+/// field and the unit are placed relative to the right edge of the lcd (see: [`place_collunm`]), in
+/// the narrower lcds they are aligned to the right edge of the line below the caption.
+///
+/// [`FieldMask`]: super::string_template_constructor::FieldMask
+pub struct NumericalTemplate<'a> {
+    pub template: FlashString,
+    pub variable: &'a Cell<u16>,
+    pub valid_range: Range<u16>,
+}
+
 pub struct OptionalParameter<'a> {
     pub parameter_name: FlashString,
     pub variable: (u8, &'a Cell<Cursor>), // (collunm_position, text)
@@ -43,6 +61,13 @@ pub struct SimpleMenuWithNumericalParameter<'a> {
     pub child_menu: MenuProgramaAreanaSelector,
 }
 
+/// A menu item with many fields (edited in sequence) that executes an action after the user confirmation
+pub struct ActionWithParameters<'a> {
+    pub parameter_name: FlashString,
+    pub fields: FieldsBuffer<'a>,
+    pub action: MenuProgramaAction,
+}
+
 impl MenuItemBuilder {
     /// collum to start to print menu_item text in the lcd
     const POINT1: Point1d = Point1d::new(1);
@@ -68,11 +93,7 @@ impl MenuItemBuilder {
         let point1 = Self::POINT1;
         let text = ctor.parameter_name;
         let point2 = ctor.variable.0.into();
-        let format = Format {
-            start: ctor.valid_range.start,
-            end: ctor.valid_range.end,
-            initial_cursor_position: 0,
-        };
+        let format = Format::new(ctor.valid_range.start, ctor.valid_range.end);
         let field = Field::from_numerical(ctor.variable.1, format);
         let unit_of_measurement_label = ctor
             .unit_of_measurement_text
@@ -88,6 +109,59 @@ impl MenuItemBuilder {
         Self::wrap_value_for_convenience(menu_item)
     }
 
+    pub fn make_numerical_parameter_from_template(
+        ctor: NumericalTemplate,
+    ) -> Option<MenuItemWidget> {
+        // prepare
+        let mut collunm = Self::POINT1.pos;
+        let minimum = Self::POINT1.pos as usize;
+        let place = |collunm: u8| Point1d::new(place_collunm(collunm as usize, minimum));
+        let mut parameter_name = (Self::POINT1, ctor.template.sub_string(0..0));
+        let mut field = None;
+        let mut field_width = 0;
+        let mut unit_of_measurement_label = None;
+        for part in make_template_iterator(ctor.template) {
+            match part {
+                TemplateKind::Field(mask) => {
+                    let (start, end) = (ctor.valid_range.start, ctor.valid_range.end);
+                    let format = parse_field_mask(mask).make_format(start, end);
+                    let numerical = Field::from_numerical(ctor.variable, format);
+                    field = Some((place(collunm), numerical));
+                    field_width = mask.len();
+                    collunm += field_width;
+                }
+                // an ill formed token is shown as it is, so the mistake is visible in the lcd
+                TemplateKind::Caption(text) | TemplateKind::IllFormed(text) => {
+                    if field.is_none() {
+                        parameter_name = (Point1d::new(collunm), text);
+                    } else {
+                        unit_of_measurement_label = Some((place(collunm), text));
+                    }
+                    collunm += text.len();
+                }
+            }
+        }
+        // in the narrower lcds the caption has its own line, so the field and the unit are aligned
+        // to the right edge of the line below it
+        if IS_NARROW_LAYOUT {
+            let unit_width = unit_of_measurement_label
+                .as_ref()
+                .map_or(0, |(_, text): &(Point1d, FlashString)| text.len());
+            let unit_start = lcd::NUMBER_OF_COLS.saturating_sub(unit_width);
+            if let Some((start, _)) = &mut unit_of_measurement_label {
+                *start = Point1d::new(unit_start);
+            }
+            if let Some((start, _)) = &mut field {
+                *start = Point1d::new(unit_start.saturating_sub(field_width));
+            }
+        }
+        let child = None;
+        // build
+        let menu_item =
+            MenuItemWidget::new(parameter_name, field, child, unit_of_measurement_label);
+        Self::wrap_value_for_convenience(menu_item)
+    }
+
     pub fn make_optional_parameter(ctor: OptionalParameter) -> Option<MenuItemWidget> {
         // prepare
         let point1 = Self::POINT1;
@@ -112,11 +186,7 @@ impl MenuItemBuilder {
         let point1 = Self::POINT1;
         let text = ctor.parameter_name;
         let point2 = ctor.variable.0.into();
-        let format = Format {
-            start: ctor.valid_range.start,
-            end: ctor.valid_range.end,
-            initial_cursor_position: 0,
-        };
+        let format = Format::new(ctor.valid_range.start, ctor.valid_range.end);
         let field = Field::from_numerical(ctor.variable.1, format);
         let unit_of_measurement_label = ctor
             .unit_of_measurement_text
@@ -131,4 +201,16 @@ impl MenuItemBuilder {
         );
         Self::wrap_value_for_convenience(menu_item)
     }
+
+    pub fn make_action_with_parameters(ctor: ActionWithParameters) -> Option<MenuItemWidget> {
+        // prepare
+        let point1 = Self::POINT1;
+        let text = ctor.parameter_name;
+        let child = None;
+        let action = Some(ctor.action);
+        // build
+        let menu_item =
+            MenuItemWidget::new_with_fields((point1, text), ctor.fields, child, action, None);
+        Self::wrap_value_for_convenience(menu_item)
+    }
 }
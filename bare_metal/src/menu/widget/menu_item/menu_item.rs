@@ -1,14 +1,15 @@
 use crate::geometry::point::{Point, Point1d};
-use crate::menu::widget::submenu_programa::spec::MenuProgramaAreanaSelector;
+use crate::geometry::rectangle::Rectangle;
+use crate::menu::widget::submenu_programa::macros::LINES_PER_MENU_ITEM;
+use crate::menu::widget::submenu_programa::spec::{MenuProgramaAction, MenuProgramaAreanaSelector};
 use crate::{
     board::keypad::KeyCode,
-    menu::{
-        screen_buffer::ScreenBuffer,
-        widget::{caption::Caption, submenu_programa::hepers::LcdLine},
-    },
+    menu::{screen_buffer::ScreenRegion, widget::caption::Caption},
 };
 
+use crate::fatal_error;
 use crate::string::flash::FlashString;
+use heapless::Vec;
 
 use super::super::{
     field::field::Field,
@@ -18,11 +19,24 @@ use super::super::{
 
 //
 
+/// Max number of fields that a single menu item can contain (ie: "Copiar programa [X]:## para [Y]:##")
+/// TODO: Each mounted menu item reserves memory for all the fields, consider to reduce this value if
+/// RAM becomes a problem.
+pub const MAX_NUMBER_OF_FIELDS: usize = 4;
+
+pub type FieldsBuffer<'a> = Vec<(Point1d, Field<'a>), MAX_NUMBER_OF_FIELDS>;
+
+/// Line of the region of the menu item where the fields and the unit of measurement are drawn, in
+/// the narrower lcds it is the line below the caption.
+const VALUE_LINE: u8 = LINES_PER_MENU_ITEM - 1;
+
 pub struct MenuItemWidget<'a> {
     point_and_caption: (Point1d, Caption),
-    //TODO: Check if is it possible to remove 'pub' visibility in below line
-    pub point_and_field: Option<(Point1d, Field<'a>)>,
+    /// Fields are edited in sequence, ENTER saves the current field and starts the edition of the next one.
+    fields: FieldsBuffer<'a>,
     pub child: Option<MenuProgramaAreanaSelector>,
+    /// Action executed (after user confirmation) when the edition of all fields is finished
+    pub action: Option<MenuProgramaAction>,
     unit_of_measurement: Option<(Point1d, Caption)>,
 }
 
@@ -34,6 +48,21 @@ impl<'a> MenuItemWidget<'a> {
         point_and_field: Option<(Point1d, Field<'a>)>,
         child: Option<MenuProgramaAreanaSelector>,
         unit_of_measurement: Option<(Point1d, FlashString)>,
+    ) -> Self {
+        let mut fields = FieldsBuffer::new();
+        if let Some(point_and_field) = point_and_field {
+            Self::push_field(&mut fields, point_and_field);
+        }
+        Self::new_with_fields(point_and_text, fields, child, None, unit_of_measurement)
+    }
+
+    /// Constructs a menu item with many fields and (optionally) an action.
+    pub fn new_with_fields(
+        point_and_text: (Point1d, FlashString),
+        fields: FieldsBuffer<'a>,
+        child: Option<MenuProgramaAreanaSelector>,
+        action: Option<MenuProgramaAction>,
+        unit_of_measurement: Option<(Point1d, FlashString)>,
     ) -> Self {
         let (point_a, text) = point_and_text;
         let unit_of_measurement = if let Some((point3, uom)) = unit_of_measurement {
@@ -43,87 +72,168 @@ impl<'a> MenuItemWidget<'a> {
         };
         Self {
             point_and_caption: (point_a, Caption::new(text)),
-            point_and_field,
+            fields,
             child,
+            action,
             unit_of_measurement,
         }
     }
+
+    /// Prints the given suffix after the caption, ie: the letter of the axis
+    pub fn with_caption_suffix(mut self, suffix: FlashString) -> Self {
+        let (point, caption) = self.point_and_caption;
+        self.point_and_caption = (point, caption.with_suffix(suffix));
+        self
+    }
+
+    pub fn push_field(fields: &mut FieldsBuffer<'a>, point_and_field: (Point1d, Field<'a>)) {
+        if let Err(_) = fields.push(point_and_field) {
+            // Error: Vector size not enough. Change 'MAX_NUMBER_OF_FIELDS' to a higher value.
+            fatal_error!(109);
+        }
+    }
+
+    pub fn has_field(&self) -> bool {
+        !self.fields.is_empty()
+    }
+
+    /// Index of the field being edited, if any
+    fn get_field_being_edited(&self) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|(_, field)| field.is_in_edit_mode())
+    }
 }
 
 impl Saveble for MenuItemWidget<'_> {
     fn restore_value(&mut self) {
         self.set_edit_mode(false); // terminate the edition
-        let Some((_, field)) = &mut self.point_and_field else { return();};
-        field.restore_value();
+        for (_, field) in self.fields.iter_mut() {
+            field.restore_value();
+        }
     }
 
     fn save_value(&mut self) {
         self.set_edit_mode(false); // terminate the edition
-        let Some((_, field)) = &mut self.point_and_field else { return(); };
-        field.save_value();
+        for (_, field) in self.fields.iter_mut() {
+            field.save_value();
+        }
     }
 }
 
 impl MenuItemWidget<'_> {
     pub fn send_key(&mut self, key: KeyCode) {
-        if self.is_in_edit_mode() {
-            match key {
-                // cancel edition
-                KeyCode::KEY_ESC => {
-                    self.restore_value();
-                }
+        let Some(index) = self.get_field_being_edited() else {
+            return ();
+        };
+        match key {
+            // cancel edition
+            KeyCode::KEY_ESC => {
+                self.restore_value();
+            }
 
-                // saves edition
-                KeyCode::KEY_ENTER => {
-                    self.save_value();
+            // saves current field and go to the next one (if it exists)
+            KeyCode::KEY_ENTER => {
+                if let Some((_, field)) = self.fields.get_mut(index) {
+                    field.save_value();
+                    field.set_edit_mode(false);
                 }
-
-                //delegate everything else to the field
-                _ => {
-                    if let Some((_, field)) = &mut self.point_and_field {
-                        field.send_key(key);
-                    };
+                if let Some((_, next_field)) = self.fields.get_mut(index + 1) {
+                    next_field.set_edit_mode(true);
                 }
-            };
-        }
+            }
+
+            //delegate everything else to the field
+            _ => {
+                if let Some((_, field)) = self.fields.get_mut(index) {
+                    field.send_key(key);
+                };
+            }
+        };
     }
 
     pub fn update(&mut self) {
         let (_, caption) = &mut self.point_and_caption;
         caption.update();
-        if let Some((_, field)) = &mut self.point_and_field {
+        for (_, field) in self.fields.iter_mut() {
             field.update();
-        };
+        }
     }
 
-    pub fn draw(&self, screen_buffer: &mut ScreenBuffer, lcd_line: LcdLine) {
-        let line = lcd_line as u8;
-        let (point1, caption) = &self.point_and_caption;
-        let point1: Point<u8> = Point::new(point1.pos, line);
-        caption.draw(screen_buffer, point1);
-        if let Some((point2, field)) = &self.point_and_field {
-            let point2: Point<u8> = Point::new(point2.pos, line);
-            field.draw(screen_buffer, point2);
-        };
+    /// Positions where the caption, the fields and the unit of measurement start
+    fn get_start_positions(&self) -> impl Iterator<Item = u8> + '_ {
+        let (caption_position, _) = self.point_and_caption;
+        let fields_positions = self.fields.iter().map(|(position, _)| position.pos);
+        let uom_position = self
+            .unit_of_measurement
+            .as_ref()
+            .map(|(position, _)| position.pos);
+        [caption_position.pos]
+            .into_iter()
+            .chain(fields_positions)
+            .chain(uom_position)
+    }
+
+    /// Area of the value line (in the given region) reserved to the element starting in the given
+    /// position, it ends where the next element to the right starts.
+    fn get_area_of(&self, start: Point1d, region: &ScreenRegion) -> Rectangle {
+        let end = self
+            .get_start_positions()
+            .filter(|position| *position > start.pos)
+            .min()
+            .unwrap_or(region.width());
+        Rectangle::new(
+            Point::new(start.pos, VALUE_LINE),
+            Point::new(end, VALUE_LINE + 1),
+        )
+    }
+
+    /// Area of the first line (in the given region) reserved to the caption, it ends where the first
+    /// field (or the unit of measurement) starts, or at the end of the line if the caption has its
+    /// own line.
+    fn get_area_of_caption(&self, region: &ScreenRegion) -> Rectangle {
+        let (start, _) = self.point_and_caption;
+        let end = self
+            .get_start_positions()
+            .skip(1)
+            .filter(|position| VALUE_LINE == 0 && *position >= start.pos)
+            .min()
+            .unwrap_or(region.width());
+        Rectangle::new(Point::new(start.pos, 0), Point::new(end, 1))
+    }
+
+    /// Draws the menu item in the given region, the caption in its first line and the fields and the
+    /// unit of measurement in the value line (the same line, except in the narrower lcds). Each
+    /// element is clipped so it does not overwrite the element at its right.
+    pub fn draw(&self, region: &mut ScreenRegion) {
+        let (_, caption) = &self.point_and_caption;
+        let area = self.get_area_of_caption(region);
+        caption.draw(&mut region.sub_region(area));
+        for (point2, field) in self.fields.iter() {
+            let area = self.get_area_of(*point2, region);
+            field.draw(&mut region.sub_region(area));
+        }
         if let Some((point3, uom)) = &self.unit_of_measurement {
-            let point3: Point<u8> = Point::new(point3.pos, line);
-            uom.draw(screen_buffer, point3);
+            let area = self.get_area_of(*point3, region);
+            uom.draw(&mut region.sub_region(area));
         };
     }
 }
 
 impl MenuItemWidget<'_> {
+    /// Starts the edition in the first field, or terminates the edition of all fields.
     pub fn set_edit_mode(&mut self, value: bool) {
-        if let Some((_, field)) = &mut self.point_and_field {
-            field.set_edit_mode(value);
-        };
+        for (_, field) in self.fields.iter_mut() {
+            field.set_edit_mode(false);
+        }
+        if value {
+            if let Some((_, field)) = self.fields.first_mut() {
+                field.set_edit_mode(true);
+            }
+        }
     }
 
     pub fn is_in_edit_mode(&self) -> bool {
-        if let Some((_, field)) = &self.point_and_field {
-            field.is_in_edit_mode()
-        } else {
-            false
-        }
+        self.get_field_being_edited().is_some()
     }
 }
@@ -1,3 +1,4 @@
+use crate::menu::widget::field::numerical::{Format, Radix};
 use crate::string::flash::FlashString;
 
 use cross_platform::utils::numerical::usize_to_u8_clamper;
@@ -85,3 +86,142 @@ impl Iterator for FlashTemplateIterator {
         }
     }
 }
+
+/// Format of a numerical field described by the characters inside its token (for example: the
+/// `${+nnn.nn}` token describes a signed number with two decimal places).
+///
+/// Token characters (the Z80 mask convention of the original TTC3100):
+///
+/// * `n` or `#`: decimal digit
+/// * `@`: decimal digit or decimal point
+/// * `.`: decimal point, the digits after it are the decimal places
+/// * `$`: hexadecimal digit
+/// * `+` or `-`: signed number
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct FieldMask {
+    pub number_of_digits: u8,
+    pub decimal_places: u8,
+    pub is_signed: bool,
+    pub radix: Radix,
+}
+
+impl FieldMask {
+    /// Makes a [`Format`] with the given valid range, using the decimal places, sign and radix of the
+    /// mask.
+    pub fn make_format(&self, start: u16, end: u16) -> Format {
+        Format {
+            decimal_places: self.decimal_places,
+            is_signed: self.is_signed,
+            radix: self.radix,
+            ..Format::new(start, end)
+        }
+    }
+}
+
+/// Parses the characters of a field token (see: [`TemplateKind::Field`]), unknown characters are ignored.
+pub fn parse_field_mask(field: FlashString) -> FieldMask {
+    let mut mask = FieldMask {
+        number_of_digits: 0,
+        decimal_places: 0,
+        is_signed: false,
+        radix: Radix::Decimal,
+    };
+    let mut is_after_decimal_point = false;
+    for byte in field {
+        match byte as char {
+            'n' | '#' | '@' => {
+                mask.number_of_digits += 1;
+                if is_after_decimal_point {
+                    mask.decimal_places += 1;
+                }
+            }
+            '$' => {
+                mask.number_of_digits += 1;
+                mask.radix = Radix::Hexadecimal;
+            }
+            '.' => is_after_decimal_point = true,
+            '+' | '-' => mask.is_signed = true,
+            _ => {}
+        }
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use avr_progmem::progmem;
+
+    progmem! {
+        static progmem string TEMPLATE = "Retardo ${n.nnn} seg";
+        static progmem string ILL_FORMED = "Retardo ${n.nnn";
+        static progmem string SIGNED_DECIMAL = "+nnn.nn";
+        static progmem string HEXADECIMAL = "$$$$";
+        static progmem string DIGIT_OR_POINT = "@@.@";
+    }
+
+    fn to_string(text: FlashString) -> String {
+        text.into_iter().map(|byte| byte as char).collect()
+    }
+
+    fn parts_of(template: FlashString) -> Vec<String> {
+        make_template_iterator(template)
+            .map(|part| match part {
+                TemplateKind::Caption(text) => format!("Caption({})", to_string(text)),
+                TemplateKind::Field(text) => format!("Field({})", to_string(text)),
+                TemplateKind::IllFormed(text) => format!("IllFormed({})", to_string(text)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_splits_the_template_in_captions_and_fields() {
+        assert_eq!(
+            parts_of(FlashString::new(&TEMPLATE)),
+            ["Caption(Retardo )", "Field(n.nnn)", "Caption( seg)"]
+        );
+    }
+
+    #[test]
+    fn it_reports_the_token_which_is_not_closed() {
+        assert_eq!(
+            parts_of(FlashString::new(&ILL_FORMED)),
+            ["Caption(Retardo )", "IllFormed(${n.nnn)"]
+        );
+    }
+
+    #[test]
+    fn it_parses_the_signed_decimal_mask() {
+        let mask = parse_field_mask(FlashString::new(&SIGNED_DECIMAL));
+        let expected = FieldMask {
+            number_of_digits: 5,
+            decimal_places: 2,
+            is_signed: true,
+            radix: Radix::Decimal,
+        };
+        assert_eq!(mask, expected);
+    }
+
+    #[test]
+    fn it_parses_the_hexadecimal_mask() {
+        let mask = parse_field_mask(FlashString::new(&HEXADECIMAL));
+        let expected = FieldMask {
+            number_of_digits: 4,
+            decimal_places: 0,
+            is_signed: false,
+            radix: Radix::Hexadecimal,
+        };
+        assert_eq!(mask, expected);
+        let format = mask.make_format(0, 0xFFFF);
+        assert_eq!(format.radix, Radix::Hexadecimal);
+        assert_eq!(format.get_number_of_digits(), 4);
+    }
+
+    #[test]
+    fn it_counts_the_digits_after_the_point_as_decimal_places() {
+        let mask = parse_field_mask(FlashString::new(&DIGIT_OR_POINT));
+        assert_eq!((mask.number_of_digits, mask.decimal_places), (3, 1));
+        let format = mask.make_format(0, 999);
+        assert_eq!((format.decimal_places, format.is_signed), (1, false));
+    }
+}
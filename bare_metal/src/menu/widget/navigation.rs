@@ -0,0 +1,519 @@
+//! Navigation between the screens of the app (splash, main menu, manual, execucao and programa).
+//!
+//! The screens are stacked in a [`NavigationStack`], only the screen on the top of the stack
+//! receives the keys, is updated and is drawn. A screen does not know the others, to change the
+//! screen being shown it requests a [`Navigation`] which is applied by the [`Navigator`]:
+//!
+//! - [`Navigation::Push`] opens other screen on top of it, so any screen can be entered from
+//!   anywhere;
+//! - [`Navigation::Pop`] closes it and goes back to the screen below, giving it a [`ReturnValue`].
+//!   By convention `ESC` pops the screen;
+//! - [`Navigation::Replace`] exchanges it by other screen (ie: splash by main menu).
+//!
+//! Holding `ESC` goes back to the main menu, leaving the screens one by one (see
+//! [`Screen::go_home`]).
+
+use heapless::Vec;
+
+use super::{
+    execucao::MenuExecucaoControler, main_menu::MainMenu, manual_mode::ManualModeMenuControler,
+    splash::Splash, submenu_programa::menu_programa_controler::MenuProgramaControler,
+    widget::Widget,
+};
+use crate::{
+    board::{
+        keyboard::KeyEvent,
+        keypad::KeyCode,
+        panel_patterns::{PanelOutput, PanelPatterns},
+    },
+    fatal_error,
+    menu::{model::DataModel, screen_buffer::ScreenRegion},
+    microcontroler::timer::now_instant,
+};
+use cross_platform::types::time::{Duration, Instant};
+
+/// Time without any key pressed after which the access level falls back to `Operador`
+const TEMPO_DE_INATIVIDADE: Duration = Duration::from_secs(5 * 60);
+
+/// Identifies each screen of the app
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ScreenId {
+    Splash,
+    MainMenu,
+    Manual,
+    Execucao,
+    Programa,
+}
+
+/// Value given back by a screen to the screen below it when it is popped
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ReturnValue {
+    Nothing,
+    /// The programs were changed and must be sent to the cmpp boards
+    ProgramasAlterados,
+}
+
+/// Navigation requested by the screen on the top of the stack
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Navigation {
+    Push(ScreenId),
+    Pop(ReturnValue),
+    Replace(ScreenId),
+}
+
+/// A [`Widget`] which takes the whole display and can be stacked by the [`Navigator`]
+pub trait Screen: Widget {
+    /// Returns (and clears) the navigation requested by the screen, the navigator polls it after
+    /// each key and update.
+    fn take_navigation(&mut self) -> Option<Navigation>;
+
+    /// Called each time the screen is pushed or replaces other screen, it may reset its state
+    fn on_enter(&mut self) {}
+
+    /// Called when the screen above it is popped, with the value returned by that screen
+    fn on_return(&mut self, _from: ScreenId, _value: ReturnValue) {}
+
+    /// Called when the user holds `ESC` to go back to the main menu, the screen should request to
+    /// be popped. By default it is the same as pressing `ESC`.
+    fn go_home(&mut self) {
+        self.send_key(KeyCode::KEY_ESC)
+    }
+}
+
+// -----------------------------------------------------------
+//            NAVIGATION STACK
+// -----------------------------------------------------------
+
+/// Each screen can be opened just once, so the stack never holds more screens than this
+const NAVIGATION_STACK_CAPACITY: usize = 5;
+
+/// Stack of the screens opened by the user, the first screen is the root and it is never popped
+pub struct NavigationStack {
+    screens: Vec<ScreenId, NAVIGATION_STACK_CAPACITY>,
+}
+
+impl NavigationStack {
+    pub fn new(root: ScreenId) -> Self {
+        let mut screens = Vec::new();
+        // the stack is empty, so there is room for the root
+        let _ = screens.push(root);
+        Self { screens }
+    }
+
+    /// Screen being shown
+    pub fn top(&self) -> ScreenId {
+        match self.screens.last() {
+            Some(screen) => *screen,
+            // the root is never popped
+            None => fatal_error!(111),
+        }
+    }
+
+    pub fn contains(&self, screen: ScreenId) -> bool {
+        self.screens.contains(&screen)
+    }
+
+    pub fn push(&mut self, screen: ScreenId) {
+        if self.screens.push(screen).is_err() {
+            // ERROR DESCRIPTION: `NAVIGATION_STACK_CAPACITY` must be redimensioned to higher capacity.
+            fatal_error!(111)
+        }
+    }
+
+    /// Removes the screen on the top and returns it, if it is the root nothing is done and returns
+    /// `None`.
+    pub fn pop(&mut self) -> Option<ScreenId> {
+        if self.screens.len() > 1 {
+            self.screens.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Exchanges the screen on the top (which may be the root) by the given one
+    pub fn replace(&mut self, screen: ScreenId) {
+        self.screens.pop();
+        self.push(screen);
+    }
+}
+
+// -----------------------------------------------------------
+//            NAVIGATOR
+// -----------------------------------------------------------
+
+/// Owns all the screens of the app and shows the screen on the top of the [`NavigationStack`]
+pub struct Navigator<'a> {
+    stack: NavigationStack,
+    splash: Splash<'a>,
+    main_menu: MainMenu<'a>,
+    menu_manual_controler: ManualModeMenuControler<'a>,
+    menu_execucao_controler: MenuExecucaoControler<'a>,
+    menu_programa_controler: MenuProgramaControler<'a>,
+    model: &'a DataModel,
+    /// Leds of the modes (manual, execucao and programa)
+    panel_patterns: &'a PanelPatterns,
+    /// Time of the last key pressed by the user, used to detect inactivity
+    last_key_time: Instant,
+}
+
+impl<'a> Navigator<'a> {
+    /// The navigation starts in the splash screen
+    pub fn new(
+        splash: Splash<'a>,
+        main_menu: MainMenu<'a>,
+        menu_manual_controler: ManualModeMenuControler<'a>,
+        menu_execucao_controler: MenuExecucaoControler<'a>,
+        menu_programa_controler: MenuProgramaControler<'a>,
+        model: &'a DataModel,
+        panel_patterns: &'a PanelPatterns,
+    ) -> Self {
+        let mut navigator = Self {
+            stack: NavigationStack::new(ScreenId::Splash),
+            splash,
+            main_menu,
+            menu_manual_controler,
+            menu_execucao_controler,
+            menu_programa_controler,
+            model,
+            panel_patterns,
+            last_key_time: now_instant(),
+        };
+        navigator.enter(ScreenId::Splash);
+        navigator
+    }
+
+    fn get_screen(&self, screen: ScreenId) -> &dyn Screen {
+        match screen {
+            ScreenId::Splash => &self.splash,
+            ScreenId::MainMenu => &self.main_menu,
+            ScreenId::Manual => &self.menu_manual_controler,
+            ScreenId::Execucao => &self.menu_execucao_controler,
+            ScreenId::Programa => &self.menu_programa_controler,
+        }
+    }
+
+    fn get_screen_mut(&mut self, screen: ScreenId) -> &mut dyn Screen {
+        match screen {
+            ScreenId::Splash => &mut self.splash,
+            ScreenId::MainMenu => &mut self.main_menu,
+            ScreenId::Manual => &mut self.menu_manual_controler,
+            ScreenId::Execucao => &mut self.menu_execucao_controler,
+            ScreenId::Programa => &mut self.menu_programa_controler,
+        }
+    }
+
+    /// Lights the led of the mode being shown
+    fn update_leds(&mut self) {
+        let top = self.stack.top();
+        let leds = [
+            (PanelOutput::LedManual, ScreenId::Manual),
+            (PanelOutput::LedExecucao, ScreenId::Execucao),
+            (PanelOutput::LedPrograma, ScreenId::Programa),
+        ];
+        for (led, screen) in leds {
+            self.panel_patterns.set(led, top == screen);
+        }
+    }
+
+    fn enter(&mut self, screen: ScreenId) {
+        self.get_screen_mut(screen).on_enter();
+        self.update_leds();
+    }
+
+    /// Applies the navigation requested by the screen on the top of the stack, if any
+    fn navigate(&mut self) {
+        let top = self.stack.top();
+        if let Some(navigation) = self.get_screen_mut(top).take_navigation() {
+            self.apply(top, navigation);
+        }
+    }
+
+    /// Applies the navigation requested by the screen `top`, which is on the top of the stack
+    fn apply(&mut self, top: ScreenId, navigation: Navigation) {
+        match navigation {
+            Navigation::Push(screen) => {
+                // a screen cannot be opened twice, ie: manual -> main menu -> manual
+                if !self.stack.contains(screen) {
+                    self.stack.push(screen);
+                    self.enter(screen);
+                }
+            }
+            Navigation::Pop(value) => {
+                if self.stack.pop().is_some() {
+                    let below = self.stack.top();
+                    self.get_screen_mut(below).on_return(top, value);
+                    self.update_leds();
+                }
+            }
+            Navigation::Replace(screen) => {
+                if !self.stack.contains(screen) {
+                    self.stack.replace(screen);
+                    self.enter(screen);
+                }
+            }
+        }
+    }
+
+    /// Leaves the screens one by one until the main menu. It stops in the screen which does not
+    /// leave, ie: because it asks the user to save the changes.
+    fn go_home(&mut self) {
+        loop {
+            let top = self.stack.top();
+            if top == ScreenId::MainMenu || top == ScreenId::Splash {
+                return;
+            }
+            self.get_screen_mut(top).go_home();
+            self.navigate();
+            if self.stack.top() == top {
+                return;
+            }
+        }
+    }
+}
+
+impl<'a> Widget for Navigator<'a> {
+    fn send_key(&mut self, key: KeyCode) {
+        self.last_key_time = now_instant();
+        let top = self.stack.top();
+        self.get_screen_mut(top).send_key(key);
+        self.navigate();
+    }
+
+    /// Holding `ESC` is handled by the navigator, the other events (keys pressed, long presses and
+    /// chords) are given to the screen on the top of the stack.
+    fn send_key_event(&mut self, event: KeyEvent) {
+        self.last_key_time = now_instant();
+        match event {
+            KeyEvent::LongPress(KeyCode::KEY_ESC) => self.go_home(),
+            _ => {
+                let top = self.stack.top();
+                self.get_screen_mut(top).send_key_event(event);
+                self.navigate();
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        // protects the equipment if the user forgets to leave a privileged access level
+        if now_instant().duration_since(self.last_key_time) > TEMPO_DE_INATIVIDADE {
+            self.model.retornar_ao_nivel_de_operador();
+        }
+
+        let top = self.stack.top();
+        self.get_screen_mut(top).update();
+        self.navigate();
+    }
+
+    fn draw(&self, region: &mut ScreenRegion) {
+        self.get_screen(self.stack.top()).draw(region);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::host::front_panel::FrontPanelRecorder,
+        menu::{
+            model::NivelDeAcesso,
+            snapshot::{make_disconnected_datalink, make_mechanical_properties},
+            widget::submenu_programa::spec::{MenuProgramaAreanaSelector, MenuProgramaArena},
+        },
+        microcontroler::timer::advance_virtual_clock,
+    };
+    use cross_platform::protocol::transport::transport_layer::TransportLayer;
+
+    /// Builds the navigator as the app does and leaves the splash screen, the menu programa starts
+    /// in the given submenu.
+    fn make_navigator<'a>(
+        transport: &'a TransportLayer<'a>,
+        model: &'a DataModel,
+        arena: &'a MenuProgramaArena<'a>,
+        panel_patterns: &'a PanelPatterns,
+        initial_menu_selector: MenuProgramaAreanaSelector,
+    ) -> Navigator<'a> {
+        let mut navigator = Navigator::new(
+            Splash::new(model, transport, transport),
+            MainMenu::new(transport, transport, model),
+            ManualModeMenuControler::new(transport),
+            MenuExecucaoControler::new(transport, &model.status_do_eixo_x),
+            MenuProgramaControler::new(initial_menu_selector, arena),
+            model,
+            panel_patterns,
+        );
+        navigator.apply(ScreenId::Splash, Navigation::Replace(ScreenId::MainMenu));
+        navigator
+    }
+
+    fn get_leds(panel_patterns: &PanelPatterns) -> (bool, bool, bool) {
+        let mut front_panel = FrontPanelRecorder::new();
+        panel_patterns.update(&mut front_panel);
+        (
+            front_panel.led_manual,
+            front_panel.led_execucao,
+            front_panel.led_programa,
+        )
+    }
+
+    #[test]
+    fn it_pushes_pops_and_replaces_the_screens() {
+        let mut stack = NavigationStack::new(ScreenId::Splash);
+        stack.replace(ScreenId::MainMenu);
+        assert_eq!(stack.top(), ScreenId::MainMenu);
+        assert!(!stack.contains(ScreenId::Splash));
+        stack.push(ScreenId::Programa);
+        assert_eq!(stack.top(), ScreenId::Programa);
+        assert!(stack.contains(ScreenId::MainMenu));
+        stack.replace(ScreenId::Manual);
+        assert_eq!(stack.top(), ScreenId::Manual);
+        assert!(!stack.contains(ScreenId::Programa));
+        assert_eq!(stack.pop(), Some(ScreenId::Manual));
+        // the root is never popped
+        assert_eq!(stack.pop(), None);
+        assert_eq!(stack.top(), ScreenId::MainMenu);
+    }
+
+    #[test]
+    fn it_opens_the_modes_and_lights_their_leds() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let panel_patterns = PanelPatterns::new();
+        let mut navigator = make_navigator(
+            &transport,
+            &model,
+            &arena,
+            &panel_patterns,
+            MenuProgramaAreanaSelector::MenuPrograma,
+        );
+        assert_eq!(get_leds(&panel_patterns), (false, false, false));
+
+        navigator.send_key(KeyCode::KEY_EXECUCAO);
+        assert_eq!(navigator.stack.top(), ScreenId::Execucao);
+        assert_eq!(get_leds(&panel_patterns), (false, true, false));
+
+        navigator.send_key(KeyCode::KEY_ESC);
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+        assert_eq!(get_leds(&panel_patterns), (false, false, false));
+
+        navigator.send_key(KeyCode::KEY_MANUAL);
+        assert_eq!(navigator.stack.top(), ScreenId::Manual);
+        assert_eq!(get_leds(&panel_patterns), (true, false, false));
+    }
+
+    #[test]
+    fn it_does_not_open_a_screen_already_on_the_stack() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let panel_patterns = PanelPatterns::new();
+        let mut navigator = make_navigator(
+            &transport,
+            &model,
+            &arena,
+            &panel_patterns,
+            MenuProgramaAreanaSelector::MenuPrograma,
+        );
+        navigator.send_key(KeyCode::KEY_MANUAL);
+
+        navigator.apply(ScreenId::Manual, Navigation::Push(ScreenId::MainMenu));
+        assert_eq!(navigator.stack.top(), ScreenId::Manual);
+        navigator.apply(ScreenId::Manual, Navigation::Replace(ScreenId::MainMenu));
+        assert_eq!(navigator.stack.top(), ScreenId::Manual);
+        assert_eq!(get_leds(&panel_patterns), (true, false, false));
+
+        navigator.apply(ScreenId::Manual, Navigation::Pop(ReturnValue::Nothing));
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+        // the main menu is the root, it is never popped
+        navigator.apply(ScreenId::MainMenu, Navigation::Pop(ReturnValue::Nothing));
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+    }
+
+    #[test]
+    fn it_gives_the_return_value_to_the_screen_below() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let panel_patterns = PanelPatterns::new();
+        let mut navigator = make_navigator(
+            &transport,
+            &model,
+            &arena,
+            &panel_patterns,
+            MenuProgramaAreanaSelector::MenuPrograma,
+        );
+        // nothing to do when the manual mode returns
+        navigator.send_key(KeyCode::KEY_MANUAL);
+        navigator.send_key(KeyCode::KEY_ESC);
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+        navigator.send_key(KeyCode::KEY_PROGRAMA);
+        assert_eq!(navigator.stack.top(), ScreenId::Programa);
+
+        // the programs are sent when the menu programa returns, the upload takes the keys
+        navigator.send_key(KeyCode::KEY_ESC);
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+        navigator.send_key(KeyCode::KEY_PROGRAMA);
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+    }
+
+    #[test]
+    fn it_goes_home_until_a_screen_asks_to_save_the_changes() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let panel_patterns = PanelPatterns::new();
+        let mut navigator = make_navigator(
+            &transport,
+            &model,
+            &arena,
+            &panel_patterns,
+            MenuProgramaAreanaSelector::MenuParametrosDeCiclo,
+        );
+        navigator.send_key(KeyCode::KEY_PROGRAMA);
+        for key in [KeyCode::KEY_ENTER, KeyCode::KEY_7, KeyCode::KEY_ENTER] {
+            navigator.send_key(key);
+        }
+        navigator.send_key_event(KeyEvent::LongPress(KeyCode::KEY_ESC));
+        assert_eq!(navigator.stack.top(), ScreenId::Programa);
+        assert!(arena.has_unsaved_changes());
+        assert_eq!(get_leds(&panel_patterns), (false, false, true));
+
+        // once the user answers, the menu programa can be left
+        navigator.send_key(KeyCode::KEY_0);
+        navigator.send_key_event(KeyEvent::LongPress(KeyCode::KEY_ESC));
+        assert_eq!(navigator.stack.top(), ScreenId::MainMenu);
+        assert_eq!(get_leds(&panel_patterns), (false, false, false));
+    }
+
+    #[test]
+    fn it_falls_back_to_operador_after_five_minutes_without_keys() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let panel_patterns = PanelPatterns::new();
+        let mut navigator = make_navigator(
+            &transport,
+            &model,
+            &arena,
+            &panel_patterns,
+            MenuProgramaAreanaSelector::MenuPrograma,
+        );
+        let nivel_de_acesso = &model.gui_state.nivel_de_acesso;
+        nivel_de_acesso.set(NivelDeAcesso::Supervisor);
+
+        advance_virtual_clock(4 * 60 * 1000);
+        navigator.send_key(KeyCode::KEY_DIRECIONAL_PARA_BAIXO);
+        advance_virtual_clock(5 * 60 * 1000);
+        navigator.update();
+        assert_eq!(nivel_de_acesso.get(), NivelDeAcesso::Supervisor);
+
+        advance_virtual_clock(1);
+        navigator.update();
+        assert_eq!(nivel_de_acesso.get(), NivelDeAcesso::Operador);
+    }
+}
@@ -1,25 +1,36 @@
 use avr_progmem::progmem;
 use cross_platform::protocol::transport::transport_layer::TransportLayer;
+use cross_platform::types::time::{Duration, Instant};
 
 use crate::geometry::point::Point;
-use crate::menu::model::{send_all, CmppData};
 use crate::string::flash::FlashString;
 use crate::{
-    board::{keypad::KeyCode, lcd},
-    menu::{model::DataModel, screen_buffer::ScreenBuffer},
-    microcontroler::{delay::delay_ms, timer::now},
+    board::keypad::KeyCode,
+    menu::{model::DataModel, screen_buffer::ScreenRegion},
+    microcontroler::timer::now_instant,
 };
 
-pub fn show_communication_error_message() {
-    lcd::clear();
-    lcd::set_cursor(0, 0);
-    lcd::print("Erro de comunicacao serial");
-    delay_ms(4000);
+use super::{
+    dialog::MessageBox,
+    navigation::{Navigation, Screen, ScreenId},
+    upload::ProgramUpload,
+    widget::Widget,
+};
+
+/// Message box to inform the user of a communication error with the cmpp boards, it must be opened
+/// on top of the current screen.
+pub fn communication_error_message() -> MessageBox {
+    const TIMEOUT: Duration = Duration::from_secs(4);
+    MessageBox::new(
+        FlashString::new(&ERRO_DE_COMUNICACAO_SERIAL),
+        FlashString::default(),
+    )
+    .with_timeout(TIMEOUT)
 }
 
 progmem! {
     static progmem string TEXT0 = "Posijet Industria e Comercio Ltda.";
-    pub static progmem string POR_FAVOR_AGUARDE_CARGA_DO_PROGRAMA = "Por favor aguarde a carga do programa ";
+    static progmem string ERRO_DE_COMUNICACAO_SERIAL = "Erro de comunicacao serial";
 }
 
 // SPLASH SCREEN RECIEPE (from on original TTC3100 Z80):
@@ -32,6 +43,9 @@ progmem! {
 //      * lcd_cursor(col=0,lin=0) - print "Por favor aguarde a carga do programa X"
 //      * ~2 segundos se equipto estiver conectado ou 0.5 segundos se nao estiver
 //      * GO_TO_MENU_PRINCIPAL
+//
+// NOTE: Instead of the messages "Por favor aguarde a carga do programa X", the progress of the
+// upload of the programs is shown (see `ProgramUpload`).
 
 #[repr(u8)]
 #[derive(PartialEq, Clone, Copy)]
@@ -66,10 +80,10 @@ impl State {
 
 pub struct Splash<'a> {
     current_state: State,
-    next_state_time_point: u32,
-    model: &'a DataModel,
-    transport_x: &'a TransportLayer<'a>,
-    transport_y: &'a TransportLayer<'a>,
+    next_state_time_point: Instant,
+    /// Upload of the programs to the cmpp boards, it runs in the `Loading` state
+    upload: ProgramUpload<'a>,
+    navigation: Option<Navigation>,
 }
 
 impl<'a> Splash<'a> {
@@ -81,58 +95,65 @@ impl<'a> Splash<'a> {
         let initial_state = State::Initial;
         Self {
             current_state: initial_state,
-            next_state_time_point: now() as u32 + Self::get_time_to_wait_in(initial_state),
-            model,
-            transport_x,
-            transport_y,
+            next_state_time_point: now_instant() + Self::get_time_to_wait_in(initial_state),
+            upload: ProgramUpload::new(model, transport_x, transport_y),
+            navigation: None,
         }
     }
 
-    /// gets time interval (in miliseconds) to wait until reach next state
-    fn get_time_to_wait_in(current_state: State) -> u32 {
+    /// gets time interval to wait until reach next state
+    fn get_time_to_wait_in(current_state: State) -> Duration {
         match current_state {
-            State::Initial => 0,
-            State::BrandName => 2000,
-            State::Loading => 2000,
-            State::End => 0,
+            State::Initial => Duration::ZERO,
+            State::BrandName => Duration::from_secs(2),
+            State::Loading => Duration::ZERO, // waits the end of the upload instead
+            State::End => Duration::ZERO,
         }
     }
 
-    pub fn is_running(&self) -> bool {
-        self.current_state != State::End
+    fn go_to_next_state(&mut self) {
+        self.current_state = self.current_state.next_state();
+        let time_interval = Self::get_time_to_wait_in(self.current_state);
+        self.next_state_time_point = now_instant() + time_interval;
+        if self.current_state == State::End {
+            // the splash is shown just once, so it is replaced by the main menu
+            self.navigation = Some(Navigation::Replace(ScreenId::MainMenu));
+        }
     }
 }
 
-impl Splash<'_> {
-    pub fn send_key(&mut self, _key: KeyCode) {
-        // do nothing
+impl Widget for Splash<'_> {
+    fn send_key(&mut self, key: KeyCode) {
+        if self.current_state == State::Loading {
+            self.upload.send_key(key);
+        }
     }
 
-    pub fn update(&mut self) {
+    fn update(&mut self) {
+        if self.current_state == State::Loading {
+            self.upload.update();
+            if self.upload.get_result().is_some() {
+                self.go_to_next_state();
+            }
+            return;
+        }
         let has_finished = self.current_state == State::End;
         if !has_finished {
-            if now() as u32 > self.next_state_time_point {
-                self.current_state = self.current_state.next_state();
-                let time_interval = Self::get_time_to_wait_in(self.current_state.clone());
-                self.next_state_time_point = now() as u32 + time_interval;
+            if self.next_state_time_point.is_before(now_instant()) {
+                self.go_to_next_state();
             }
         }
     }
 
-    pub fn draw(&self, screen_buffer: &mut ScreenBuffer) {
-        screen_buffer.clear();
+    fn draw(&self, region: &mut ScreenRegion) {
+        region.clear();
         match self.current_state {
             State::Initial => {}
             State::BrandName => {
-                screen_buffer.set_cursor(Point::new(4, 0));
-                screen_buffer.print(FlashString::new(&TEXT0));
+                region.set_cursor(Point::new(4, 0));
+                region.print(FlashString::new(&TEXT0));
             }
-            State::Loading => send_all_and_show_user_info_on_screen(
-                self.model,
-                //screen_buffer,
-                self.transport_x,
-                self.transport_y,
-            ),
+            State::Loading => self.upload.draw(region),
 
             State::End => {
                 // do nothing
@@ -141,46 +162,37 @@ impl Splash<'_> {
     }
 }
 
-/// TODO: Eventually this function should be place in a better location instead of in this module.
-pub fn send_all_and_show_user_info_on_screen(
-    model: &DataModel,
-    //screen_buffer: &mut ScreenBuffer,
-    transport_x: &TransportLayer,
-    transport_y: &TransportLayer,
-) {
-    fn message(line: u8, axis_name: u8) {
-        lcd::clear();
-        lcd::set_cursor(0, line);
-        for c in POR_FAVOR_AGUARDE_CARGA_DO_PROGRAMA.chars() {
-            lcd::print_u8(c as u8);
+impl Screen for Splash<'_> {
+    fn take_navigation(&mut self) -> Option<Navigation> {
+        self.navigation.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::menu::snapshot::{make_disconnected_datalink, make_mechanical_properties};
+    use crate::microcontroler::timer::advance_virtual_clock;
+
+    fn update_during(splash: &mut Splash, milisecs: u64) {
+        for _ in 0..milisecs {
+            splash.update();
+            advance_virtual_clock(1);
         }
-        lcd::print_u8(axis_name)
     }
 
-    // **************************
-    // Send all data to X-Axis
-    // **************************
-
-    message(1, b'X');
-
-    // TODO: Choose the right `arquivo de eixo` and `config de eixo` to send. Consider
-    // the cases when the system have more than one axis, and more than one program
-    let cmpp_data_x = CmppData {
-        arquivo_de_eixo: &model.arquivo_de_eixo_00,
-        configuracao_de_eixo: &model.configuracao_do_eixo_x,
-    };
-    send_all(&transport_x, &cmpp_data_x);
-
-    // **************************
-    // Send all data to Y-Axis
-    // **************************
-
-    message(0, b'Y');
-    // TODO: Choose the right `arquivo de eixo` and `config de eixo` to send. Consider
-    // the cases when the system have more than one axis, and more than one program
-    let cmpp_data_y = CmppData {
-        arquivo_de_eixo: &model.arquivo_de_eixo_00,
-        configuracao_de_eixo: &model.configuracao_do_eixo_y,
-    };
-    send_all(&transport_y, &cmpp_data_y);
+    #[test]
+    fn it_shows_the_brand_name_across_the_wrap_of_the_clock() {
+        advance_virtual_clock(u32::MAX as u64 - 1000);
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut splash = Splash::new(&model, &transport, &transport);
+        update_during(&mut splash, 10);
+        assert!(splash.current_state == State::BrandName);
+        update_during(&mut splash, 1990);
+        assert!(splash.current_state == State::BrandName);
+        update_during(&mut splash, 20);
+        assert!(splash.current_state == State::Loading);
+    }
 }
@@ -1,7 +1,7 @@
 pub mod core;
 pub mod flash_texts;
 pub mod hepers;
+pub mod macros;
 pub mod menu_programa_controler;
 pub mod navigation_state;
 pub mod spec;
-pub mod spec_options;
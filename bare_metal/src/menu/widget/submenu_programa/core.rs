@@ -1,4 +1,7 @@
-use crate::menu::widget::menu_item::menu_item::MenuItemWidget;
+use crate::menu::{
+    model::{ModelError, NivelDeAcesso},
+    widget::menu_item::menu_item::MenuItemWidget,
+};
 
 /// Trait implemented by all sub menus
 ///
@@ -9,6 +12,23 @@ pub trait SubmenuLayout {
     /// Gets the size of menu items inside the submenu
     fn get_item(&self, index: usize) -> Option<MenuItemWidget>;
 
+    /// Called before the navigation goes to the child submenu of the menu item at `index`. If an
+    /// error is returned the navigation is aborted.
+    ///
+    /// Override it if the child submenu depends on some preparation of the model (ie: loading the
+    /// selected program).
+    fn before_go_to_child(&self, _index: usize) -> Result<(), ModelError> {
+        Ok(())
+    }
+
+    /// Minimum access level required to edit the menu item at `index`, menu items which the user has
+    /// not access are shown read only.
+    ///
+    /// NOTE: Navigation to child submenus is not protected, so the user can still see the values.
+    fn get_access_level(&self, _index: usize) -> NivelDeAcesso {
+        NivelDeAcesso::Operador
+    }
+
     /// TODO: This algoritm may be highly optimized, because the length currently is obtained instantiating &
     /// throwing away all the menu items in memory. A better option may be to restructure datastructures
     /// to calculate this size in static time.
@@ -1,6 +1,9 @@
+use crate::menu_texts;
 use avr_progmem::progmem;
 
-progmem! {
+// NOTE: The length of each text is checked in compile time to fit in the lcd line. The text after
+// the `|` is the short caption used in the narrower lcds, where each menu item takes two lines.
+menu_texts! {
 
     // UNITS OF MEASUREMENT (UOM)
     pub static progmem string MILIMETROS = "mm";
@@ -24,18 +27,53 @@ progmem! {
     //pub static progmem string CONFIGURACAO_DO_EQUIPAMENTO =     "Configuracao do equipamento ...";
     //pub static progmem string ROTINAS_DE_TESTES_E_VERIFICACAO = "Rotinas de testes e verificacao ...";
 
-    pub static progmem string EDITAR_PROGRAMA_EIXO_X = "Editar numero do programa do eixo X: ";
-    pub static progmem string CONFIGURACAO_EIXO_X = "Configuracao eixo X...";
-    pub static progmem string CONFIGURACAO_DO_EQUIPAMENTO = "Configuracao do equipamento...";
-
+    pub static progmem string EDITAR_PROGRAMA_EIXO_X = "Editar numero do programa do eixo X: "
+        | "Programa eixo X";
+    pub static progmem string EDITAR_PROGRAMA_EIXO_Y = "Editar numero do programa do eixo Y: "
+        | "Programa eixo Y";
+    pub static progmem string COPIAR_PROGRAMA = "Copiar programa    :   para    :"
+        | "Copiar de/para";
+    pub static progmem string RESTAURAR_PADRAO = "Restaurar padrao:            :"
+        | "Restaura padrao";
+    pub static progmem string CONFIGURACAO_EIXO_X = "Configuracao eixo X..." | "Conf. eixo X...";
+    pub static progmem string CONFIGURACAO_EIXO_Y = "Configuracao eixo Y..." | "Conf. eixo Y...";
+    pub static progmem string NIVEL_DE_ACESSO = "Nivel de acesso:          Senha:" | "Nivel/senha";
+    pub static progmem string TROCAR_SENHA = "Trocar senha:          Nova senha:" | "Trocar senha";
+    pub static progmem string CONFIGURACAO_DO_EQUIPAMENTO = "Configuracao do equipamento..."
+        | "Equipamento...";
+
+
+    // CONFIRMACAO DE ACOES
+
+    pub static progmem string CONFIRMA_COPIA_DE_PROGRAMA = "O programa destino sera sobrescrito!";
+    pub static progmem string CONFIRMA_RESTAURACAO_DE_PADRAO = "Os valores atuais serao perdidos!";
+    pub static progmem string ENTER_CONFIRMA_ESC_CANCELA = "ENTER: Confirma       ESC: Cancela";
+    pub static progmem string SALVAR_ALTERACOES = "Salvar alteracoes?";
+    pub static progmem string SIM_NAO_CONTINUAR = "1: Sim    0: Nao    ESC: Continuar";
+
+    // MENSAGENS DE ERRO
+
+    pub static progmem string NUMERO_DO_PROGRAMA_INVALIDO = "Numero do programa invalido";
+    pub static progmem string MAIOR_NUMERO_PERMITIDO = "Maior numero permitido: ";
+    pub static progmem string SENHA_INVALIDA = "Senha invalida";
+    pub static progmem string ACESSO_NEGADO = "Acesso negado";
+    pub static progmem string NIVEL_DE_ACESSO_INSUFICIENTE = "Nivel de acesso insuficiente";
+    pub static progmem string PROGRAMA_NAO_SALVO = "O programa alterado nao foi salvo";
+    pub static progmem string SAIA_DO_MENU_PARA_SALVAR = "Saia do menu para salva-lo";
+    pub static progmem string CORRIJA_O_PROGRAMA_DO_EIXO_X = "Corrija o programa do eixo X";
+    pub static progmem string CORRIJA_O_PROGRAMA_DO_EIXO_Y = "Corrija o programa do eixo Y";
 
     //ARQUIVO DE EIXO
 
-    pub static progmem string PARAMETROS_DE_MOVIMENTO = "Parametro de Movimento...";
-    pub static progmem string PARAMETROS_DE_IMPRESSAO = "Parametros de Impressao...";
-    pub static progmem string CONFIGURACAO_DO_CICLO = "Configuracao do Ciclo...";
-    pub static progmem string CONFIGURACAO_DA_IMPRESSORA = "Configuracao da impressora...";
-    pub static progmem string INTERTRAVAMENTO_DOIS_EIXOS_PASSO_A_PASSO = "Intertravamento: dois eixos e pas/pas..";
+    pub static progmem string PARAMETROS_DE_MOVIMENTO = "Parametro de Movimento..."
+        | "Movimento...";
+    pub static progmem string PARAMETROS_DE_IMPRESSAO = "Parametros de Impressao..."
+        | "Impressao...";
+    pub static progmem string CONFIGURACAO_DO_CICLO = "Configuracao do Ciclo..." | "Ciclo...";
+    pub static progmem string CONFIGURACAO_DA_IMPRESSORA = "Configuracao da impressora..."
+        | "Impressora...";
+    pub static progmem string INTERTRAVAMENTO_DOIS_EIXOS_PASSO_A_PASSO = "Intertravamento: dois eixos e pas/pas.."
+        | "Intertravam...";
     // NAO ACHEI A LINHA ABAIXO NO CODIGO FONTE DO CPU-Z80, VOU DEIXAR PARA VERIFICAR ISTO NO TESTE EM LAB
     //pub static progmem string PARAMETROS_SELECAO_DE_MENSAGEM = "Parametros de Selecao de mensagem...";
 
@@ -43,19 +81,25 @@ progmem! {
 
     pub static progmem string POSICAO_INICIAL = "Posicao inicial";
     pub static progmem string POSICAO_FINAL = "Posicao final";
-    pub static progmem string ACELERACAO_DE_AVANCO = "Aceleracao de avanco";
-    pub static progmem string ACELERACAO_DE_RETORNO = "Aceleracao de retorno";
-    pub static progmem string VELOCIDADE_DE_AVANCO = "Velocidade de avanco";
-    pub static progmem string VELOCIDADE_DE_RETORNO = "Velocidade de retorno";
+    pub static progmem string ACELERACAO_DE_AVANCO = "Aceleracao de avanco" | "Acel. avanco";
+    pub static progmem string ACELERACAO_DE_RETORNO = "Aceleracao de retorno" | "Acel. retorno";
+    pub static progmem string VELOCIDADE_DE_AVANCO = "Velocidade de avanco" | "Veloc. avanco";
+    pub static progmem string VELOCIDADE_DE_RETORNO = "Velocidade de retorno" | "Veloc. retorno";
 
     // PARAMETROS DE IMPRESSAO
 
-    pub static progmem string NUMERO_DE_MENSAGEM_NO_AVANCO = "Numero de mensagem no avanco";
-    pub static progmem string NUMERO_DE_MENSAGEM_NO_RETORNO= "Numero de mensagem no retorno";
-    pub static progmem string PRIMEIRA_MENSAGEM_NO_AVANCO= "Primeira mensagem no avanco";
-    pub static progmem string PRIMEIRA_MENSAGEM_NO_RETORNO= "Primeira mensagem no retorno";
-    pub static progmem string ULTIMA_MENSAGEM_NO_AVANCO = "Ultima mensagem no avanco";
-    pub static progmem string ULTIMA_MENSAGEM_NO_RETORNO = "Ultima mensagem no retorno";
+    pub static progmem string NUMERO_DE_MENSAGEM_NO_AVANCO = "Numero de mensagem no avanco"
+        | "N. msg avanco";
+    pub static progmem string NUMERO_DE_MENSAGEM_NO_RETORNO= "Numero de mensagem no retorno"
+        | "N. msg retorno";
+    pub static progmem string PRIMEIRA_MENSAGEM_NO_AVANCO= "Primeira mensagem no avanco"
+        | "1a msg avanco";
+    pub static progmem string PRIMEIRA_MENSAGEM_NO_RETORNO= "Primeira mensagem no retorno"
+        | "1a msg retorno";
+    pub static progmem string ULTIMA_MENSAGEM_NO_AVANCO = "Ultima mensagem no avanco"
+        | "Ult msg avanco";
+    pub static progmem string ULTIMA_MENSAGEM_NO_RETORNO = "Ultima mensagem no retorno"
+        | "Ult msg retorno";
     pub static progmem string MENSAGEM_REVERSA_LIGADA = "Mensagem reversa ligada";
     pub static progmem string NUMERO_DE_MULTIPLAS_IMPRESSOES = "Numero de multiplas impressoes";
     pub static progmem string PASSO_DAS_MULTIPLAS_IMPRESSOES = "passo das multiplas impressoes";
@@ -63,50 +107,86 @@ progmem! {
     // CONFIGURACAO DE CICLO
 
     pub static progmem string RETARDO_NO_START_AUTOMATICO = "Retardo no start automatico";
+    pub static progmem string RETARDO_NO_START_AUTOMATICO_EM_SEGUNDOS = "Retardo no start automatico   ${n.nnn} seg"
+        | "Ret. start auto ${n.nnn} seg";
     pub static progmem string RETARDO_NO_START_EXTERNO = "Retardo no start externo";
-    pub static progmem string START_AUTOMATICO_NO_AVANCO = "Start automatico no avanco";
-    pub static progmem string START_AUTOMATICO_NO_RETORNO = "Start automatico no retorno";
-    pub static progmem string MODO_DE_TRABALHO_DO_EIXO = "Modo de trabalho do eixo";
+    pub static progmem string RETARDO_NO_START_EXTERNO_EM_SEGUNDOS = "Retardo no start externo      ${n.nnn} seg"
+        | "Ret. start ext. ${n.nnn} seg";
+    pub static progmem string START_AUTOMATICO_NO_AVANCO = "Start automatico no avanco"
+        | "St.auto avanco";
+    pub static progmem string START_AUTOMATICO_NO_RETORNO = "Start automatico no retorno"
+        | "St.auto retorno";
+    pub static progmem string MODO_DE_TRABALHO_DO_EIXO = "Modo de trabalho do eixo"
+        | "Modo trabalho";
 
     // CONFIGURACAO DA IMPRESSORA
 
-    pub static progmem string LOGICA_DO_SINAL_DE_IMPRESSAO = "Logica do sinal de impressao";
+    pub static progmem string LOGICA_DO_SINAL_DE_IMPRESSAO = "Logica do sinal de impressao"
+        | "Logica impres.";
     pub static progmem string LARGURA_DO_SINAL_DE_IMPRESSAO = "Largura do sinal dimpressao";
-    pub static progmem string REVERSAO_DE_MENSAGEM_VIA_SERIAL = "Reversao dmensagem via serial";
-    pub static progmem string SELECAO_DE_MENSAGEM_VIA_SERIAL = "Selecao de mensagem via serial";
+    pub static progmem string LARGURA_DO_SINAL_DE_IMPRESSAO_EM_SEGUNDOS = "Largura do sinal dimpressao   ${n.nnn} seg"
+        | "Largura impres. ${n.nnn} seg";
+    pub static progmem string REVERSAO_DE_MENSAGEM_VIA_SERIAL = "Reversao dmensagem via serial"
+        | "Reversao serial";
+    pub static progmem string SELECAO_DE_MENSAGEM_VIA_SERIAL = "Selecao de mensagem via serial"
+        | "Selecao serial";
 
     // INTERTRAVAMENTO PARA DOIS EIXOS
 
-    pub static progmem string ANTECIPACAO_DA_SAIDA_DE_START = "Antecipacao da saida de start";
-    pub static progmem string SAIDA_DE_START_NO_AVANCO = "Saida de Start no avanco";
-    pub static progmem string SAIDA_DE_START_NO_RETORNO = "Saida de Start no retorno";
-    pub static progmem string ENTRADA_DE_START_ENTRE_EIXOS = "Entrada de start entre eixos";
+    pub static progmem string ANTECIPACAO_DA_SAIDA_DE_START = "Antecipacao da saida de start"
+        | "Antecip. start";
+    pub static progmem string SAIDA_DE_START_NO_AVANCO = "Saida de Start no avanco"
+        | "Saida avanco";
+    pub static progmem string SAIDA_DE_START_NO_RETORNO = "Saida de Start no retorno"
+        | "Saida retorno";
+    pub static progmem string ENTRADA_DE_START_ENTRE_EIXOS = "Entrada de start entre eixos"
+        | "Entrada eixos";
     pub static progmem string RETARDO_DO_START_ENTRE_EIXOS = "Retardo do start entre eixo";
-    pub static progmem string START_PELO_TECLADO_E_EXTERNO = "Start pelo teclado e externo";
+    pub static progmem string RETARDO_DO_START_ENTRE_EIXOS_EM_SEGUNDOS = "Retardo do start entre eixo   ${n.nnn} seg"
+        | "Ret. start eixo ${n.nnn} seg";
+    pub static progmem string START_PELO_TECLADO_E_EXTERNO = "Start pelo teclado e externo"
+        | "Start tecl/ext";
     pub static progmem string RETARDO_NO_SINAL_DE_IMPRESSAO = "Retardo no sinal de impressao";
     pub static progmem string RETARDO_NO_START_PASSO_A_PASSO = "Retardo no start passo/passo";
-    pub static progmem string START_AUTOMATICO_PASSO_A_PASSO = "Start automatico passo/passo";
-    pub static progmem string SAIDA_START_PASSO_A_PASSO = "Saida de start passo a passo";
+    pub static progmem string RETARDO_NO_START_PASSO_A_PASSO_EM_SEGUNDOS = "Retardo no start passo/passo  ${n.nnn} seg"
+        | "Ret. pas/pas ${n.nnn} seg";
+    pub static progmem string START_AUTOMATICO_PASSO_A_PASSO = "Start automatico passo/passo"
+        | "St.auto pas/pas";
+    pub static progmem string SAIDA_START_PASSO_A_PASSO = "Saida de start passo a passo"
+        | "Saida pas/pas";
 
     // MENU CONFIGURACAO DE EIXO
-
-    pub static progmem string NUMERO_DO_CANAL_X = "Numero do canal X";
-    pub static progmem string NUMERO_DE_PULSO_DO_GIRO_X = "Numero de pulso do giro X";
-    pub static progmem string JANELA_DE_PROTECAO_DO_GITO_X = "Janela de protecao do giro X";
-    pub static progmem string DESLOCAMENTO_GIRO_DO_MOTOR_X = "Deslocamento/Giro do Motor X";
-    pub static progmem string GIRO_COM_FUNCAO_DE_PROTECAO = "Giro X com funcao de protecao";
-    pub static progmem string GIRO_COM_FUNCAO_DE_CORRECAO = "Giro X com funcao de correcao";
-    pub static progmem string LOGICA_DO_START_EXTERNO = "Logica do start externo X";
-    pub static progmem string VALOR_DA_POSICAO_DA_REFERENCIA = "Valor da posicao da referencia";
-    pub static progmem string VELOCIDADE_PARA_REFERENCIA_X = "Velocidade para referencia X";
-    pub static progmem string ACELERACAO_PARA_REFERENCIA_X = "Aceleracao para referencia X";
-    pub static progmem string REDUCAO_DA_CORRENTE_EM_REPOUSO = "Reducao da corrente em repouso";
-    pub static progmem string REFERENCIA_PELO_START_EXTERNO = "Referencia pelo start externo";
-    pub static progmem string MODO_TURBO_X = "Modo Turbo X";
+    // NOTE: The items are shown for both axis, the letter of the axis is printed after the caption
+
+    pub static progmem string NUMERO_DO_CANAL = "Numero do canal" | "Num. canal";
+    pub static progmem string NUMERO_DE_PULSO_DO_GIRO = "Numero de pulso do giro"
+        | "Pulsos/giro";
+    pub static progmem string JANELA_DE_PROTECAO_DO_GIRO = "Janela de protecao do giro"
+        | "Janela giro";
+    pub static progmem string DESLOCAMENTO_GIRO_DO_MOTOR = "Deslocamento/Giro do Motor"
+        | "Desloc./giro";
+    pub static progmem string GIRO_COM_FUNCAO_DE_PROTECAO = "Giro com funcao de protecao"
+        | "Protecao giro";
+    pub static progmem string GIRO_COM_FUNCAO_DE_CORRECAO = "Giro com funcao de correcao"
+        | "Correcao giro";
+    pub static progmem string LOGICA_DO_START_EXTERNO = "Logica do start externo"
+        | "Logica start";
+    pub static progmem string VALOR_DA_POSICAO_DA_REFERENCIA = "Valor da posicao da referencia"
+        | "Pos. referencia";
+    pub static progmem string VELOCIDADE_PARA_REFERENCIA = "Velocidade para referencia"
+        | "Veloc. ref.";
+    pub static progmem string ACELERACAO_PARA_REFERENCIA = "Aceleracao para referencia"
+        | "Acel. ref.";
+    pub static progmem string REDUCAO_DA_CORRENTE_EM_REPOUSO = "Reducao da corrente em repouso"
+        | "Reduz corrente";
+    pub static progmem string REFERENCIA_PELO_START_EXTERNO = "Referencia pelo start externo"
+        | "Ref. start ext.";
+    pub static progmem string MODO_TURBO = "Modo Turbo";
 
     // MENU CONFIGURACAO DO EQUIPAMENTO
 
-    pub static progmem string VELOCIDADE_DE_COMUNICACAO = "Velocidade de comunicacao";
+    pub static progmem string VELOCIDADE_DE_COMUNICACAO = "Velocidade de comunicacao"
+        | "Veloc. comunic.";
 
 
 
@@ -124,12 +204,34 @@ progmem! {
     pub static progmem string FECHADO = "Fechad";
     pub static progmem string B2400 = "2400";
     pub static progmem string B9600 = "9600";
+    pub static progmem string EIXO_X = "X";
+    pub static progmem string EIXO_Y = "Y";
+    pub static progmem string ESCOPO_PROGRAMA = "Progr";
+    pub static progmem string ESCOPO_EIXO =     "Eixo ";
+    pub static progmem string ESCOPO_TUDO =     "Tudo ";
+    pub static progmem string NIVEL_SUPERVISOR = "Superv";
+    pub static progmem string NIVEL_MANUTENCAO = "Manut.";
+    pub static progmem string NIVEL_OPERADOR =   "Operad";
+    pub static progmem string NIVEL_PROTEGIDO =  "Proteg";
+
+    pub static progmem string ERRO_01 = "Erro de construcao de string";
 
+    // Violated validation rules, shown in the first line of the lcd
+    pub static progmem string REGRA_POSICAO_FINAL = "Posicao final menor que a inicial";
+    pub static progmem string REGRA_PRIMEIRA_NO_AVANCO_NO_CURSO = "Prim. msg avanco fora do curso";
+    pub static progmem string REGRA_ULTIMA_NO_AVANCO_NO_CURSO = "Ult. msg avanco fora do curso";
+    pub static progmem string REGRA_PRIMEIRA_NO_RETORNO_NO_CURSO = "Prim. msg retorno fora do curso";
+    pub static progmem string REGRA_ULTIMA_NO_RETORNO_NO_CURSO = "Ult. msg retorno fora do curso";
+    pub static progmem string REGRA_ORDEM_NO_AVANCO = "Ult. msg avanco antes da primeira";
+    pub static progmem string REGRA_ORDEM_NO_RETORNO = "Ult. msg retorno apos a primeira";
+    pub static progmem string REGRA_MENSAGENS_NO_AVANCO = "Msgs no avanco nao cabem no curso";
+    pub static progmem string REGRA_MENSAGENS_NO_RETORNO = "Msgs no retorno nao cabem no curso";
+}
 
+progmem! {
     //NOTE: it is possible to load any type in progmem not only strings
     pub static progmem TABLE_01: [u8; 6] = [0,1,2,3,4,5];
     pub static progmem TABLE_02: [u8; 1] = [
         0
     ];
-    pub static progmem string ERRO_01 = "Erro de construcao de string";
 }
@@ -1,16 +1,30 @@
-/// Helper type to represent each lines of the 40x2 LCD display.
+use super::macros::NUMBER_OF_VISIBLE_ITEMS;
+
+/// Helper type to represent each lines of the menu in the LCD display, one per visible menu item
+/// (see [`NUMBER_OF_VISIBLE_ITEMS`]). In the narrower lcds each line of the menu takes two lcd lines.
 ///
-/// You may consider this type just to avoid to cast the two lcd lines direct to a `u8` type.
+/// You may consider this type just to avoid to cast the lcd lines direct to a `u8` type.
 #[derive(PartialEq, Copy, Clone)]
 #[repr(u8)]
 pub enum LcdLine {
     Line0 = 0,
     Line1 = 1,
+    Line2 = 2,
+    Line3 = 3,
 }
 
 impl LcdLine {
+    /// Iterates over the lines of the menu in the lcd in use
     pub fn iterator() -> impl Iterator<Item = LcdLine> {
-        [LcdLine::Line0, LcdLine::Line1].iter().copied()
+        [
+            LcdLine::Line0,
+            LcdLine::Line1,
+            LcdLine::Line2,
+            LcdLine::Line3,
+        ]
+        .iter()
+        .copied()
+        .take(NUMBER_OF_VISIBLE_ITEMS as usize)
     }
 }
 
@@ -19,6 +33,8 @@ impl From<u8> for LcdLine {
         match value {
             0 => LcdLine::Line0,
             1 => LcdLine::Line1,
+            2 => LcdLine::Line2,
+            3 => LcdLine::Line3,
             _ => LcdLine::Line0, // default
         }
     }
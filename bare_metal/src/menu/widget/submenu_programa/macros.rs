@@ -0,0 +1,784 @@
+//! Declarative definition of the menu `Programa`.
+//!
+//! To add a new parameter in a submenu: put its caption inside [`menu_texts!`] in `flash_texts.rs`,
+//! the variable in the model and a new line in the items of the [`submenu!`]. To add a new submenu:
+//! declare it with [`submenu!`] and registry its name in the [`menu_arena!`] in `spec.rs`.
+//!
+//! The menu items may be annotated with `#[access_level(Level)]`, to require another access level
+//! than the one of the submenu, with `#[before_go_to_child(expression)]`, to prepare the model
+//! before the navigation to its child submenu (see: [`SubmenuLayout`]), and with
+//! `#[axis_suffix(eixo)]`, to print the letter of the axis after the caption of a parameter shown
+//! for both axis.
+//!
+//! [`SubmenuLayout`]: super::core::SubmenuLayout
+
+use super::flash_texts::{EIXO_X, EIXO_Y};
+use crate::{board::lcd, menu::model::Eixo, string::flash::FlashString};
+
+/// Collunms taken by the suffix `#[axis_suffix(eixo)]` after the caption: a blank and the letter
+pub const AXIS_SUFFIX_WIDTH: usize = 2;
+
+/// Letter printed after the caption of the menu items annotated with `#[axis_suffix(eixo)]`
+pub fn axis_suffix(eixo: Eixo) -> FlashString {
+    match eixo {
+        Eixo::X => FlashString::new(&EIXO_X),
+        Eixo::Y => FlashString::new(&EIXO_Y),
+    }
+}
+
+/// Number of columns the menu is designed for (the 40x2 lcd), the menu texts and fields must fit
+/// in it. In narrower lcds the elements are placed relative to the right edge of the line (see:
+/// [`place_collunm`]).
+pub const MENU_NUMBER_OF_COLUMNS: usize = 40;
+
+/// Collunm where the caption of the menu items starts (the collunm 0 is used by the menu item selector)
+pub const CAPTION_COLLUNM: usize = 1;
+
+/// True if the lcd is narrower than the menu, then each menu item takes two lcd lines: its short
+/// caption (see: [`menu_texts!`]) in the first one and its fields in the second one.
+pub const IS_NARROW_LAYOUT: bool = (lcd::NUMBER_OF_COLS as usize) < MENU_NUMBER_OF_COLUMNS;
+
+/// Number of lcd lines taken by each menu item
+pub const LINES_PER_MENU_ITEM: u8 = if IS_NARROW_LAYOUT { 2 } else { 1 };
+
+/// Number of menu items visible at the same time in the lcd
+pub const NUMBER_OF_VISIBLE_ITEMS: u8 = lcd::NUMBER_OF_LINES / LINES_PER_MENU_ITEM;
+
+/// Number of collunms the elements of the menu items are moved to the left to fit in the lcd
+const LAYOUT_SHIFT: usize = MENU_NUMBER_OF_COLUMNS.saturating_sub(lcd::NUMBER_OF_COLS as usize);
+
+/// Collunm of the lcd where starts the element designed to start in the given collunm of the menu.
+///
+/// The elements keep their distance to the right edge of the line, so in narrower lcds the fields and
+/// units of measurement stay aligned at the right of the line below the caption. The element is
+/// never placed before the `minimum` collunm.
+pub const fn place_collunm(collunm: usize, minimum: usize) -> u8 {
+    let placed = collunm.saturating_sub(LAYOUT_SHIFT);
+    if placed < minimum {
+        minimum as u8
+    } else {
+        placed as u8
+    }
+}
+
+/// Collunms of the lcd where start the fields of a menu item with many fields, given the collunm
+/// where each one was designed to start and its width. The fields are placed as in [`place_collunm`]
+/// but, in narrower lcds, each one is moved to the right when necessary to keep at least one blank
+/// collunm after the previous one. It panics (a compile error in const context) if they do not fit.
+pub const fn place_fields<const N: usize>(fields: [(usize, usize); N]) -> [u8; N] {
+    let mut collunms = [0; N];
+    let mut minimum = CAPTION_COLLUNM;
+    let mut end = CAPTION_COLLUNM;
+    let mut index = 0;
+    while index < N {
+        let (collunm, width) = fields[index];
+        collunms[index] = place_collunm(collunm, minimum);
+        end = collunms[index] as usize + width;
+        minimum = end + 1;
+        index += 1;
+    }
+    assert!(
+        end <= lcd::NUMBER_OF_COLS as usize,
+        "Menu item fields do not fit in the lcd line"
+    );
+    collunms
+}
+
+/// Number of collunms taken by the text in the lcd, the delimiters of the template tokens (ie:
+/// `${n.nnn}`, see: `NumericalTemplate`) are not drawn.
+pub const fn drawn_length(text: &str) -> usize {
+    let text = text.as_bytes();
+    let mut length = 0;
+    let mut index = 0;
+    while index < text.len() {
+        let is_token_begin =
+            text[index] == b'$' && index + 1 < text.len() && text[index + 1] == b'{';
+        if is_token_begin {
+            index += 2;
+        } else {
+            if text[index] != b'}' {
+                length += 1;
+            }
+            index += 1;
+        }
+    }
+    length
+}
+
+/// Number of collunms taken by the caption of the text, the part before the first template token
+/// (see: `NumericalTemplate`) without its trailing blanks.
+pub const fn caption_width(text: &str) -> usize {
+    let text = text.as_bytes();
+    let mut end = 0;
+    while end < text.len() && !(text[end] == b'$' && end + 1 < text.len() && text[end + 1] == b'{')
+    {
+        end += 1;
+    }
+    while end > 0 && text[end - 1] == b' ' {
+        end -= 1;
+    }
+    end
+}
+
+/// True if the text has a template token of a signed numerical field (ie: `${+nnn}`, see:
+/// `FieldMask`).
+pub const fn has_signed_field(text: &str) -> bool {
+    let text = text.as_bytes();
+    let mut is_inside_token = false;
+    let mut index = 0;
+    while index < text.len() {
+        if text[index] == b'$' && index + 1 < text.len() && text[index + 1] == b'{' {
+            is_inside_token = true;
+            index += 1;
+        } else if text[index] == b'}' {
+            is_inside_token = false;
+        } else if is_inside_token && (text[index] == b'+' || text[index] == b'-') {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Text declared in [`menu_texts!`] for the lcd in use, the short one in the narrower lcds.
+pub const fn select_text(text: &'static str, short: &'static str) -> &'static str {
+    if IS_NARROW_LAYOUT {
+        short
+    } else {
+        text
+    }
+}
+
+/// Number of chars drawn by a decimal unsigned numerical field whose valid range ends in `end`
+/// (see: `Format::get_number_of_digits`).
+pub const fn numerical_field_width(end: u16) -> usize {
+    let mut width = 1;
+    let mut rest = end / 10;
+    while rest > 0 {
+        width += 1;
+        rest /= 10;
+    }
+    width
+}
+
+/// Number of chars drawn by an optional field with options of the given lengths: the brackets
+/// around the widest option.
+pub const fn optional_field_width(lengths: &[usize]) -> usize {
+    let mut widest = 0;
+    let mut index = 0;
+    while index < lengths.len() {
+        if lengths[index] > widest {
+            widest = lengths[index];
+        }
+        index += 1;
+    }
+    widest + 2
+}
+
+/// Index of the menu item with the given caption in the captions of a [`submenu!`], the first one if
+/// the caption is repeated. It must be evaluated in compile time (see: [`item_index!`]) where the
+/// panic is a compile error.
+pub const fn index_of_item(captions: &[&str], caption: &str) -> usize {
+    let mut index = 0;
+    while index < captions.len() {
+        if is_same_text(captions[index], caption) {
+            return index;
+        }
+        index += 1;
+    }
+    panic!("The submenu has no menu item with this caption")
+}
+
+const fn is_same_text(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+/// Declares the texts of the menu in flash (through `progmem!`) and checks in compile time that
+/// each text fits in the line of the menu when printed from the [`CAPTION_COLLUNM`]. The length of
+/// each text is declared in the module `lengths`, it is used to check the width of the fields of the
+/// menu items.
+///
+/// A text may be followed by a short one (ie: `= "Aceleracao de avanco" | "Acel. avanco";`), which
+/// is used instead of it in the narrower lcds (see: [`IS_NARROW_LAYOUT`]). The width of the caption
+/// of each text in use is declared in the module `caption_widths`, the captions of the menu items
+/// must fit in the lcd line (see: [`submenu!`]). The module `signed_fields` tells which templates
+/// have a signed field, its valid range is checked in [`menu_item!`].
+#[macro_export]
+macro_rules! menu_texts {
+    (
+        $(
+            $(#[$attr:meta])*
+            $vis:vis static progmem string $name:ident = $text:literal $(| $short:literal)?;
+        )*
+    ) => {
+        avr_progmem::progmem! {
+            $(
+                $(#[$attr])*
+                $vis static progmem string $name = $crate::menu_texts!(@select $text $(, $short)?);
+            )*
+        }
+
+        #[allow(dead_code)]
+        pub mod lengths {
+            $( pub const $name: usize = $crate::menu_texts!(@select $text $(, $short)?).len(); )*
+        }
+
+        #[allow(dead_code)]
+        pub mod caption_widths {
+            $(
+                pub const $name: usize =
+                    $crate::menu::widget::submenu_programa::macros::caption_width(
+                        $crate::menu_texts!(@select $text $(, $short)?),
+                    );
+            )*
+        }
+
+        #[allow(dead_code)]
+        pub mod signed_fields {
+            $(
+                pub const $name: bool =
+                    $crate::menu::widget::submenu_programa::macros::has_signed_field(
+                        $crate::menu_texts!(@select $text $(, $short)?),
+                    );
+            )*
+        }
+
+        $(
+            const _: () = assert!(
+                $crate::menu::widget::submenu_programa::macros::drawn_length($text)
+                    <= $crate::menu::widget::submenu_programa::macros::MENU_NUMBER_OF_COLUMNS
+                        - $crate::menu::widget::submenu_programa::macros::CAPTION_COLLUNM,
+                "Menu text does not fit in the lcd line"
+            );
+        )*
+    };
+
+    (@select $text:literal) => {
+        $text
+    };
+
+    (@select $text:literal, $short:literal) => {
+        $crate::menu::widget::submenu_programa::macros::select_text($text, $short)
+    };
+}
+
+/// Constructs one menu item of a [`submenu!`], the available kinds of menu items are:
+///
+/// * `simple_menu(CAPTION => ChildSubmenu)`
+/// * `simple_menu_with_numerical(CAPTION => ChildSubmenu, (collunm, &cell), valid_range)`
+/// * `numerical(CAPTION, (collunm, &cell), valid_range)`
+/// * `numerical(CAPTION, (collunm, &cell), valid_range, (collunm, UNIT_OF_MEASUREMENT))`
+/// * `template(TEMPLATE, &cell, valid_range)`, where the template places the field and its unit of
+///   measurement (ie: `"Retardo ${n.nnn} seg"`, see: `NumericalTemplate`)
+/// * `optional(CAPTION, (collunm, &cell), [OPTION_1, OPTION_2, ...])`
+/// * `action(CAPTION => Action, [fields...])`, where each field is one of:
+///     * `numerical(collunm, &cell, valid_range)`
+///     * `password(collunm, &cell, valid_range)`, a numerical field which hides its digits
+///     * `optional(collunm, &cell, [OPTION_1, OPTION_2, ...])`
+///
+/// The collunms are the ones of the line of the menu ([`MENU_NUMBER_OF_COLUMNS`] wide), the fields
+/// are placed in the lcd relative to its right edge (see: [`place_collunm`]). Each field is checked
+/// in compile time to fit in the line of the menu and in the lcd line (ie: the collunm plus the
+/// width of the field), the texts must be declared with [`menu_texts!`] to know its length.
+#[macro_export]
+macro_rules! menu_item {
+    (@check_field $col:literal, $width:expr) => {
+        $crate::menu_item!(
+            @check_field $col,
+            $width,
+            $crate::menu::widget::submenu_programa::macros::MENU_NUMBER_OF_COLUMNS
+        );
+    };
+
+    (@check_field $col:literal, $width:expr, $end:expr) => {
+        const _: () = assert!(
+            $col + $width <= $end,
+            "Menu item field does not fit in the lcd line"
+        );
+    };
+
+    (@place $col:literal, $width:expr) => {{
+        const COLLUNM: u8 = $crate::menu::widget::submenu_programa::macros::place_collunm(
+            $col,
+            $crate::menu::widget::submenu_programa::macros::CAPTION_COLLUNM,
+        );
+        const _: () = assert!(
+            COLLUNM as usize + $width <= $crate::board::lcd::NUMBER_OF_COLS as usize,
+            "Menu item field does not fit in the lcd line"
+        );
+        COLLUNM
+    }};
+
+    (@count $field:ident) => {
+        1
+    };
+
+    (@field_collunm $field:ident($col:literal, $($args:tt)*)) => {
+        $col
+    };
+
+    (@field_width numerical($col:literal, $variable:expr, $range:expr $(,)?)) => {
+        $crate::menu_item!(@numerical_width $range)
+    };
+
+    (@field_width password($col:literal, $variable:expr, $range:expr $(,)?)) => {
+        $crate::menu_item!(@numerical_width $range)
+    };
+
+    (@field_width optional($col:literal, $variable:expr, [$($option:ident),+ $(,)?] $(,)?)) => {
+        $crate::menu_item!(@optional_width [$($option),+])
+    };
+
+    (@numerical_width $range:expr) => {
+        $crate::menu::widget::submenu_programa::macros::numerical_field_width($range.end)
+    };
+
+    (@optional_width [$($option:ident),+]) => {
+        $crate::menu::widget::submenu_programa::macros::optional_field_width(&[
+            $( lengths::$option ),+
+        ])
+    };
+
+    (@options [$($option:ident),+]) => {
+        [$( $crate::string::flash::FlashString::new(&$option) ),+]
+    };
+
+    (@field $fields:ident, $collunm:expr, numerical($col:literal, $variable:expr, $range:expr $(,)?)) => {
+        $crate::menu_item!(@check_field $col, $crate::menu_item!(@numerical_width $range));
+        let field = $crate::menu::widget::field::builder::FieldBuilder::numerical($variable)
+            .valid_range($range)
+            .build();
+        $crate::menu::widget::menu_item::menu_item::MenuItemWidget::push_field(
+            &mut $fields,
+            ($crate::geometry::point::Point1d::new($collunm), field),
+        );
+    };
+
+    (@field $fields:ident, $collunm:expr, password($col:literal, $variable:expr, $range:expr $(,)?)) => {
+        $crate::menu_item!(@check_field $col, $crate::menu_item!(@numerical_width $range));
+        let field = $crate::menu::widget::field::builder::FieldBuilder::numerical($variable)
+            .valid_range($range)
+            .masked()
+            .build();
+        $crate::menu::widget::menu_item::menu_item::MenuItemWidget::push_field(
+            &mut $fields,
+            ($crate::geometry::point::Point1d::new($collunm), field),
+        );
+    };
+
+    (@field
+        $fields:ident,
+        $collunm:expr,
+        optional($col:literal, $variable:expr, [$($option:ident),+ $(,)?] $(,)?)
+    ) => {
+        $crate::menu_item!(@check_field $col, $crate::menu_item!(@optional_width [$($option),+]));
+        let field = $crate::menu::widget::field::builder::FieldBuilder::optional(
+            $variable,
+            $crate::menu_item!(@options [$($option),+]),
+        )
+        .build();
+        $crate::menu::widget::menu_item::menu_item::MenuItemWidget::push_field(
+            &mut $fields,
+            ($crate::geometry::point::Point1d::new($collunm), field),
+        );
+    };
+
+    (simple_menu($text:ident => $child:ident $(,)?)) => {
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_simple_menu(
+            $crate::menu::widget::menu_item::builder::SimpleMenu {
+                parent_name: $crate::string::flash::FlashString::new(&$text),
+                child_menu:
+                    $crate::menu::widget::submenu_programa::spec::MenuProgramaAreanaSelector::$child,
+            },
+        )
+    };
+
+    (simple_menu_with_numerical(
+        $text:ident => $child:ident,
+        ($col:literal, $variable:expr),
+        $range:expr $(,)?
+    )) => {{
+        $crate::menu_item!(@check_field $col, $crate::menu_item!(@numerical_width $range));
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_simple_menu_with_parameter(
+            $crate::menu::widget::menu_item::builder::SimpleMenuWithNumericalParameter {
+                parameter_name: $crate::string::flash::FlashString::new(&$text),
+                variable: (
+                    $crate::menu_item!(@place $col, $crate::menu_item!(@numerical_width $range)),
+                    $variable,
+                ),
+                valid_range: $range,
+                unit_of_measurement_text: None,
+                child_menu:
+                    $crate::menu::widget::submenu_programa::spec::MenuProgramaAreanaSelector::$child,
+            },
+        )
+    }};
+
+    (numerical($text:ident, ($col:literal, $variable:expr), $range:expr $(,)?)) => {{
+        $crate::menu_item!(@check_field $col, $crate::menu_item!(@numerical_width $range));
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_numerical_parameter(
+            $crate::menu::widget::menu_item::builder::NumericalParameter {
+                parameter_name: $crate::string::flash::FlashString::new(&$text),
+                variable: (
+                    $crate::menu_item!(@place $col, $crate::menu_item!(@numerical_width $range)),
+                    $variable,
+                ),
+                valid_range: $range,
+                unit_of_measurement_text: None,
+            },
+        )
+    }};
+
+    (numerical(
+        $text:ident,
+        ($col:literal, $variable:expr),
+        $range:expr,
+        ($unit_col:literal, $unit:ident) $(,)?
+    )) => {{
+        $crate::menu_item!(
+            @check_field $col,
+            $crate::menu_item!(@numerical_width $range),
+            $unit_col
+        );
+        $crate::menu_item!(@check_field $unit_col, lengths::$unit);
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_numerical_parameter(
+            $crate::menu::widget::menu_item::builder::NumericalParameter {
+                parameter_name: $crate::string::flash::FlashString::new(&$text),
+                variable: (
+                    $crate::menu_item!(@place $col, $crate::menu_item!(@numerical_width $range)),
+                    $variable,
+                ),
+                valid_range: $range,
+                unit_of_measurement_text: Some((
+                    $crate::menu_item!(@place $unit_col, lengths::$unit),
+                    $crate::string::flash::FlashString::new(&$unit),
+                )),
+            },
+        )
+    }};
+
+    (template($text:ident, $variable:expr, $range:expr $(,)?)) => {{
+        const _: () = assert!(
+            !signed_fields::$text
+                || $range.end <= $crate::menu::widget::field::numerical::Format::MAX_SIGNED_END,
+            "The valid range of a signed field must fit in an i16"
+        );
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_numerical_parameter_from_template(
+            $crate::menu::widget::menu_item::builder::NumericalTemplate {
+                template: $crate::string::flash::FlashString::new(&$text),
+                variable: $variable,
+                valid_range: $range,
+            },
+        )
+    }};
+
+    (optional($text:ident, ($col:literal, $variable:expr), [$($option:ident),+ $(,)?] $(,)?)) => {{
+        $crate::menu_item!(@check_field $col, $crate::menu_item!(@optional_width [$($option),+]));
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_optional_parameter(
+            $crate::menu::widget::menu_item::builder::OptionalParameter {
+                parameter_name: $crate::string::flash::FlashString::new(&$text),
+                variable: (
+                    $crate::menu_item!(@place $col, $crate::menu_item!(@optional_width [$($option),+])),
+                    $variable,
+                ),
+                options_list: $crate::menu::widget::field::optional::make_options_buffer_from_array(
+                    $crate::menu_item!(@options [$($option),+]),
+                ),
+            },
+        )
+    }};
+
+    (action($text:ident => $action:ident, [$( $field:ident $field_args:tt ),+ $(,)?] $(,)?)) => {{
+        const COLLUNMS: [u8; 0 $( + $crate::menu_item!(@count $field) )+] =
+            $crate::menu::widget::submenu_programa::macros::place_fields([$((
+                $crate::menu_item!(@field_collunm $field $field_args),
+                $crate::menu_item!(@field_width $field $field_args),
+            )),+]);
+        let mut collunms = COLLUNMS.into_iter();
+        let mut fields = $crate::menu::widget::menu_item::menu_item::FieldsBuffer::new();
+        $(
+            let collunm = collunms.next().unwrap_or_default();
+            $crate::menu_item!(@field fields, collunm, $field $field_args);
+        )+
+        $crate::menu::widget::menu_item::builder::MenuItemBuilder::make_action_with_parameters(
+            $crate::menu::widget::menu_item::builder::ActionWithParameters {
+                parameter_name: $crate::string::flash::FlashString::new(&$text),
+                fields,
+                action: $crate::menu::widget::submenu_programa::spec::MenuProgramaAction::$action,
+            },
+        )
+    }};
+}
+
+/// Declares a submenu and implements its [`SubmenuLayout`].
+///
+/// The identifier between parenthesis names the `&DataModel` inside the items' expressions, and
+/// the `access_level` is the [`NivelDeAcesso`] required to edit the items of the submenu which are
+/// not annotated with `#[access_level(Level)]`. See [`menu_item!`] for the kinds of items.
+///
+/// The captions of the items are listed in the associated constant `ITEMS`, in the order they are
+/// shown, so the index of an item is found by its caption (see: [`item_index!`]). Each caption is
+/// checked in compile time to fit in the lcd line in the narrower lcds, where it has its own line.
+///
+/// [`SubmenuLayout`]: crate::menu::widget::submenu_programa::core::SubmenuLayout
+/// [`NivelDeAcesso`]: crate::menu::model::NivelDeAcesso
+#[macro_export]
+macro_rules! submenu {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident($model:ident) {
+            access_level: $level:ident,
+            items: [
+                $(
+                    $(#[$modifier:ident $modifier_args:tt])*
+                    $kind:ident($text:ident $($args:tt)*)
+                ),* $(,)?
+            ]
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name<'a> {
+            model: &'a $crate::menu::model::DataModel,
+        }
+
+        impl<'a> $name<'a> {
+            pub const fn new(model: &'a $crate::menu::model::DataModel) -> Self {
+                Self { model }
+            }
+
+            #[allow(dead_code)]
+            pub const ITEMS: &'static [&'static str] = &[$( stringify!($text) ),*];
+        }
+
+        $(
+            const _: () = assert!(
+                !$crate::menu::widget::submenu_programa::macros::IS_NARROW_LAYOUT
+                    || caption_widths::$text
+                        $( + $crate::submenu!(@suffix_width $modifier) )*
+                        <= $crate::board::lcd::NUMBER_OF_COLS as usize
+                            - $crate::menu::widget::submenu_programa::macros::CAPTION_COLLUNM,
+                "Menu item caption does not fit in the lcd line, declare a short one"
+            );
+        )*
+
+        impl $crate::menu::widget::submenu_programa::core::SubmenuLayout for $name<'_> {
+            fn get_item(
+                &self,
+                index: usize,
+            ) -> Option<$crate::menu::widget::menu_item::menu_item::MenuItemWidget> {
+                #[allow(unused_variables)]
+                let $model = self.model;
+                let mut _item_index: usize = 0;
+                $(
+                    if index == _item_index {
+                        #[allow(unused_mut)]
+                        let mut item = $crate::menu_item!($kind($text $($args)*));
+                        $(
+                            $crate::submenu!(@decorate item, $modifier $modifier_args);
+                        )*
+                        return item;
+                    }
+                    _item_index += 1;
+                )*
+                None
+            }
+
+            fn before_go_to_child(
+                &self,
+                _index: usize,
+            ) -> Result<(), $crate::menu::model::ModelError> {
+                #[allow(unused_variables)]
+                let $model = self.model;
+                let mut _item_index: usize = 0;
+                $(
+                    $(
+                        $crate::submenu!(
+                            @before_go_to_child _index == _item_index,
+                            $modifier $modifier_args
+                        );
+                    )*
+                    _item_index += 1;
+                )*
+                Ok(())
+            }
+
+            fn get_access_level(&self, _index: usize) -> $crate::menu::model::NivelDeAcesso {
+                let mut _item_index: usize = 0;
+                $(
+                    $(
+                        $crate::submenu!(
+                            @access_level _index == _item_index,
+                            $modifier $modifier_args
+                        );
+                    )*
+                    _item_index += 1;
+                )*
+                $crate::menu::model::NivelDeAcesso::$level
+            }
+
+            fn len(&self) -> usize {
+                0 $( + $crate::submenu!(@count $kind) )*
+            }
+        }
+    };
+
+    (@access_level $condition:expr, access_level($level:ident)) => {
+        if $condition {
+            return $crate::menu::model::NivelDeAcesso::$level;
+        }
+    };
+
+    (@access_level $condition:expr, before_go_to_child $hook:tt) => {};
+
+    (@access_level $condition:expr, axis_suffix $eixo:tt) => {};
+
+    (@before_go_to_child $condition:expr, before_go_to_child($hook:expr)) => {
+        if $condition {
+            return $hook;
+        }
+    };
+
+    (@before_go_to_child $condition:expr, access_level $level:tt) => {};
+
+    (@before_go_to_child $condition:expr, axis_suffix $eixo:tt) => {};
+
+    (@decorate $item:ident, axis_suffix($eixo:expr)) => {
+        $item = $item.map(|item| {
+            item.with_caption_suffix(
+                $crate::menu::widget::submenu_programa::macros::axis_suffix($eixo),
+            )
+        });
+    };
+
+    (@decorate $item:ident, $modifier:ident $modifier_args:tt) => {};
+
+    (@suffix_width axis_suffix) => {
+        $crate::menu::widget::submenu_programa::macros::AXIS_SUFFIX_WIDTH
+    };
+
+    (@suffix_width $modifier:ident) => {
+        0
+    };
+
+    (@count $kind:ident) => {
+        1
+    };
+}
+
+/// Index of a menu item in its [`submenu!`], given by the caption of the item. It is evaluated in
+/// compile time, so it does not compile if the submenu has not the item.
+///
+/// ```
+/// let index = item_index!(MenuParametrosDeMovimento::POSICAO_FINAL);
+/// ```
+#[macro_export]
+macro_rules! item_index {
+    ($submenu:ident::$caption:ident) => {{
+        const INDEX: usize = $crate::menu::widget::submenu_programa::macros::index_of_item(
+            $submenu::ITEMS,
+            stringify!($caption),
+        );
+        INDEX
+    }};
+}
+
+/// Declares the selector of the submenus and the arena which stores all of them. The name of each
+/// submenu is used as the name of its selector variant and of its field in the arena.
+///
+/// NOTE: It must be invoked in `spec.rs` because it uses the private `Register` constructor.
+#[macro_export]
+macro_rules! menu_arena {
+    (
+        $(#[$selector_attr:meta])*
+        $selector_vis:vis enum $selector:ident;
+
+        $(#[$arena_attr:meta])*
+        $arena_vis:vis struct $arena:ident {
+            $( $menu:ident ),* $(,)?
+        }
+    ) => {
+        $(#[$selector_attr])*
+        #[derive(Copy, Clone, PartialEq)]
+        $selector_vis enum $selector {
+            $( $menu, )*
+        }
+
+        $(#[$arena_attr])*
+        $arena_vis struct $arena<'a> {
+            model: &'a $crate::menu::model::DataModel,
+            $( pub $menu: RegisterSubMenu<$menu<'a>>, )*
+        }
+
+        impl<'a> $arena<'a> {
+            /// Constructs all the menus and initializes its internal state
+            pub fn new(model: &'a $crate::menu::model::DataModel) -> Self {
+                Self {
+                    model,
+                    $( $menu: Register::from_submenu($menu::new(model)), )*
+                }
+            }
+
+            /// Retrieves an menu item given the sub menu and the index number of the menu item.
+            /// If index is out of range than returns None
+            pub fn get_item(
+                &self,
+                menu_selector: $selector,
+                index: usize,
+            ) -> Option<$crate::menu::widget::menu_item::menu_item::MenuItemWidget> {
+                match menu_selector {
+                    $( $selector::$menu => self.$menu.get_item(index), )*
+                }
+            }
+
+            /// Gets the navigation state of the submenu
+            pub fn get_navigation_state(
+                &self,
+                menu_selector: $selector,
+            ) -> &core::cell::Cell<NavigationStateModel> {
+                match menu_selector {
+                    $( $selector::$menu => self.$menu.get_navigation_state(), )*
+                }
+            }
+
+            /// Prepares the model before go to the child submenu of the given menu item.
+            pub fn before_go_to_child(
+                &self,
+                menu_selector: $selector,
+                index: usize,
+            ) -> Result<(), $crate::menu::model::ModelError> {
+                match menu_selector {
+                    $( $selector::$menu => self.$menu.before_go_to_child(index), )*
+                }
+            }
+
+            /// Access level required to edit the given menu item
+            fn get_access_level(
+                &self,
+                menu_selector: $selector,
+                index: usize,
+            ) -> $crate::menu::model::NivelDeAcesso {
+                match menu_selector {
+                    $( $selector::$menu => self.$menu.get_access_level(index), )*
+                }
+            }
+
+            /// Given an sub menu index, get the size of menu items inside it.
+            pub fn len(&self, menu_selector: $selector) -> usize {
+                match menu_selector {
+                    $( $selector::$menu => self.$menu.len(), )*
+                }
+            }
+        }
+    };
+}
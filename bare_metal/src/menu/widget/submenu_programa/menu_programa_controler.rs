@@ -1,26 +1,95 @@
 use core::cell::Cell;
 
 use super::{
-    super::menu_item::menu_item::MenuItemWidget, hepers::LcdLine,
+    super::menu_item::menu_item::MenuItemWidget,
+    hepers::LcdLine,
+    macros::{LINES_PER_MENU_ITEM, NUMBER_OF_VISIBLE_ITEMS},
     navigation_state::NavigationStateModel,
 };
+use crate::board::lcd::glyph::Glyph;
 use crate::fatal_error;
 use crate::geometry::point::Point;
+use crate::geometry::rectangle::Rectangle;
 use crate::menu::widget::widget::Widget;
 use crate::microcontroler::ratangular_wave::RectangularWave;
+use crate::string::flash::FlashString;
 use crate::{
-    board::{keypad::KeyCode, lcd},
+    board::keypad::KeyCode,
     menu::{
-        screen_buffer::ScreenBuffer,
-        widget::submenu_programa::spec::{MenuProgramaAreanaSelector, MenuProgramaArena},
+        model::{Eixo, ModelError, RegraDeValidacao, NUMERO_MAXIMO_DE_PROGRAMAS},
+        screen_buffer::ScreenRegion,
+        widget::{
+            dialog::{ConfirmationBox, Dialog, DialogResult, MessageBox, ModalDialog},
+            navigation::{Navigation, ReturnValue, Screen},
+            submenu_programa::{
+                flash_texts::{
+                    ACESSO_NEGADO, CORRIJA_O_PROGRAMA_DO_EIXO_X, CORRIJA_O_PROGRAMA_DO_EIXO_Y,
+                    ENTER_CONFIRMA_ESC_CANCELA, MAIOR_NUMERO_PERMITIDO,
+                    NIVEL_DE_ACESSO_INSUFICIENTE, NUMERO_DO_PROGRAMA_INVALIDO, PROGRAMA_NAO_SALVO,
+                    SAIA_DO_MENU_PARA_SALVAR, SALVAR_ALTERACOES, SENHA_INVALIDA, SIM_NAO_CONTINUAR,
+                },
+                spec::{
+                    get_rule_location, get_rule_message, MenuProgramaAction,
+                    MenuProgramaAreanaSelector, MenuProgramaArena,
+                },
+            },
+        },
     },
-    microcontroler::delay::delay_ms,
 };
+use cross_platform::types::time::Duration;
 use cross_platform::utils::numerical::usize_to_u8_clamper;
 use heapless::Vec;
 
 /////////////////////////////////
 
+/// Maximum number of menu items visible on screen, one per line of the menu
+const NUMBER_OF_MOUNTED_ITEMS: usize = NUMBER_OF_VISIBLE_ITEMS as usize;
+
+/// Char drawn in the first column of the menu items which the user has not access to edit
+const READ_ONLY_MARKER: u8 = Glyph::Lock.as_u8();
+
+/// Message box to show a model error to the user
+fn model_error_message(error: ModelError) -> MessageBox {
+    match error {
+        ModelError::InvalidProgramNumber(_) => MessageBox::new(
+            FlashString::new(&NUMERO_DO_PROGRAMA_INVALIDO),
+            FlashString::new(&MAIOR_NUMERO_PERMITIDO),
+        )
+        .with_value(NUMERO_MAXIMO_DE_PROGRAMAS - 1),
+        ModelError::InvalidPassword => {
+            MessageBox::new(FlashString::new(&SENHA_INVALIDA), FlashString::default())
+        }
+        ModelError::AccessDenied => MessageBox::new(
+            FlashString::new(&ACESSO_NEGADO),
+            FlashString::new(&NIVEL_DE_ACESSO_INSUFICIENTE),
+        ),
+        ModelError::UnsavedChanges => MessageBox::new(
+            FlashString::new(&PROGRAMA_NAO_SALVO),
+            FlashString::new(&SAIA_DO_MENU_PARA_SALVAR),
+        ),
+    }
+}
+
+/// Message box to show to the user the validation rule violated by the program of the given axis
+fn rule_violation_message(eixo: Eixo, regra: RegraDeValidacao) -> MessageBox {
+    let axis_message = match eixo {
+        Eixo::X => FlashString::new(&CORRIJA_O_PROGRAMA_DO_EIXO_X),
+        Eixo::Y => FlashString::new(&CORRIJA_O_PROGRAMA_DO_EIXO_Y),
+    };
+    MessageBox::new(get_rule_message(regra), axis_message)
+}
+
+/// Why a dialog was opened on top of the menu, it defines what is done with its result
+#[derive(Copy, Clone)]
+enum DialogReason {
+    /// Just informs something to the user (ie: errors)
+    Information,
+    /// Asks the user confirmation before executing the action
+    ActionConfirmation(MenuProgramaAction),
+    /// Asks the user if the changes are saved or discarded when leaving the menu
+    ExitConfirmation,
+}
+
 /// Responsible to render the menu on the screen
 ///
 /// TODO: Improve error handling
@@ -29,17 +98,18 @@ pub struct MenuProgramaControler<'a> {
     menu_arena: &'a MenuProgramaArena<'a>,
     /// Points to the current menu being displayed on screen
     current_menu: MenuProgramaAreanaSelector,
-    /// State of widgets which are currently mounted and therefore visible on screen.
-    /// TODO: Is not necessary to have two MenuItemWidget states on memory but just one. Introduce some logic when possible
+    /// State of widgets which are currently mounted and therefore visible on screen, the item of
+    /// each lcd line is stored at the index of the line. Submenus with less items than lcd lines
+    /// leave the last lines empty.
+    /// TODO: Is not necessary to have one MenuItemWidget state per line on memory but just one. Introduce some logic when possible
     /// to optimize this.
-    mounted: [MenuItemWidget<'a>; 2], // TOTAL_NUMBER_OF_LINES_IN_LCD as usize],
+    mounted: Vec<MenuItemWidget<'a>, NUMBER_OF_MOUNTED_ITEMS>,
     /// Stores the path of menu jumps that user perform, so you can go back to previous menu
     navigation_path: Vec<MenuProgramaAreanaSelector, 7>,
-    /// Main menu reads this bit, if it is set then it will render the main menu. When menu_menu pass control
-    /// do menu_programa it resets this bit, and then menu_program is responsible to set it when it want to give
-    /// back control to main_menu.
-    /// TODO: Improve this communication methodology
-    pub must_return_to_main_menu: bool,
+    /// Set when the menu gives back the control to the main menu
+    navigation: Option<Navigation>,
+    /// Dialog opened on top of the menu, alongside the reason why it was opened
+    dialog: Option<(DialogReason, ModalDialog)>,
     /// Blinks navigation cursor the select each item of the menu
     /// TODO: Move the blink code to the Caption widget
     blink: RectangularWave,
@@ -51,27 +121,22 @@ impl<'a> MenuProgramaControler<'a> {
         menu_arena: &'a MenuProgramaArena,
     ) -> Self {
         // Configuring character blinking
-        const T_ON: u16 = 500;
-        const T_OFF: u16 = 500;
+        const T_ON: Duration = Duration::from_millis(500);
+        const T_OFF: Duration = Duration::from_millis(500);
         let blink = RectangularWave::new(T_ON, T_OFF);
 
-        // Mount menu itens that will be visible on the screen
-        let (Some(fist_menu_item), Some(second_menu_item)) = (
-            menu_arena.get_item(current_menu, 0),
-            menu_arena.get_item(current_menu, 1),
-        ) else {
-            // TODO: currently we do not accept submenus with less then 2 menu_items, accept it when possible
-            fatal_error!(100);
-        };
-
-        Self {
+        let mut menu_programa_controler = Self {
             menu_arena,
-            mounted: [fist_menu_item, second_menu_item],
+            mounted: Vec::new(),
             current_menu,
             navigation_path: Vec::new(),
-            must_return_to_main_menu: false,
+            navigation: None,
+            dialog: None,
             blink,
-        }
+        };
+        // Mount menu itens that will be visible on the screen
+        menu_programa_controler.mount();
+        menu_programa_controler
     }
 
     // -----------------------------------------------------------
@@ -85,11 +150,10 @@ impl<'a> MenuProgramaControler<'a> {
     /// Mount widgets that are being renderized
     /// TODO: Consider rename to `redraw`. (Hum! Maybe no because I'm saving the Widgets but not
     /// running the .draw method of it. It represents just some internal `model` change)
-    /// TODO: Check if this functoin can be reused in the constructor (Self::new). Because it seems that
-    /// the code is duplicated.
     fn mount(&mut self) {
         // Algorithm: For each line of the Lcd recriates the menu_item Widgets based in the
-        // current navigation state and overwrite old widgets.
+        // current navigation state and overwrite old widgets, until the submenu has no more items.
+        self.mounted.clear();
         for lcd_line in LcdLine::iterator() {
             let current_menu_item = self.menu_arena.get_item(
                 self.current_menu,
@@ -97,12 +161,12 @@ impl<'a> MenuProgramaControler<'a> {
                     .get()
                     .get_current_index_for(lcd_line) as usize,
             );
-            let mounted = self.mounted.get_mut(lcd_line as u8 as usize);
-            if let (Some(menu_item_widget), Some(mounting_place)) = (current_menu_item, mounted) {
-                *mounting_place = menu_item_widget;
-            } else {
-                fatal_error!(103); // Menu mounting error
+            let Some(menu_item_widget) = current_menu_item else {
+                break; // the remaining lines are empty
             };
+            if self.mounted.push(menu_item_widget).is_err() {
+                fatal_error!(103); // Menu mounting error
+            }
         }
     }
 
@@ -182,12 +246,63 @@ impl<'a> MenuProgramaControler<'a> {
         }
     }
 
+    /// Goes to the child submenu of the menu item mounted in the given lcd line, if the model cannot be
+    /// prepared to it (ie: invalid program number) then shows the error and stays in the current menu.
+    fn try_go_to_child(&mut self, lcd_line: LcdLine, child: MenuProgramaAreanaSelector) {
+        let index = self
+            .retrieve_current_menu_navigation_state()
+            .get()
+            .get_current_index_for(lcd_line) as usize;
+        match self.menu_arena.before_go_to_child(self.current_menu, index) {
+            Ok(()) => self.go_to_child(child),
+            Err(error) => {
+                self.open_dialog(DialogReason::Information, model_error_message(error));
+                // remounts to show the restored parameter value
+                self.mount();
+            }
+        }
+    }
+
+    /// Executes the action of a menu item, or asks the user confirmation before if the action
+    /// requires it.
+    fn request_action(&mut self, action: MenuProgramaAction) {
+        match action.get_confirmation_message() {
+            Some(message) => self.open_dialog(
+                DialogReason::ActionConfirmation(action),
+                ConfirmationBox::new(message, FlashString::new(&ENTER_CONFIRMA_ESC_CANCELA)),
+            ),
+            None => self.execute_action(action),
+        }
+    }
+
+    fn execute_action(&mut self, action: MenuProgramaAction) {
+        match self.menu_arena.execute_action(action) {
+            Ok(()) => {
+                if action.must_return_to_main_menu() {
+                    self.return_to_main_menu();
+                }
+            }
+            Err(error) => self.open_dialog(DialogReason::Information, model_error_message(error)),
+        }
+        // remounts to show the updated values
+        self.mount();
+    }
+
     fn back_to_parent(&mut self) {
+        // the submenu is not left while its parameters violate some validation rule
+        if let Err((eixo, regra)) = self.menu_arena.validate(self.current_menu) {
+            self.open_dialog(
+                DialogReason::Information,
+                rule_violation_message(eixo, regra),
+            );
+            self.go_to_violated_rule(regra);
+            return;
+        }
         // pops parent from navigation path
         let parent = match self.navigation_path.pop() {
             Some(parent) => parent,
             None => {
-                self.must_return_to_main_menu = true;
+                self.return_to_main_menu();
                 self.current_menu
             }
         };
@@ -195,6 +310,101 @@ impl<'a> MenuProgramaControler<'a> {
         self.go_to_menu(parent)
     }
 
+    /// Gives back the control to the main menu, which sends the changed data to the boards. If the
+    /// program of some axis violates a validation rule, the user is taken to fix it instead. If
+    /// there are unsaved changes the user is asked to save or discard them before.
+    fn return_to_main_menu(&mut self) {
+        match self.menu_arena.validate_all() {
+            Ok(()) => {
+                if self.menu_arena.has_unsaved_changes() {
+                    self.open_dialog(
+                        DialogReason::ExitConfirmation,
+                        ConfirmationBox::new(
+                            FlashString::new(&SALVAR_ALTERACOES),
+                            FlashString::new(&SIM_NAO_CONTINUAR),
+                        ),
+                    );
+                } else {
+                    self.leave();
+                }
+            }
+            Err((eixo, regra)) => {
+                self.open_dialog(
+                    DialogReason::Information,
+                    rule_violation_message(eixo, regra),
+                );
+                self.go_to_violated_rule(regra);
+            }
+        }
+    }
+
+    /// Pops the menu, telling the main menu if the programs must be sent to the boards
+    fn leave(&mut self) {
+        let value = if self.menu_arena.has_pending_upload() {
+            ReturnValue::ProgramasAlterados
+        } else {
+            ReturnValue::Nothing
+        };
+        self.navigation = Some(Navigation::Pop(value));
+    }
+
+    /// Navigates to the submenu of the `Arquivo de Eixo` being edited and selects the menu item
+    /// which must be edited to fix the given validation rule.
+    fn go_to_violated_rule(&mut self, regra: RegraDeValidacao) {
+        let (menu_selector, index) = get_rule_location(regra);
+        if self.current_menu == MenuProgramaAreanaSelector::MenuPrograma {
+            self.go_to_child(MenuProgramaAreanaSelector::MenuArquivoDeEixo);
+        }
+        self.go_to_child(menu_selector);
+        let size_of_menu = self.menu_arena.len(menu_selector) as u8;
+        self.retrieve_current_menu_navigation_state()
+            .update(|mut nav| {
+                nav.select_index(index as u8, size_of_menu);
+                nav
+            });
+        self.mount();
+    }
+
+    // -----------------------------------------------------------
+    //            DIALOGS
+    // -----------------------------------------------------------
+
+    /// Opens a dialog on top of the menu, it receives all the keys until it is closed
+    fn open_dialog(&mut self, reason: DialogReason, dialog: impl Into<ModalDialog>) {
+        self.dialog = Some((reason, dialog.into()));
+    }
+
+    /// If the dialog has a result then closes it and handles the result according the reason
+    /// why the dialog was opened.
+    fn handle_dialog_result(&mut self) {
+        let Some((reason, result)) = self
+            .dialog
+            .as_ref()
+            .and_then(|(reason, dialog)| dialog.get_result().map(|result| (*reason, result)))
+        else {
+            return;
+        };
+        self.dialog = None;
+        match (reason, result) {
+            (DialogReason::ActionConfirmation(action), DialogResult::Yes) => {
+                self.execute_action(action)
+            }
+            (DialogReason::ExitConfirmation, DialogResult::Yes) => {
+                self.menu_arena.save_changes();
+                self.leave();
+            }
+            (DialogReason::ExitConfirmation, DialogResult::No) => {
+                self.menu_arena.discard_changes();
+                self.leave();
+                // remounts to show the restored values in the next time the menu is opened
+                self.mount();
+            }
+            _ => {
+                // information closed or confirmation canceled, nothing to do
+            }
+        }
+    }
+
     // -----------------------------------------------------------
     //            LINE BEING EDITED
     // -----------------------------------------------------------
@@ -204,38 +414,60 @@ impl<'a> MenuProgramaControler<'a> {
     fn get_line_being_edited(&self) -> Option<LcdLine> {
         self.mounted
             .iter()
-            .filter(|item| item.is_in_edit_mode())
             .enumerate()
+            .filter(|(_, item)| item.is_in_edit_mode())
             .next()
             .map(|x| (x.0 as u8).into())
     }
 
+    /// True if the current access level does not allow to edit the menu item mounted in the given lcd
+    /// line. Simple submenus (without fields or actions) are never read only.
+    fn is_read_only(&self, lcd_line: LcdLine) -> bool {
+        let menu_item = self.get_mounted_item_for_lcd_line(lcd_line);
+        let is_simple_submenu = !menu_item.has_field() && menu_item.action.is_none();
+        let index = self
+            .retrieve_current_menu_navigation_state()
+            .get()
+            .get_current_index_for(lcd_line) as usize;
+        !is_simple_submenu && !self.menu_arena.has_access(self.current_menu, index)
+    }
+
     // -----------------------------------------------------------
     //            DRAWING HELPER
     // -----------------------------------------------------------
 
-    /// helper function to draw submenu cursor on screen
-    fn draw_menu_item_selector(&self, line: LcdLine, screen_buffer: &mut ScreenBuffer) {
+    /// helper function to draw submenu cursor in the first column of the region of the line
+    fn draw_menu_item_selector(&self, line: LcdLine, line_region: &mut ScreenRegion) {
         const EDITING_CURSOR: u8 = b'*';
         const NAVIGATING_CURSOR: u8 = b'>';
         const EMPTY_CURSOR: u8 = b' ';
         // position cursor
-        screen_buffer.set_cursor(Point::new(0, line as u8));
+        line_region.set_cursor(Point::new(0, 0));
         // draw selector char
         match self.get_line_being_edited() {
             Some(_line) => {
-                screen_buffer.print_u8(EDITING_CURSOR);
+                line_region.print_u8(EDITING_CURSOR);
             }
             None => {
                 let is_time_to_blink = self.blink.read();
                 if is_time_to_blink {
-                    screen_buffer.print_u8(NAVIGATING_CURSOR);
+                    line_region.print_u8(NAVIGATING_CURSOR);
+                } else if self.is_read_only(line) {
+                    line_region.print_u8(READ_ONLY_MARKER)
                 } else {
-                    screen_buffer.print_u8(EMPTY_CURSOR)
+                    line_region.print_u8(EMPTY_CURSOR)
                 }
             }
         }
     }
+
+    /// helper function to mark the read only menu items in the first column of the region of the line
+    fn draw_read_only_marker(&self, line: LcdLine, line_region: &mut ScreenRegion) {
+        if self.is_read_only(line) {
+            line_region.set_cursor(Point::new(0, 0));
+            line_region.print_u8(READ_ONLY_MARKER);
+        }
+    }
 }
 
 // -----------------------------------------------------------
@@ -245,18 +477,26 @@ impl<'a> MenuProgramaControler<'a> {
 impl<'a> Widget for MenuProgramaControler<'a> {
     /// TODO: Improve this code when possible
     fn send_key(&mut self, key: KeyCode) {
-        if let Some(line_being_edited) = self.get_line_being_edited() {
+        if let Some((_, dialog)) = &mut self.dialog {
+            // while a dialog is open it receives all the keys
+            dialog.send_key(key);
+            self.handle_dialog_result();
+        } else if let Some(line_being_edited) = self.get_line_being_edited() {
             // if is editing some line, delegate keys to sub widgets.
             let current_menu_item = self.get_mounted_item_for_lcd_line_mut(line_being_edited);
             current_menu_item.send_key(key);
             // if Enter key on a submenu with parameter, after editing the
             // parameter, we need to change to the submenu accordingly.
-            if key == KeyCode::KEY_ENTER {
+            // NOTE: Menu items with many fields are still in edit mode until the last field is saved
+            if key == KeyCode::KEY_ENTER && !current_menu_item.is_in_edit_mode() {
                 // we assume here that the field has already had its content saved
+                let (child, action) = (current_menu_item.child, current_menu_item.action);
+                self.menu_arena.register_change(self.current_menu);
 
-                if let Some(child_handle) = current_menu_item.child {
-                    current_menu_item.set_edit_mode(false);
-                    self.go_to_child(child_handle)
+                if let Some(child_handle) = child {
+                    self.try_go_to_child(line_being_edited, child_handle)
+                } else if let Some(action) = action {
+                    self.request_action(action);
                 }
             }
         } else {
@@ -271,19 +511,35 @@ impl<'a> Widget for MenuProgramaControler<'a> {
                 }
 
                 KeyCode::KEY_ENTER => {
+                    let selected_line = self
+                        .retrieve_current_menu_navigation_state()
+                        .get()
+                        .get_current_lcd_line();
+
+                    if self.is_read_only(selected_line) {
+                        // read only menu items cannot be edited, but its child submenu can be seen
+                        if let Some(child_handle) = self.get_selected_menu_item().child {
+                            self.try_go_to_child(selected_line, child_handle)
+                        }
+                        return;
+                    }
+
                     let current_menu_item = self.get_selected_menu_item();
 
-                    let has_field = current_menu_item.point_and_field.is_some();
+                    let has_field = current_menu_item.has_field();
 
                     if let Some(child_handle) = current_menu_item.child {
                         // TEMP CODE: if current mitem has a child submenu, opens it.
                         if !has_field {
                             // if it is a pure simple submenu (without parameter) jump straight to the submenu on enter
-                            self.go_to_child(child_handle)
+                            self.try_go_to_child(selected_line, child_handle)
                         } else {
                             // if sub menu has field (is not simple submenu) then process it first
                             current_menu_item.set_edit_mode(true);
                         };
+                    } else if let (false, Some(action)) = (has_field, current_menu_item.action) {
+                        // action without parameters is requested straight on enter
+                        self.request_action(action);
                     } else {
                         // Enters edit mode on sub-widgets.
                         current_menu_item.set_edit_mode(true);
@@ -302,28 +558,417 @@ impl<'a> Widget for MenuProgramaControler<'a> {
     }
 
     fn update(&mut self) {
+        // updates the dialog (ie: timeout of messages)
+        if let Some((_, dialog)) = &mut self.dialog {
+            dialog.update();
+            self.handle_dialog_result();
+        }
         // updates the blinker
         self.blink.update();
         // updates each line
-        for line in LcdLine::iterator() {
-            self.get_mounted_item_for_lcd_line_mut(line).update();
+        for menu_item in self.mounted.iter_mut() {
+            menu_item.update();
         }
     }
 
-    /// TODO: Check if it is possible to remove the unused `_start_point` argument.
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, _start_point: Point) {
+    fn draw(&self, region: &mut ScreenRegion) {
+        // draw the dialog opened on top of the menu
+        if let Some((_, dialog)) = &self.dialog {
+            dialog.draw(region);
+            return;
+        }
         // clear screen
-        screen_buffer.clear();
-        // draw menu item selector
-        let line = self
+        region.clear();
+        let selected_line = self
             .retrieve_current_menu_navigation_state()
             .get()
             .get_current_lcd_line();
-        self.draw_menu_item_selector(line, screen_buffer);
-        // draw menu items
-        for line in LcdLine::iterator() {
-            self.get_mounted_item_for_lcd_line(line)
-                .draw(screen_buffer, line);
+        for (line, menu_item) in LcdLine::iterator().zip(self.mounted.iter()) {
+            // each menu item is drawn in its own lines
+            let top = line as u8 * LINES_PER_MENU_ITEM;
+            let area =
+                Rectangle::from_size(Point::new(0, top), region.width(), LINES_PER_MENU_ITEM);
+            let mut line_region = region.sub_region(area);
+            // draw menu item selector
+            if line == selected_line {
+                self.draw_menu_item_selector(line, &mut line_region);
+            } else {
+                self.draw_read_only_marker(line, &mut line_region);
+            }
+            // draw menu item
+            menu_item.draw(&mut line_region);
+        }
+    }
+}
+
+impl<'a> Screen for MenuProgramaControler<'a> {
+    fn take_navigation(&mut self) -> Option<Navigation> {
+        self.navigation.take()
+    }
+
+    fn on_enter(&mut self) {
+        self.menu_arena.write_pending_changes();
+    }
+
+    /// Goes back submenu by submenu, so their validation rules are checked, until the menu is left.
+    /// The field being edited is canceled.
+    fn go_home(&mut self) {
+        // the user must answer the dialog which is open
+        if self.dialog.is_some() {
+            return;
+        }
+        if let Some(line_being_edited) = self.get_line_being_edited() {
+            self.get_mounted_item_for_lcd_line_mut(line_being_edited)
+                .send_key(KeyCode::KEY_ESC);
         }
+        while self.dialog.is_none() && self.navigation.is_none() {
+            self.back_to_parent();
+        }
+    }
+}
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::model::{DataModel, NivelDeAcesso, Registro};
+    use crate::menu::snapshot::{assert_screen, draw};
+    use crate::menu::widget::submenu_programa::spec::MenuProgramaArena;
+
+    #[test]
+    fn it_shows_the_first_items_of_the_menu() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        assert_eq!(
+            draw(&menu),
+            [
+                ">Editar numero do programa do eixo X:0  ",
+                " Editar numero do programa do eixo Y:0  ",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_scrolls_down_and_marks_the_read_only_items() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        let keyboard = ScriptedKeyboard::new().keys(&[
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+        ]);
+        // the operator cannot copy programs, so the selector alternates with the lock
+        assert_screen(
+            &mut menu,
+            keyboard,
+            &[
+                " Editar numero do programa do eixo Y:0",
+                "■Copiar programa [X] 0       [X] 1",
+            ],
+        );
+    }
+
+    #[test]
+    fn it_edits_the_program_number_and_enters_its_submenu() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        // waits the blinking cursor be shown over the single digit of the field
+        let keyboard = ScriptedKeyboard::new()
+            .keys(&[KeyCode::KEY_ENTER, KeyCode::KEY_3])
+            .wait(400);
+        assert_screen(
+            &mut menu,
+            keyboard,
+            &[
+                "*Editar numero do programa do eixo X:3",
+                " Editar numero do programa do eixo Y:0",
+            ],
+        );
+        assert_screen(
+            &mut menu,
+            ScriptedKeyboard::new().key(KeyCode::KEY_ENTER),
+            &[">Parametro de Movimento...", " Parametros de Impressao..."],
+        );
+        assert_eq!(model.gui_state.numero_do_programa(Eixo::X).get(), 3);
+        // ESC goes back to the parent menu (typed while the selector is shown)
+        assert_screen(
+            &mut menu,
+            ScriptedKeyboard::new().key_after(800, KeyCode::KEY_ESC),
+            &[
+                ">Editar numero do programa do eixo X:3",
+                " Editar numero do programa do eixo Y:0",
+            ],
+        );
+        assert_eq!(menu.take_navigation(), None);
+    }
+
+    #[test]
+    fn it_shows_the_delays_in_seconds() {
+        let model = DataModel::new();
+        let arquivo_de_eixo = model.get_arquivo_de_eixo_by_ref();
+        arquivo_de_eixo.retardo_no_start_automatico.set(1500);
+        arquivo_de_eixo.retardo_no_start_externo.set(25);
+        let arena = MenuProgramaArena::new(&model);
+        let menu =
+            MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuParametrosDeCiclo, &arena);
+        assert_eq!(
+            draw(&menu),
+            [
+                ">Retardo no start automatico   1.500 seg",
+                " Retardo no start externo      0.025 seg",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_discards_the_changes_when_the_user_does_not_save_them() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu =
+            MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuParametrosDeCiclo, &arena);
+        for key in [KeyCode::KEY_ENTER, KeyCode::KEY_7, KeyCode::KEY_ENTER] {
+            menu.send_key(key);
+        }
+        let arquivo_de_eixo = model.get_arquivo_de_eixo(Eixo::X);
+        let retardo = arquivo_de_eixo.retardo_no_start_automatico.get();
+        assert_ne!(retardo, 0);
+        // leaving the menu asks to save the change, nothing was written in eeprom before it
+        menu.send_key(KeyCode::KEY_ESC);
+        assert!(arena.has_unsaved_changes());
+        menu.send_key(KeyCode::KEY_0);
+        assert!(!arena.has_unsaved_changes());
+        assert_ne!(arquivo_de_eixo.retardo_no_start_automatico.get(), retardo);
+        assert!(menu.take_navigation().is_some());
+    }
+
+    #[test]
+    fn it_configures_the_axis_of_the_menu_item() {
+        let model = DataModel::new();
+        model.configuracao_do_eixo_x.numero_do_canal.set(1);
+        model.configuracao_do_eixo_y.numero_do_canal.set(2);
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        let to_configuracao_eixo_y = ScriptedKeyboard::new()
+            .keys(&[KeyCode::KEY_DIRECIONAL_PARA_BAIXO; 5])
+            .key(KeyCode::KEY_ENTER);
+        // the operator cannot edit the configuration, so the selector alternates with the lock
+        assert_screen(
+            &mut menu,
+            to_configuracao_eixo_y,
+            &[
+                "■Numero do canal Y               02",
+                "■Numero de pulso do giro Y       400",
+            ],
+        );
+        let to_configuracao_eixo_x = ScriptedKeyboard::new().keys(&[
+            KeyCode::KEY_ESC,
+            KeyCode::KEY_DIRECIONAL_PARA_CIMA,
+            KeyCode::KEY_ENTER,
+        ]);
+        assert_screen(
+            &mut menu,
+            to_configuracao_eixo_x,
+            &[
+                "■Numero do canal X               01",
+                "■Numero de pulso do giro X       400",
+            ],
+        );
+    }
+
+    #[test]
+    fn it_registers_the_change_in_the_configuration_of_the_axis_being_edited() {
+        let model = DataModel::new();
+        model
+            .gui_state
+            .nivel_de_acesso
+            .set(NivelDeAcesso::Supervisor);
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        for _ in 0..5 {
+            menu.send_key(KeyCode::KEY_DIRECIONAL_PARA_BAIXO);
+        }
+        for key in [
+            KeyCode::KEY_ENTER,
+            KeyCode::KEY_ENTER,
+            KeyCode::KEY_7,
+            KeyCode::KEY_ENTER,
+        ] {
+            menu.send_key(key);
+        }
+        assert_ne!(model.configuracao_do_eixo_y.numero_do_canal.get(), 0);
+        assert_eq!(model.configuracao_do_eixo_x.numero_do_canal.get(), 0);
+        let alteracoes = model.get_alteracoes();
+        assert!(alteracoes.contem(Registro::ConfiguracaoDoEixo(Eixo::Y)));
+        assert!(!alteracoes.contem(Registro::ConfiguracaoDoEixo(Eixo::X)));
+    }
+
+    #[test]
+    fn it_asks_to_send_the_programs_when_leaving() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        menu.send_key(KeyCode::KEY_ESC);
+        assert_eq!(
+            menu.take_navigation(),
+            Some(Navigation::Pop(ReturnValue::ProgramasAlterados))
+        );
+    }
+}
+
+/// The menu is designed for the 40x2 lcd, in the narrower ones each menu item takes two lines: its
+/// short caption and, below it, its fields kept at the right edge
+#[cfg(all(test, feature = "lcd_20x4"))]
+mod tests_lcd_20x4 {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::model::DataModel;
+    use crate::menu::snapshot::{assert_screen, draw};
+    use crate::menu::widget::submenu_programa::spec::MenuProgramaArena;
+
+    #[test]
+    fn it_shows_the_fields_below_the_short_captions() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        assert_eq!(
+            draw(&menu),
+            [
+                ">Programa eixo X    ",
+                "                 0  ",
+                " Programa eixo Y    ",
+                "                 0  ",
+            ]
+        );
+        let keyboard = ScriptedKeyboard::new().keys(&[
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+        ]);
+        // the operator cannot copy programs, so the selector alternates with the lock
+        assert_screen(
+            &mut menu,
+            keyboard,
+            &[
+                " Programa eixo Y",
+                "                 0",
+                "■Copiar de/para",
+                " [X] 0   [X] 1",
+            ],
+        );
+    }
+
+    #[test]
+    fn it_shows_the_unit_of_measurement() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let menu = MenuProgramaControler::new(
+            MenuProgramaAreanaSelector::MenuParametrosDeMovimento,
+            &arena,
+        );
+        assert_eq!(
+            draw(&menu),
+            [
+                ">Posicao inicial    ",
+                "          0050 mm   ",
+                " Posicao final      ",
+                "          0600 mm   ",
+            ]
+        );
+        let menu =
+            MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuParametrosDeCiclo, &arena);
+        assert_eq!(
+            draw(&menu),
+            [
+                ">Ret. start auto    ",
+                "           0.010 seg",
+                " Ret. start ext.    ",
+                "           0.010 seg",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_shows_the_axis_after_the_short_captions() {
+        let model = DataModel::new();
+        model.configuracao_do_eixo_y.numero_do_canal.set(2);
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        let to_configuracao_eixo_y = ScriptedKeyboard::new()
+            .keys(&[KeyCode::KEY_DIRECIONAL_PARA_BAIXO; 5])
+            .key(KeyCode::KEY_ENTER);
+        assert_screen(
+            &mut menu,
+            to_configuracao_eixo_y,
+            &[
+                "■Num. canal Y",
+                "             02",
+                "■Pulsos/giro Y",
+                "             400",
+            ],
+        );
+    }
+
+    #[test]
+    fn it_edits_the_program_number() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        let keyboard = ScriptedKeyboard::new()
+            .keys(&[KeyCode::KEY_ENTER, KeyCode::KEY_3])
+            .wait(400);
+        assert_screen(
+            &mut menu,
+            keyboard,
+            &[
+                "*Programa eixo X",
+                "                 3",
+                " Programa eixo Y",
+                "                 0",
+            ],
+        );
+    }
+}
+
+/// The menu is designed for the 40x2 lcd, in the narrower ones each menu item takes two lines: its
+/// short caption and, below it, its fields kept at the right edge
+#[cfg(all(test, feature = "lcd_16x2"))]
+mod tests_lcd_16x2 {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::model::DataModel;
+    use crate::menu::snapshot::{assert_screen, draw};
+    use crate::menu::widget::submenu_programa::spec::MenuProgramaArena;
+
+    #[test]
+    fn it_shows_one_menu_item_at_a_time() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let mut menu = MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuPrograma, &arena);
+        assert_eq!(draw(&menu), [">Programa eixo X", "             0  "]);
+        let keyboard = ScriptedKeyboard::new().keys(&[
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+        ]);
+        // the operator cannot edit it, so the selector alternates with the lock
+        assert_screen(&mut menu, keyboard, &["■Restaura padrao", " [Progr] [X] 0"]);
+        // and scrolls back to the first item
+        let keyboard = ScriptedKeyboard::new().keys(&[KeyCode::KEY_DIRECIONAL_PARA_CIMA; 3]);
+        assert_screen(&mut menu, keyboard, &[" Programa eixo X", "             0"]);
+    }
+
+    #[test]
+    fn it_shows_the_unit_of_measurement() {
+        let model = DataModel::new();
+        let arena = MenuProgramaArena::new(&model);
+        let menu = MenuProgramaControler::new(
+            MenuProgramaAreanaSelector::MenuParametrosDeMovimento,
+            &arena,
+        );
+        assert_eq!(draw(&menu), [">Posicao inicial", "      0050 mm   "]);
+        let menu =
+            MenuProgramaControler::new(MenuProgramaAreanaSelector::MenuParametrosDeCiclo, &arena);
+        assert_eq!(draw(&menu), [">Ret. start auto", "       0.010 seg"]);
     }
 }
@@ -1,6 +1,4 @@
-use crate::board::lcd;
-
-use super::hepers::LcdLine;
+use super::{hepers::LcdLine, macros::NUMBER_OF_VISIBLE_ITEMS};
 
 use cross_platform::utils::{
     bit_wise::{configure_bit, get_bit_at, reset_bit_at},
@@ -71,7 +69,7 @@ pub struct NavigationStateModel {
 }
 
 impl NavigationStateModel {
-    const TOTAL_NUMBER_OF_LINES_IN_LCD: u8 = lcd::NUMBER_OF_LINES;
+    const TOTAL_NUMBER_OF_LINES_IN_LCD: u8 = NUMBER_OF_VISIBLE_ITEMS;
     const DEFAULT_INITIAL_LINE_SELECTED: u8 = 0;
     const DEFAULT_INITIAL_MENU_ITEM: u8 = 0;
 
@@ -82,9 +80,17 @@ impl NavigationStateModel {
         }
     }
 
+    /// Number of lcd lines filled with menu items, the submenu may have less items than lcd lines
+    fn get_number_of_visible_lines(number_of_menu_items: u8) -> u8 {
+        number_of_menu_items
+            .min(Self::TOTAL_NUMBER_OF_LINES_IN_LCD)
+            .max(1)
+    }
+
     /// Scrolls menu down, return true if it the scroll has already been exausted
     fn scroll_down(&mut self, number_of_menu_items: u8) -> bool {
-        let end = number_of_menu_items - (Self::TOTAL_NUMBER_OF_LINES_IN_LCD - 1);
+        let visible_lines = Self::get_number_of_visible_lines(number_of_menu_items);
+        let end = number_of_menu_items.max(1) - (visible_lines - 1);
         let has_exhausted = StatelessCursor::next(end, &mut self.first_line_to_render);
         has_exhausted
     }
@@ -97,7 +103,7 @@ impl NavigationStateModel {
     }
 
     pub fn key_down(&mut self, number_of_menu_items: u8) {
-        let end = Self::TOTAL_NUMBER_OF_LINES_IN_LCD;
+        let end = Self::get_number_of_visible_lines(number_of_menu_items);
         let has_exausted = StatelessCursor::next(end, &mut self.lcd_line_cursor);
         if has_exausted {
             self.scroll_down(number_of_menu_items);
@@ -112,6 +118,19 @@ impl NavigationStateModel {
         };
     }
 
+    /// Scrolls the menu until the given menu item is selected, keeping the lcd filled of menu items.
+    pub fn select_index(&mut self, index: u8, number_of_menu_items: u8) {
+        let last_first_line =
+            number_of_menu_items.saturating_sub(Self::TOTAL_NUMBER_OF_LINES_IN_LCD);
+        if index > last_first_line {
+            self.first_line_to_render = last_first_line;
+            self.lcd_line_cursor = index - last_first_line;
+        } else {
+            self.first_line_to_render = index;
+            self.lcd_line_cursor = Self::DEFAULT_INITIAL_LINE_SELECTED;
+        }
+    }
+
     pub fn get_current_lcd_line(&self) -> LcdLine {
         LcdLine::from(self.lcd_line_cursor)
     }
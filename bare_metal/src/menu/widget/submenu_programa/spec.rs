@@ -1,26 +1,19 @@
 /// Here we specify the concrete menu items and submenus of the application
 ///
-/// If you want to add more submenus or menu items consider this commit a
-/// an example: 9959bd686
-///
-/// Note that this is a first implementation of the menu, and there is plenty of room
-/// to improve and to make the operation of add submenus or menuitems a lot more easier.
-/// By for example using macros! etc
-use core::{cell::Cell, u8};
-
-use crate::{
-    menu::widget::menu_item::builder::{
-        MenuItemBuilder, NumericalParameter, OptionalParameter, SimpleMenu,
-        SimpleMenuWithNumericalParameter,
-    },
-    string::flash::FlashString,
-};
+/// If you want to add more submenus or menu items see the declarative macros in `macros.rs`.
+use core::cell::Cell;
+use core::ops::Range;
+
+use crate::{menu::widget::menu_item::menu_item::MenuItemWidget, string::flash::FlashString};
+
+use crate::{item_index, menu_arena, submenu};
 
 use super::{
-    super::super::{model::DataModel, widget::menu_item::menu_item::MenuItemWidget},
+    super::super::model::{
+        Eixo, ModelError, NivelDeAcesso, Registro, RegraDeValidacao, NUMERO_MAXIMO_DE_PROGRAMAS,
+    },
     core::SubmenuLayout,
     navigation_state::NavigationStateModel,
-    spec_options::Options,
 };
 
 ///////////////////////////////////////////////////
@@ -30,19 +23,117 @@ use super::flash_texts::*;
 
 ////////////////////////////////////////////////////
 
-/// Indexes the sub menus in the menu arena, it works like a reference pointer to the concrete menu.
-/// If you create a new sub menu you must registry it here.
+/// Actions that can be executed by the menu items, after the confirmation of the user.
+/// If you create a new action you must registry it here.
 #[derive(Copy, Clone, PartialEq)]
-pub enum MenuProgramaAreanaSelector {
-    MenuPrograma,
-    MenuArquivoDeEixo,
-    MenuParametrosDeMovimento,
-    MenuParametrosDeImpressao,
-    MenuParametrosDeCiclo,
-    MenuConfiguracaoDaImpressora,
-    MenuIntertravamentoParaDoisEixos,
-    MenuConfiguracaoDeEixo,
-    MenuConfiguracaoDoEquipamento,
+pub enum MenuProgramaAction {
+    /// `COPIPRG` in the original TTC3100
+    CopiarPrograma,
+    RestaurarPadraoDeFabrica,
+    /// `TROCNIV` in the original TTC3100
+    TrocarNivelDeAcesso,
+    /// `TROCSEN` in the original TTC3100
+    TrocarSenha,
+}
+
+impl MenuProgramaAction {
+    /// Message shown to the user asking for the confirmation of the action, if the action does not
+    /// require confirmation returns `None` and it is executed straight away.
+    pub fn get_confirmation_message(&self) -> Option<FlashString> {
+        match self {
+            MenuProgramaAction::CopiarPrograma => {
+                Some(FlashString::new(&CONFIRMA_COPIA_DE_PROGRAMA))
+            }
+            MenuProgramaAction::RestaurarPadraoDeFabrica => {
+                Some(FlashString::new(&CONFIRMA_RESTAURACAO_DE_PADRAO))
+            }
+            MenuProgramaAction::TrocarNivelDeAcesso => None,
+            MenuProgramaAction::TrocarSenha => None,
+        }
+    }
+
+    /// If true, after the execution of the action the menu returns to the main menu, which sends
+    /// all the data to the boards and saves it into the eeprom.
+    pub fn must_return_to_main_menu(&self) -> bool {
+        match self {
+            MenuProgramaAction::CopiarPrograma => false,
+            MenuProgramaAction::RestaurarPadraoDeFabrica => true,
+            MenuProgramaAction::TrocarNivelDeAcesso => false,
+            MenuProgramaAction::TrocarSenha => false,
+        }
+    }
+}
+
+/// Program numbers which may be selected in the menu, the ones which have a slot in eeprom.
+///
+/// NOTE: The numerical fields clamp the value to the end of the range (included).
+const NUMEROS_DE_PROGRAMA: Range<u16> = 0..NUMERO_MAXIMO_DE_PROGRAMAS - 1;
+
+/// Message shown to the user when the given validation rule is violated
+pub fn get_rule_message(regra: RegraDeValidacao) -> FlashString {
+    // each text has its own length (and type), so they are converted in each arm
+    match regra {
+        RegraDeValidacao::PosicaoFinalMaiorQueInicial => FlashString::new(&REGRA_POSICAO_FINAL),
+        RegraDeValidacao::PrimeiraMensagemNoAvancoDentroDoCurso => {
+            FlashString::new(&REGRA_PRIMEIRA_NO_AVANCO_NO_CURSO)
+        }
+        RegraDeValidacao::UltimaMensagemNoAvancoDentroDoCurso => {
+            FlashString::new(&REGRA_ULTIMA_NO_AVANCO_NO_CURSO)
+        }
+        RegraDeValidacao::PrimeiraMensagemNoRetornoDentroDoCurso => {
+            FlashString::new(&REGRA_PRIMEIRA_NO_RETORNO_NO_CURSO)
+        }
+        RegraDeValidacao::UltimaMensagemNoRetornoDentroDoCurso => {
+            FlashString::new(&REGRA_ULTIMA_NO_RETORNO_NO_CURSO)
+        }
+        RegraDeValidacao::UltimaMensagemNoAvancoAposPrimeira => {
+            FlashString::new(&REGRA_ORDEM_NO_AVANCO)
+        }
+        RegraDeValidacao::UltimaMensagemNoRetornoAntesDaPrimeira => {
+            FlashString::new(&REGRA_ORDEM_NO_RETORNO)
+        }
+        RegraDeValidacao::MensagensNoAvancoCabemEntrePrimeiraEUltima => {
+            FlashString::new(&REGRA_MENSAGENS_NO_AVANCO)
+        }
+        RegraDeValidacao::MensagensNoRetornoCabemEntrePrimeiraEUltima => {
+            FlashString::new(&REGRA_MENSAGENS_NO_RETORNO)
+        }
+    }
+}
+
+/// Submenu and index of the menu item the user must edit to fix the given validation rule.
+pub fn get_rule_location(regra: RegraDeValidacao) -> (MenuProgramaAreanaSelector, usize) {
+    let movimento = |index| (MenuProgramaAreanaSelector::MenuParametrosDeMovimento, index);
+    let impressao = |index| (MenuProgramaAreanaSelector::MenuParametrosDeImpressao, index);
+    match regra {
+        RegraDeValidacao::PosicaoFinalMaiorQueInicial => {
+            movimento(item_index!(MenuParametrosDeMovimento::POSICAO_FINAL))
+        }
+        RegraDeValidacao::PrimeiraMensagemNoAvancoDentroDoCurso => impressao(item_index!(
+            MenuParametrosDeImpressao::PRIMEIRA_MENSAGEM_NO_AVANCO
+        )),
+        RegraDeValidacao::UltimaMensagemNoAvancoDentroDoCurso => impressao(item_index!(
+            MenuParametrosDeImpressao::ULTIMA_MENSAGEM_NO_AVANCO
+        )),
+        RegraDeValidacao::PrimeiraMensagemNoRetornoDentroDoCurso => impressao(item_index!(
+            MenuParametrosDeImpressao::PRIMEIRA_MENSAGEM_NO_RETORNO
+        )),
+        RegraDeValidacao::UltimaMensagemNoRetornoDentroDoCurso => impressao(item_index!(
+            MenuParametrosDeImpressao::ULTIMA_MENSAGEM_NO_RETORNO
+        )),
+        RegraDeValidacao::UltimaMensagemNoAvancoAposPrimeira => impressao(item_index!(
+            MenuParametrosDeImpressao::ULTIMA_MENSAGEM_NO_AVANCO
+        )),
+        RegraDeValidacao::UltimaMensagemNoRetornoAntesDaPrimeira => impressao(item_index!(
+            MenuParametrosDeImpressao::ULTIMA_MENSAGEM_NO_RETORNO
+        )),
+        RegraDeValidacao::MensagensNoAvancoCabemEntrePrimeiraEUltima => impressao(item_index!(
+            MenuParametrosDeImpressao::NUMERO_DE_MENSAGEM_NO_AVANCO
+        )),
+        RegraDeValidacao::MensagensNoRetornoCabemEntrePrimeiraEUltima => impressao(item_index!(
+            MenuParametrosDeImpressao::NUMERO_DE_MENSAGEM_NO_RETORNO
+        )),
+    }
 }
 
 /// Used to store the menu itself alongside its navigation state
@@ -74,695 +165,443 @@ impl<T: SubmenuLayout> SubmenuLayout for Register<T, NavigationStateModel> {
     fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
         self.menu.get_item(index)
     }
-}
 
-type RegisterSubMenu<T> = Register<T, NavigationStateModel>;
+    fn before_go_to_child(&self, index: usize) -> Result<(), ModelError> {
+        self.menu.before_go_to_child(index)
+    }
 
-/// The storage for all sub menus inside the submenu 'Programa'. If you create a new sub menu you must put it here.
-///
-/// TODO: When possible generalize the [`MenuProgramaAreana`] to include all menus used in the app
-/// (ie: splash, main_menu, menu_execucao and menu_manual, etc)
-pub struct MenuProgramaArena<'a> {
-    model: &'a DataModel,
-
-    pub MenuPrograma: RegisterSubMenu<MenuPrograma<'a>>,
-
-    // ARQUIVO DE EIXO
-    pub MenuArquivoDeEixo: RegisterSubMenu<MenuArquivoDeEixo<'a>>,
-    pub MenuParametrosDeMovimento: RegisterSubMenu<MenuParametrosDeMovimento<'a>>,
-    pub MenuParametrosDeImpressao: RegisterSubMenu<MenuParametrosDeImpressao<'a>>,
-    pub MenuParametrosDeCiclo: RegisterSubMenu<MenuParametrosDeCiclo<'a>>,
-    pub MenuConfiguracaoDaImpressora: RegisterSubMenu<MenuConfiguracaoDaImpressora<'a>>,
-    pub MenuIntertravamentoParaDoisEixos: RegisterSubMenu<MenuIntertravamentoParaDoisEixos<'a>>,
-    //pub MenuParametrosDeSelecaoDeMensagem: RegisterSubMenu<MenuParametrosDeSelecaoDeMensagem>,
-    pub MenuConfiguracaoDoEixo: RegisterSubMenu<MenuConfiguracaoDeEixo<'a>>,
-    pub MenuConfiguracaoDoEquipamento: RegisterSubMenu<MenuConfiguracaoDoEquipamento<'a>>,
-}
+    fn get_access_level(&self, index: usize) -> NivelDeAcesso {
+        self.menu.get_access_level(index)
+    }
 
-impl<'a> MenuProgramaArena<'a> {
-    /// Constructs all the menus and initializes its internal state
-    pub fn new(model: &'a DataModel) -> Self {
-        Self {
-            model,
-            MenuPrograma: Register::from_submenu(MenuPrograma::new(model)),
-            MenuArquivoDeEixo: Register::from_submenu(MenuArquivoDeEixo::new(model)),
-            MenuParametrosDeMovimento: Register::from_submenu(MenuParametrosDeMovimento::new(
-                model,
-            )),
-            MenuParametrosDeImpressao: Register::from_submenu(MenuParametrosDeImpressao::new(
-                model,
-            )),
-            MenuParametrosDeCiclo: Register::from_submenu(MenuParametrosDeCiclo::new(model)),
-            MenuConfiguracaoDaImpressora: Register::from_submenu(
-                MenuConfiguracaoDaImpressora::new(model),
-            ),
-            MenuIntertravamentoParaDoisEixos: Register::from_submenu(
-                MenuIntertravamentoParaDoisEixos::new(model),
-            ),
-            MenuConfiguracaoDoEixo: Register::from_submenu(MenuConfiguracaoDeEixo::new(model)),
-            MenuConfiguracaoDoEquipamento: Register::from_submenu(
-                MenuConfiguracaoDoEquipamento::new(model),
-            ),
-        }
+    fn len(&self) -> usize {
+        self.menu.len()
     }
+}
 
-    /// Retrieves an menu item given the sub menu and the index number of the menu item.
-    /// If index is out of range than returns None
-    pub fn get_item(
-        &self,
-        menu_selector: MenuProgramaAreanaSelector,
-        index: usize,
-    ) -> Option<MenuItemWidget> {
-        match menu_selector {
-            MenuProgramaAreanaSelector::MenuPrograma => self.MenuPrograma.get_item(index),
-            MenuProgramaAreanaSelector::MenuArquivoDeEixo => self.MenuArquivoDeEixo.get_item(index),
-            MenuProgramaAreanaSelector::MenuParametrosDeMovimento => {
-                self.MenuParametrosDeMovimento.get_item(index)
-            }
+type RegisterSubMenu<T> = Register<T, NavigationStateModel>;
 
-            MenuProgramaAreanaSelector::MenuParametrosDeImpressao => {
-                self.MenuParametrosDeImpressao.get_item(index)
-            }
+menu_arena! {
+    /// Indexes the sub menus in the menu arena, it works like a reference pointer to the concrete menu.
+    pub enum MenuProgramaAreanaSelector;
 
-            MenuProgramaAreanaSelector::MenuParametrosDeCiclo => {
-                self.MenuParametrosDeCiclo.get_item(index)
-            }
-            MenuProgramaAreanaSelector::MenuConfiguracaoDaImpressora => {
-                self.MenuConfiguracaoDaImpressora.get_item(index)
-            }
+    /// The storage for all sub menus inside the submenu 'Programa'.
+    ///
+    /// NOTE: The other screens of the app (ie: splash, main_menu, menu_execucao and menu_manual)
+    /// are not menus, they are stacked by the [`Navigator`](crate::menu::widget::navigation::Navigator).
+    pub struct MenuProgramaArena {
+        MenuPrograma,
+        // ARQUIVO DE EIXO
+        MenuArquivoDeEixo,
+        MenuParametrosDeMovimento,
+        MenuParametrosDeImpressao,
+        MenuParametrosDeCiclo,
+        MenuConfiguracaoDaImpressora,
+        MenuIntertravamentoParaDoisEixos,
+        //MenuParametrosDeSelecaoDeMensagem,
+        MenuConfiguracaoDeEixo,
+        MenuConfiguracaoDoEquipamento,
+    }
+}
 
-            MenuProgramaAreanaSelector::MenuIntertravamentoParaDoisEixos => {
-                self.MenuIntertravamentoParaDoisEixos.get_item(index)
-            }
-            MenuProgramaAreanaSelector::MenuConfiguracaoDeEixo => {
-                self.MenuConfiguracaoDoEixo.get_item(index)
+impl<'a> MenuProgramaArena<'a> {
+    /// Executes the given action over the model
+    pub fn execute_action(&self, action: MenuProgramaAction) -> Result<(), ModelError> {
+        match action {
+            MenuProgramaAction::CopiarPrograma => self.model.copiar_programa_selecionado(),
+            MenuProgramaAction::RestaurarPadraoDeFabrica => {
+                self.model.restaurar_padrao_selecionado()
             }
-            MenuProgramaAreanaSelector::MenuConfiguracaoDoEquipamento => {
-                self.MenuConfiguracaoDoEquipamento.get_item(index)
+            MenuProgramaAction::TrocarNivelDeAcesso => {
+                self.model.trocar_nivel_de_acesso_selecionado()
             }
+            MenuProgramaAction::TrocarSenha => self.model.trocar_senha_selecionada(),
         }
     }
 
-    /// Gets the navigation state of the submenu
-    /// TODO: Simplify this function implementation reusing self.get_item which has a similar implementation
-    pub fn get_navigation_state(
-        &self,
-        menu_selector: MenuProgramaAreanaSelector,
-    ) -> &Cell<NavigationStateModel> {
-        match menu_selector {
-            MenuProgramaAreanaSelector::MenuPrograma => self.MenuPrograma.get_navigation_state(),
-            MenuProgramaAreanaSelector::MenuArquivoDeEixo => {
-                self.MenuArquivoDeEixo.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuParametrosDeMovimento => {
-                self.MenuParametrosDeMovimento.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuParametrosDeImpressao => {
-                self.MenuParametrosDeImpressao.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuParametrosDeCiclo => {
-                self.MenuParametrosDeCiclo.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuConfiguracaoDaImpressora => {
-                self.MenuConfiguracaoDaImpressora.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuIntertravamentoParaDoisEixos => {
-                self.MenuIntertravamentoParaDoisEixos.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuConfiguracaoDeEixo => {
-                self.MenuConfiguracaoDoEixo.get_navigation_state()
-            }
-            MenuProgramaAreanaSelector::MenuConfiguracaoDoEquipamento => {
-                self.MenuConfiguracaoDoEquipamento.get_navigation_state()
-            }
-        }
+    /// True if the current access level of the user allows to edit the given menu item, if not the
+    /// menu item is read only.
+    pub fn has_access(&self, menu_selector: MenuProgramaAreanaSelector, index: usize) -> bool {
+        let required = self.get_access_level(menu_selector, index);
+        self.model.tem_acesso(required)
     }
 
-    /// Given an sub menu index, get the size of menu items inside it.
-    ///
-    /// TODO: This algoritm may be highly optimized, because the length currently is obtained instantiating &
-    /// throwing away all the menu items in memory. A better option may be to restructure datastructures
-    /// to calculate this size in static time.
-    pub fn len(&self, menu_selector: MenuProgramaAreanaSelector) -> usize {
-        for index in 0..u8::MAX {
-            if let None = self.get_item(menu_selector, index as usize) {
-                return index as usize;
+    /// Informs that the user edited some parameter of the given submenu, so the record which holds
+    /// it must be verified when leaving the menu. The parameters of `MenuPrograma` are not saved.
+    pub fn register_change(&self, menu_selector: MenuProgramaAreanaSelector) {
+        use MenuProgramaAreanaSelector::*;
+        let registro = match menu_selector {
+            MenuPrograma => return,
+            MenuArquivoDeEixo
+            | MenuParametrosDeMovimento
+            | MenuParametrosDeImpressao
+            | MenuParametrosDeCiclo
+            | MenuConfiguracaoDaImpressora
+            | MenuIntertravamentoParaDoisEixos => {
+                Registro::ArquivoDeEixo(self.model.gui_state.eixo_em_edicao.get())
             }
-        }
-        return 0;
+            MenuConfiguracaoDeEixo => {
+                Registro::ConfiguracaoDoEixo(self.model.gui_state.eixo_em_configuracao.get())
+            }
+            MenuConfiguracaoDoEquipamento => Registro::ConfiguracaoDoEquipamento,
+        };
+        self.model.registrar_alteracao(registro);
     }
-}
 
-////////////////////////////////////////////////////
-
-pub struct MenuPrograma<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuPrograma<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+    /// True if some value was changed by the user and not saved in eeprom yet
+    pub fn has_unsaved_changes(&self) -> bool {
+        !self.model.get_alteracoes().is_empty()
     }
-}
 
-impl SubmenuLayout for MenuPrograma<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => {
-                MenuItemBuilder::make_simple_menu_with_parameter(SimpleMenuWithNumericalParameter {
-                    parameter_name: FlashString::new(&EDITAR_PROGRAMA_EIXO_X),
-                    child_menu: MenuProgramaAreanaSelector::MenuArquivoDeEixo,
-                    unit_of_measurement_text: None,
-                    valid_range: 0..99,
-                    variable: (37, &self.model.gui_state.numero_do_programa_do_eixo_x),
-                })
-            }
-
-            1 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&CONFIGURACAO_EIXO_X),
-                child_menu: MenuProgramaAreanaSelector::MenuConfiguracaoDeEixo,
-            }),
-
-            2 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&CONFIGURACAO_DO_EQUIPAMENTO),
-                child_menu: MenuProgramaAreanaSelector::MenuConfiguracaoDoEquipamento,
-            }),
-
-            _ => None,
-        }
+    /// Saves the changes made by the user in eeprom
+    pub fn save_changes(&self) {
+        self.model.salvar_alteracoes()
     }
-}
-
-////////////////////////////////////////////////////////////////////////////////////////////////////////
-//          MENU ARQUIVO DE EIXO
-////////////////////////////////////////////////////////////////////////////////////////////////////////
-
-pub struct MenuArquivoDeEixo<'a> {
-    model: &'a DataModel,
-}
 
-impl<'a> MenuArquivoDeEixo<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+    /// Writes into eeprom the changes saved the last time the menu was left, so they are not
+    /// mixed with the new changes (see: [`DataModel::salvar_alteracoes`]).
+    ///
+    /// [`DataModel::salvar_alteracoes`]: crate::menu::model::DataModel::salvar_alteracoes
+    pub fn write_pending_changes(&self) {
+        self.model.gravar_alteracoes_pendentes()
     }
-}
 
-impl SubmenuLayout for MenuArquivoDeEixo<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&PARAMETROS_DE_MOVIMENTO),
-                child_menu: MenuProgramaAreanaSelector::MenuParametrosDeMovimento,
-            }),
-
-            1 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&PARAMETROS_DE_IMPRESSAO),
-                child_menu: MenuProgramaAreanaSelector::MenuParametrosDeImpressao,
-            }),
-
-            2 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&CONFIGURACAO_DO_CICLO),
-                child_menu: MenuProgramaAreanaSelector::MenuParametrosDeCiclo,
-            }),
-
-            3 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&CONFIGURACAO_DA_IMPRESSORA),
-                child_menu: MenuProgramaAreanaSelector::MenuConfiguracaoDaImpressora,
-            }),
-
-            4 => MenuItemBuilder::make_simple_menu(SimpleMenu {
-                parent_name: FlashString::new(&INTERTRAVAMENTO_DOIS_EIXOS_PASSO_A_PASSO),
-                child_menu: MenuProgramaAreanaSelector::MenuIntertravamentoParaDoisEixos,
-            }),
-
-            _ => None,
-        }
+    /// Reverts the values changed by the user to the ones saved in eeprom
+    pub fn discard_changes(&self) {
+        self.model.descartar_alteracoes()
     }
-}
-
-////////////////////////////////////////////////////
 
-pub struct MenuParametrosDeMovimento<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuParametrosDeMovimento<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+    /// True if the programs saved in eeprom were not sent to the cmpp boards yet
+    pub fn has_pending_upload(&self) -> bool {
+        self.model.tem_envio_pendente()
     }
-}
 
-impl SubmenuLayout for MenuParametrosDeMovimento<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&POSICAO_INICIAL),
-                variable: (30, &self.model.get_arquivo_de_eixo_by_ref().posicao_inicial),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((35, FlashString::new(&MILIMETROS))),
-            }),
-
-            1 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&POSICAO_FINAL),
-                variable: (30, &self.model.get_arquivo_de_eixo_by_ref().posicao_final),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((35, FlashString::new(&MILIMETROS))),
-            }),
-
-            2 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&ACELERACAO_DE_AVANCO),
-                variable: (
-                    30,
-                    &self.model.get_arquivo_de_eixo_by_ref().aceleracao_de_avanco,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((
-                    35,
-                    FlashString::new(&MILIMETROS_POR_SEGUNDO_AO_QUADRADO),
-                )),
-            }),
-
-            3 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&ACELERACAO_DE_RETORNO),
-                variable: (
-                    30,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .aceleracao_de_retorno,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((
-                    35,
-                    FlashString::new(&MILIMETROS_POR_SEGUNDO_AO_QUADRADO),
-                )),
-            }),
-
-            4 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&VELOCIDADE_DE_AVANCO),
-                variable: (
-                    30,
-                    &self.model.get_arquivo_de_eixo_by_ref().velocidade_de_avanco,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((35, FlashString::new(&MILIMETROS_POR_SEGUNDO))),
-            }),
-
-            5 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&VELOCIDADE_DE_RETORNO),
-                variable: (
-                    30,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .velocidade_de_retorno,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((35, FlashString::new(&MILIMETROS_POR_SEGUNDO))),
-            }),
-
-            _ => None,
+    /// Verifies, in the `Arquivo de Eixo` being edited, the validation rules located in the given
+    /// submenu. Leaving `MenuArquivoDeEixo` verifies all the rules.
+    pub fn validate(
+        &self,
+        menu_selector: MenuProgramaAreanaSelector,
+    ) -> Result<(), (Eixo, RegraDeValidacao)> {
+        let eixo = self.model.gui_state.eixo_em_edicao.get();
+        let arquivo_de_eixo = self.model.get_arquivo_de_eixo(eixo);
+        let is_verified_in_menu = |regra: RegraDeValidacao| {
+            menu_selector == MenuProgramaAreanaSelector::MenuArquivoDeEixo
+                || get_rule_location(regra).0 == menu_selector
+        };
+        match RegraDeValidacao::TODAS
+            .into_iter()
+            .find(|regra| is_verified_in_menu(*regra) && !arquivo_de_eixo.verificar(*regra))
+        {
+            Some(regra) => Err((eixo, regra)),
+            None => Ok(()),
         }
     }
-}
-
-////////////////////////////////////////////////////
 
-pub struct MenuParametrosDeImpressao<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuParametrosDeImpressao<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+    /// Verifies all the validation rules in the programs loaded in both axis, if some rule is
+    /// violated the axis of the program is made the axis being edited.
+    pub fn validate_all(&self) -> Result<(), (Eixo, RegraDeValidacao)> {
+        self.model
+            .validar_programas_carregados()
+            .map_err(|(eixo, regra)| {
+                self.model.gui_state.eixo_em_edicao.set(eixo);
+                (eixo, regra)
+            })
     }
 }
 
-impl SubmenuLayout for MenuParametrosDeImpressao<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&NUMERO_DE_MENSAGEM_NO_AVANCO),
-                variable: (
-                    35,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .numero_de_mensagem_no_avanco,
-                ),
-                valid_range: 0..99,
-                unit_of_measurement_text: None,
-            }),
-
-            1 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&NUMERO_DE_MENSAGEM_NO_AVANCO),
-                variable: (
-                    35,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .velocidade_de_retorno,
-                ),
-                valid_range: 0..99,
-                unit_of_measurement_text: None,
-            }),
-
-            2 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&PRIMEIRA_MENSAGEM_NO_AVANCO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .primeira_mensagem_no_avanco,
-                ),
-                valid_range: 0..99,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILIMETROS))),
-            }),
-
-            3 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&PRIMEIRA_MENSAGEM_NO_RETORNO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .primeira_mensagem_no_avanco,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILIMETROS))),
-            }),
-
-            4 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&ULTIMA_MENSAGEM_NO_AVANCO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .ultima_mensagem_no_avanco,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILIMETROS))),
-            }),
-
-            5 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&ULTIMA_MENSAGEM_NO_RETORNO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .ultima_mensagem_no_retorno,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILIMETROS))),
-            }),
+////////////////////////////////////////////////////
 
-            _ => None,
-        }
+submenu! {
+    pub struct MenuPrograma(model) {
+        access_level: Operador,
+        items: [
+            // loads the program of the axis selected by the user before editing it
+            #[before_go_to_child(model.editar_programa_do_eixo(Eixo::X))]
+            simple_menu_with_numerical(
+                EDITAR_PROGRAMA_EIXO_X => MenuArquivoDeEixo,
+                (37, &model.gui_state.numero_do_programa_do_eixo_x),
+                NUMEROS_DE_PROGRAMA,
+            ),
+            #[before_go_to_child(model.editar_programa_do_eixo(Eixo::Y))]
+            simple_menu_with_numerical(
+                EDITAR_PROGRAMA_EIXO_Y => MenuArquivoDeEixo,
+                (37, &model.gui_state.numero_do_programa_do_eixo_y),
+                NUMEROS_DE_PROGRAMA,
+            ),
+            // `COPIPRG` requires `NNIVEL-1` in the original TTC3100
+            #[access_level(Manutencao)]
+            action(COPIAR_PROGRAMA => CopiarPrograma, [
+                optional(17, &model.gui_state.eixo_de_origem_da_copia, [EIXO_X, EIXO_Y]),
+                numerical(21, &model.gui_state.programa_de_origem_da_copia, NUMEROS_DE_PROGRAMA),
+                optional(29, &model.gui_state.eixo_de_destino_da_copia, [EIXO_X, EIXO_Y]),
+                numerical(33, &model.gui_state.programa_de_destino_da_copia, NUMEROS_DE_PROGRAMA),
+            ]),
+            #[access_level(Supervisor)]
+            action(RESTAURAR_PADRAO => RestaurarPadraoDeFabrica, [
+                optional(
+                    19,
+                    &model.gui_state.escopo_da_restauracao,
+                    [ESCOPO_PROGRAMA, ESCOPO_EIXO, ESCOPO_TUDO],
+                ),
+                optional(27, &model.gui_state.eixo_da_restauracao, [EIXO_X, EIXO_Y]),
+                numerical(31, &model.gui_state.programa_da_restauracao, NUMEROS_DE_PROGRAMA),
+            ]),
+            #[before_go_to_child(model.editar_configuracao_do_eixo(Eixo::X))]
+            simple_menu(CONFIGURACAO_EIXO_X => MenuConfiguracaoDeEixo),
+            #[before_go_to_child(model.editar_configuracao_do_eixo(Eixo::Y))]
+            simple_menu(CONFIGURACAO_EIXO_Y => MenuConfiguracaoDeEixo),
+            simple_menu(CONFIGURACAO_DO_EQUIPAMENTO => MenuConfiguracaoDoEquipamento),
+            // anyone can try to change its own access level
+            #[access_level(Protegido)]
+            action(NIVEL_DE_ACESSO => TrocarNivelDeAcesso, [
+                optional(
+                    18,
+                    &model.gui_state.nivel_de_acesso_selecionado,
+                    [NIVEL_SUPERVISOR, NIVEL_MANUTENCAO, NIVEL_OPERADOR, NIVEL_PROTEGIDO],
+                ),
+                password(33, &model.gui_state.senha_digitada, 0..9999),
+            ]),
+            action(TROCAR_SENHA => TrocarSenha, [
+                optional(
+                    15,
+                    &model.gui_state.nivel_da_troca_de_senha,
+                    [NIVEL_SUPERVISOR, NIVEL_MANUTENCAO, NIVEL_OPERADOR, NIVEL_PROTEGIDO],
+                ),
+                password(35, &model.gui_state.nova_senha, 0..9999),
+            ]),
+        ]
     }
 }
 
-////////////////////////////////////////////////////
-
-pub struct MenuParametrosDeCiclo<'a> {
-    model: &'a DataModel,
-}
+////////////////////////////////////////////////////////////////////////////////////////////////////////
+//          MENU ARQUIVO DE EIXO
+////////////////////////////////////////////////////////////////////////////////////////////////////////
 
-impl<'a> MenuParametrosDeCiclo<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+submenu! {
+    pub struct MenuArquivoDeEixo(model) {
+        access_level: Operador,
+        items: [
+            simple_menu(PARAMETROS_DE_MOVIMENTO => MenuParametrosDeMovimento),
+            simple_menu(PARAMETROS_DE_IMPRESSAO => MenuParametrosDeImpressao),
+            simple_menu(CONFIGURACAO_DO_CICLO => MenuParametrosDeCiclo),
+            simple_menu(CONFIGURACAO_DA_IMPRESSORA => MenuConfiguracaoDaImpressora),
+            simple_menu(
+                INTERTRAVAMENTO_DOIS_EIXOS_PASSO_A_PASSO => MenuIntertravamentoParaDoisEixos,
+            ),
+        ]
     }
 }
 
-impl SubmenuLayout for MenuParametrosDeCiclo<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&RETARDO_NO_START_AUTOMATICO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .retardo_no_start_automatico,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILI_SEGUNDOS))),
-            }),
-
-            1 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&RETARDO_NO_START_EXTERNO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .retardo_no_start_externo,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILI_SEGUNDOS))),
-            }),
-
-            2 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&START_AUTOMATICO_NO_AVANCO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .start_automatico_no_avanco,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            3 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&START_AUTOMATICO_NO_RETORNO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .start_automatico_no_retorno,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            4 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&MODO_DE_TRABALHO_DO_EIXO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .modo_de_trabalho_do_eixo,
-                ),
-                options_list: Options::continuo_passo_a_passo(),
-            }),
+////////////////////////////////////////////////////
 
-            _ => None,
-        }
+submenu! {
+    // Edition of the program parameters (`REDICAO` requires `NNIVEL-1` in the original TTC3100,
+    // but here the operator edits the programs as before the access levels were introduced)
+    pub struct MenuParametrosDeMovimento(model) {
+        access_level: Operador,
+        items: [
+            numerical(
+                POSICAO_INICIAL,
+                (30, &model.get_arquivo_de_eixo_by_ref().posicao_inicial),
+                0..9999,
+                (35, MILIMETROS),
+            ),
+            numerical(
+                POSICAO_FINAL,
+                (30, &model.get_arquivo_de_eixo_by_ref().posicao_final),
+                0..9999,
+                (35, MILIMETROS),
+            ),
+            numerical(
+                ACELERACAO_DE_AVANCO,
+                (30, &model.get_arquivo_de_eixo_by_ref().aceleracao_de_avanco),
+                0..9999,
+                (35, MILIMETROS_POR_SEGUNDO_AO_QUADRADO),
+            ),
+            numerical(
+                ACELERACAO_DE_RETORNO,
+                (30, &model.get_arquivo_de_eixo_by_ref().aceleracao_de_retorno),
+                0..9999,
+                (35, MILIMETROS_POR_SEGUNDO_AO_QUADRADO),
+            ),
+            numerical(
+                VELOCIDADE_DE_AVANCO,
+                (30, &model.get_arquivo_de_eixo_by_ref().velocidade_de_avanco),
+                0..9999,
+                (35, MILIMETROS_POR_SEGUNDO),
+            ),
+            numerical(
+                VELOCIDADE_DE_RETORNO,
+                (30, &model.get_arquivo_de_eixo_by_ref().velocidade_de_retorno),
+                0..9999,
+                (35, MILIMETROS_POR_SEGUNDO),
+            ),
+        ]
     }
 }
 
 ////////////////////////////////////////////////////
 
-pub struct MenuConfiguracaoDaImpressora<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuConfiguracaoDaImpressora<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+submenu! {
+    pub struct MenuParametrosDeImpressao(model) {
+        access_level: Operador,
+        items: [
+            numerical(
+                NUMERO_DE_MENSAGEM_NO_AVANCO,
+                (35, &model.get_arquivo_de_eixo_by_ref().numero_de_mensagem_no_avanco),
+                0..99,
+            ),
+            numerical(
+                NUMERO_DE_MENSAGEM_NO_RETORNO,
+                (35, &model.get_arquivo_de_eixo_by_ref().numero_de_mensagem_no_retorno),
+                0..99,
+            ),
+            numerical(
+                PRIMEIRA_MENSAGEM_NO_AVANCO,
+                (33, &model.get_arquivo_de_eixo_by_ref().primeira_mensagem_no_avanco),
+                0..9999,
+                (38, MILIMETROS),
+            ),
+            numerical(
+                PRIMEIRA_MENSAGEM_NO_RETORNO,
+                (33, &model.get_arquivo_de_eixo_by_ref().primeira_mensagem_no_retorno),
+                0..9999,
+                (38, MILIMETROS),
+            ),
+            numerical(
+                ULTIMA_MENSAGEM_NO_AVANCO,
+                (33, &model.get_arquivo_de_eixo_by_ref().ultima_mensagem_no_avanco),
+                0..9999,
+                (38, MILIMETROS),
+            ),
+            numerical(
+                ULTIMA_MENSAGEM_NO_RETORNO,
+                (33, &model.get_arquivo_de_eixo_by_ref().ultima_mensagem_no_retorno),
+                0..9999,
+                (38, MILIMETROS),
+            ),
+        ]
     }
 }
 
-impl SubmenuLayout for MenuConfiguracaoDaImpressora<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&LOGICA_DO_SINAL_DE_IMPRESSAO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .logica_do_sinal_de_impressao,
-                ),
-                options_list: Options::aberto_fechado(),
-            }),
-
-            1 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&LARGURA_DO_SINAL_DE_IMPRESSAO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .largura_do_sinal_de_impressao,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILI_SEGUNDOS))),
-            }),
-
-            2 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&REVERSAO_DE_MENSAGEM_VIA_SERIAL),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .arquivo_de_eixo_00
-                        .reversao_de_mensagem_via_serial,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            3 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&SELECAO_DE_MENSAGEM_VIA_SERIAL),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .selecao_de_mensagem_via_serial,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
+////////////////////////////////////////////////////
 
-            _ => None,
-        }
+submenu! {
+    pub struct MenuParametrosDeCiclo(model) {
+        access_level: Operador,
+        items: [
+            template(
+                RETARDO_NO_START_AUTOMATICO_EM_SEGUNDOS,
+                &model.get_arquivo_de_eixo_by_ref().retardo_no_start_automatico,
+                0..9999,
+            ),
+            template(
+                RETARDO_NO_START_EXTERNO_EM_SEGUNDOS,
+                &model.get_arquivo_de_eixo_by_ref().retardo_no_start_externo,
+                0..9999,
+            ),
+            optional(
+                START_AUTOMATICO_NO_AVANCO,
+                (32, &model.get_arquivo_de_eixo_by_ref().start_automatico_no_avanco),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                START_AUTOMATICO_NO_RETORNO,
+                (32, &model.get_arquivo_de_eixo_by_ref().start_automatico_no_retorno),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                MODO_DE_TRABALHO_DO_EIXO,
+                (32, &model.get_arquivo_de_eixo_by_ref().modo_de_trabalho_do_eixo),
+                [CONTINUO, PASSO_A_PASSO],
+            ),
+        ]
     }
 }
 
 ////////////////////////////////////////////////////
 
-pub struct MenuIntertravamentoParaDoisEixos<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuIntertravamentoParaDoisEixos<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
+submenu! {
+    pub struct MenuConfiguracaoDaImpressora(model) {
+        access_level: Operador,
+        items: [
+            optional(
+                LOGICA_DO_SINAL_DE_IMPRESSAO,
+                (32, &model.get_arquivo_de_eixo_by_ref().logica_do_sinal_de_impressao),
+                [ABERTO, FECHADO],
+            ),
+            template(
+                LARGURA_DO_SINAL_DE_IMPRESSAO_EM_SEGUNDOS,
+                &model.get_arquivo_de_eixo_by_ref().largura_do_sinal_de_impressao,
+                0..9999,
+            ),
+            optional(
+                REVERSAO_DE_MENSAGEM_VIA_SERIAL,
+                (32, &model.get_arquivo_de_eixo_by_ref().reversao_de_mensagem_via_serial),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                SELECAO_DE_MENSAGEM_VIA_SERIAL,
+                (32, &model.get_arquivo_de_eixo_by_ref().selecao_de_mensagem_via_serial),
+                [DESLIGADO, LIGADO],
+            ),
+        ]
     }
 }
 
-impl SubmenuLayout for MenuIntertravamentoParaDoisEixos<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&ANTECIPACAO_DA_SAIDA_DE_START),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .antecipacao_da_saida_de_start,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILI_SEGUNDOS))),
-            }),
-
-            1 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&SAIDA_DE_START_NO_AVANCO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .saida_de_start_no_avaco,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            2 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&SAIDA_DE_START_NO_RETORNO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .saida_de_start_no_retorno,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            3 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&ENTRADA_DE_START_ENTRE_EIXOS),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .entrada_de_start_entre_eixos,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            4 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&RETARDO_DO_START_ENTRE_EIXOS),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .retardo_do_start_entre_eixos,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILI_SEGUNDOS))),
-            }),
-
-            5 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&START_PELO_TECLADO_E_EXTERNO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .start_pelo_teclado_e_externo,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            6 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&RETARDO_NO_START_PASSO_A_PASSO),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .retardo_no_start_passo_a_passo,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: Some((38, FlashString::new(&MILI_SEGUNDOS))),
-            }),
-
-            7 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&START_AUTOMATICO_PASSO_A_PASSO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .start_automatico_passo_a_passo,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            8 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&SAIDA_START_PASSO_A_PASSO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .get_arquivo_de_eixo_by_ref()
-                        .saida_de_start_passo_a_passo,
-                ),
-                options_list: Options::continuo_passo_a_passo(),
-            }),
+////////////////////////////////////////////////////
 
-            _ => None,
-        }
+submenu! {
+    pub struct MenuIntertravamentoParaDoisEixos(model) {
+        access_level: Operador,
+        items: [
+            numerical(
+                ANTECIPACAO_DA_SAIDA_DE_START,
+                (33, &model.get_arquivo_de_eixo_by_ref().antecipacao_da_saida_de_start),
+                0..9999,
+                (38, MILI_SEGUNDOS),
+            ),
+            optional(
+                SAIDA_DE_START_NO_AVANCO,
+                (32, &model.get_arquivo_de_eixo_by_ref().saida_de_start_no_avaco),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                SAIDA_DE_START_NO_RETORNO,
+                (32, &model.get_arquivo_de_eixo_by_ref().saida_de_start_no_retorno),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                ENTRADA_DE_START_ENTRE_EIXOS,
+                (32, &model.get_arquivo_de_eixo_by_ref().entrada_de_start_entre_eixos),
+                [DESLIGADO, LIGADO],
+            ),
+            template(
+                RETARDO_DO_START_ENTRE_EIXOS_EM_SEGUNDOS,
+                &model.get_arquivo_de_eixo_by_ref().retardo_do_start_entre_eixos,
+                0..9999,
+            ),
+            optional(
+                START_PELO_TECLADO_E_EXTERNO,
+                (32, &model.get_arquivo_de_eixo_by_ref().start_pelo_teclado_e_externo),
+                [DESLIGADO, LIGADO],
+            ),
+            template(
+                RETARDO_NO_START_PASSO_A_PASSO_EM_SEGUNDOS,
+                &model.get_arquivo_de_eixo_by_ref().retardo_no_start_passo_a_passo,
+                0..9999,
+            ),
+            optional(
+                START_AUTOMATICO_PASSO_A_PASSO,
+                (32, &model.get_arquivo_de_eixo_by_ref().start_automatico_passo_a_passo),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                SAIDA_START_PASSO_A_PASSO,
+                (32, &model.get_arquivo_de_eixo_by_ref().saida_de_start_passo_a_passo),
+                [CONTINUO, PASSO_A_PASSO],
+            ),
+        ]
     }
 }
 
@@ -770,154 +609,89 @@ impl SubmenuLayout for MenuIntertravamentoParaDoisEixos<'_> {
 //          MENU CONFIGURACAO DE EIXO
 ////////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub struct MenuConfiguracaoDeEixo<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuConfiguracaoDeEixo<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
-    }
-}
-
-impl SubmenuLayout for MenuConfiguracaoDeEixo<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&NUMERO_DO_CANAL_X),
-                variable: (33, &self.model.configuracao_do_eixo_x.numero_do_canal),
-                valid_range: 0..99, // TODO: test define range as `0..64`
-                unit_of_measurement_text: None,
-            }),
-
-            1 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&NUMERO_DE_PULSO_DO_GIRO_X),
-                variable: (
-                    33,
-                    &self.model.configuracao_do_eixo_x.numero_de_pulso_do_giro,
-                ),
-                valid_range: 0..999,
-                unit_of_measurement_text: None,
-            }),
-
-            2 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&JANELA_DE_PROTECAO_DO_GITO_X),
-                variable: (
-                    33,
-                    &self.model.configuracao_do_eixo_x.janela_de_protecao_do_giro,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: None,
-            }),
-
-            3 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&DESLOCAMENTO_GIRO_DO_MOTOR_X),
-                variable: (
-                    33,
-                    &self.model.configuracao_do_eixo_x.deslocamento_giro_do_motor,
-                ),
-                valid_range: 0..0xFFFF,
-                unit_of_measurement_text: None,
-            }),
-
-            4 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&GIRO_COM_FUNCAO_DE_PROTECAO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .configuracao_do_eixo_x
-                        .giro_com_funcao_de_protecao,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            5 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&GIRO_COM_FUNCAO_DE_CORRECAO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .configuracao_do_eixo_x
-                        .giro_com_funcao_de_correcao,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            6 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&LOGICA_DO_START_EXTERNO),
-                variable: (
-                    32,
-                    &self.model.configuracao_do_eixo_x.logica_do_start_externo,
-                ),
-                options_list: Options::aberto_fechado(),
-            }),
-
-            7 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&VALOR_DA_POSICAO_DA_REFERENCIA),
-                variable: (
-                    33,
-                    &self
-                        .model
-                        .configuracao_do_eixo_x
-                        .valor_da_posicao_de_referencia,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: None,
-            }),
-
-            8 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&VELOCIDADE_PARA_REFERENCIA_X),
-                variable: (
-                    33,
-                    &self.model.configuracao_do_eixo_x.velocidade_para_referencia,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: None,
-            }),
-
-            9 => MenuItemBuilder::make_numerical_parameter(NumericalParameter {
-                parameter_name: FlashString::new(&ACELERACAO_PARA_REFERENCIA_X),
-                variable: (
-                    33,
-                    &self.model.configuracao_do_eixo_x.aceleracao_para_referencia,
-                ),
-                valid_range: 0..9999,
-                unit_of_measurement_text: None,
-            }),
-
-            10 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&REDUCAO_DA_CORRENTE_EM_REPOUSO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .configuracao_do_eixo_x
-                        .reducao_da_corrente_em_repouso,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            11 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&REFERENCIA_PELO_START_EXTERNO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .configuracao_do_eixo_x
-                        .referencia_pelo_start_externo,
-                ),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            12 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&MODO_TURBO_X),
-                variable: (32, &self.model.configuracao_do_eixo_x.modo_turbo),
-                options_list: Options::ligado_desligado(),
-            }),
-
-            _ => None,
-        }
+submenu! {
+    // Channels and mechanical settings of the axis being configured (`SUBMENX` and `SUBMENY`
+    // require `NNIVEL-1` in the original TTC3100)
+    pub struct MenuConfiguracaoDeEixo(model) {
+        access_level: Manutencao,
+        items: [
+            // TODO: test define range as `0..64`
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            numerical(
+                NUMERO_DO_CANAL,
+                (33, &model.get_configuracao_do_eixo_by_ref().numero_do_canal),
+                0..99,
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            numerical(
+                NUMERO_DE_PULSO_DO_GIRO,
+                (33, &model.get_configuracao_do_eixo_by_ref().numero_de_pulso_do_giro),
+                0..999,
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            numerical(
+                JANELA_DE_PROTECAO_DO_GIRO,
+                (33, &model.get_configuracao_do_eixo_by_ref().janela_de_protecao_do_giro),
+                0..9999,
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            numerical(
+                DESLOCAMENTO_GIRO_DO_MOTOR,
+                (33, &model.get_configuracao_do_eixo_by_ref().deslocamento_giro_do_motor),
+                0..0xFFFF,
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            optional(
+                GIRO_COM_FUNCAO_DE_PROTECAO,
+                (32, &model.get_configuracao_do_eixo_by_ref().giro_com_funcao_de_protecao),
+                [DESLIGADO, LIGADO],
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            optional(
+                GIRO_COM_FUNCAO_DE_CORRECAO,
+                (32, &model.get_configuracao_do_eixo_by_ref().giro_com_funcao_de_correcao),
+                [DESLIGADO, LIGADO],
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            optional(
+                LOGICA_DO_START_EXTERNO,
+                (32, &model.get_configuracao_do_eixo_by_ref().logica_do_start_externo),
+                [ABERTO, FECHADO],
+            ),
+            numerical(
+                VALOR_DA_POSICAO_DA_REFERENCIA,
+                (33, &model.get_configuracao_do_eixo_by_ref().valor_da_posicao_de_referencia),
+                0..9999,
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            numerical(
+                VELOCIDADE_PARA_REFERENCIA,
+                (33, &model.get_configuracao_do_eixo_by_ref().velocidade_para_referencia),
+                0..9999,
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            numerical(
+                ACELERACAO_PARA_REFERENCIA,
+                (33, &model.get_configuracao_do_eixo_by_ref().aceleracao_para_referencia),
+                0..9999,
+            ),
+            optional(
+                REDUCAO_DA_CORRENTE_EM_REPOUSO,
+                (32, &model.get_configuracao_do_eixo_by_ref().reducao_da_corrente_em_repouso),
+                [DESLIGADO, LIGADO],
+            ),
+            optional(
+                REFERENCIA_PELO_START_EXTERNO,
+                (32, &model.get_configuracao_do_eixo_by_ref().referencia_pelo_start_externo),
+                [DESLIGADO, LIGADO],
+            ),
+            #[axis_suffix(model.gui_state.eixo_em_configuracao.get())]
+            optional(
+                MODO_TURBO,
+                (32, &model.get_configuracao_do_eixo_by_ref().modo_turbo),
+                [DESLIGADO, LIGADO],
+            ),
+        ]
     }
 }
 
@@ -925,45 +699,22 @@ impl SubmenuLayout for MenuConfiguracaoDeEixo<'_> {
 //          MENU CONFIGURACAO DO EQUIPAMENTO
 ////////////////////////////////////////////////////////////////////////////////////////////////////////
 
-pub struct MenuConfiguracaoDoEquipamento<'a> {
-    model: &'a DataModel,
-}
-
-impl<'a> MenuConfiguracaoDoEquipamento<'a> {
-    pub const fn new(model: &'a DataModel) -> Self {
-        Self { model }
-    }
-}
-
-impl SubmenuLayout for MenuConfiguracaoDoEquipamento<'_> {
-    fn get_item(&self, index: usize) -> Option<MenuItemWidget> {
-        match index {
-            0 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&VELOCIDADE_DE_COMUNICACAO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .configuracao_do_equipamento
-                        .velocidade_de_comunicacao,
-                ),
-                options_list: Options::baudrate_2400_9600(),
-            }),
-
+submenu! {
+    // Hardware settings (`XHARD` requires `NNIVEL-2` in the original TTC3100)
+    pub struct MenuConfiguracaoDoEquipamento(model) {
+        access_level: Supervisor,
+        items: [
+            optional(
+                VELOCIDADE_DE_COMUNICACAO,
+                (32, &model.configuracao_do_equipamento.velocidade_de_comunicacao),
+                [B2400, B9600],
+            ),
             // TODO: Remove the need of this duplicated parameter when possible
-            1 => MenuItemBuilder::make_optional_parameter(OptionalParameter {
-                parameter_name: FlashString::new(&VELOCIDADE_DE_COMUNICACAO),
-                variable: (
-                    32,
-                    &self
-                        .model
-                        .configuracao_do_equipamento
-                        .velocidade_de_comunicacao,
-                ),
-                options_list: Options::baudrate_2400_9600(),
-            }),
-
-            _ => None,
-        }
+            optional(
+                VELOCIDADE_DE_COMUNICACAO,
+                (32, &model.configuracao_do_equipamento.velocidade_de_comunicacao),
+                [B2400, B9600],
+            ),
+        ]
     }
 }
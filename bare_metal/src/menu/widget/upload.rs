@@ -0,0 +1,759 @@
+//! Upload of the programs loaded in ram to the cmpp boards of both axis.
+//!
+//! The upload is an incremental job: each call to [`Widget::update`] sends just one parameter, so
+//! the main loop keeps running (ie: keys and screen) while the data is sent. The user may abort it
+//! with `ESC`, and in the end a summary of the upload is shown.
+
+use avr_progmem::progmem;
+use cross_platform::protocol::{
+    datalink::datalink::Status,
+    transport::transport_layer::{TLError, TransportLayer},
+};
+
+use crate::{
+    board::keypad::KeyCode,
+    geometry::{point::Point, rectangle::Rectangle},
+    menu::{
+        model::{CmppData, DataModel, Eixo},
+        screen_buffer::ScreenRegion,
+    },
+    string::flash::FlashString,
+};
+
+use super::{
+    dialog::{Dialog, MessageBox, ProgressBar},
+    submenu_programa::flash_texts::*,
+    widget::Widget,
+};
+
+progmem! {
+    static progmem string CARGA_DO_EIXO_X = "Eixo X";
+    static progmem string CARGA_DO_EIXO_Y = "Eixo Y";
+    static progmem string CARGA_CONCLUIDA = "Carga do programa concluida";
+    static progmem string CARGA_COM_FALHA = "Falha na carga do programa";
+    static progmem string PARAMETROS_NAO_ENVIADOS = "Parametros nao enviados: ";
+    static progmem string CARGA_INTERROMPIDA = "Carga do programa interrompida";
+}
+
+/// Transaction which sends one parameter to the cmpp board
+type Transaction = fn(&TransportLayer, &CmppData) -> Result<Status, TLError>;
+
+/// One parameter sent to the cmpp board
+struct Parameter {
+    /// Name shown to the user while it is sent
+    name: FlashString,
+    send: Transaction,
+}
+
+/// Number of parameters sent to the cmpp board of each axis
+const NUMBER_OF_PARAMETERS: u8 = 41;
+
+/// Gets the parameter of the given index, the index must be less than [`NUMBER_OF_PARAMETERS`]
+fn get_parameter(index: u8) -> Option<Parameter> {
+    let parameter = match index {
+        0 => Parameter {
+            name: FlashString::new(&POSICAO_INICIAL),
+            send: |transport, data| {
+                transport
+                    .posicao_inicial()
+                    .set(data.arquivo_de_eixo.posicao_inicial.get().into())
+            },
+        },
+        1 => Parameter {
+            name: FlashString::new(&POSICAO_FINAL),
+            send: |transport, data| {
+                transport
+                    .posicao_final()
+                    .set(data.arquivo_de_eixo.posicao_final.get().into())
+            },
+        },
+        2 => Parameter {
+            name: FlashString::new(&ACELERACAO_DE_AVANCO),
+            send: |transport, data| {
+                transport
+                    .aceleracao_de_avanco()
+                    .set(data.arquivo_de_eixo.aceleracao_de_avanco.get().into())
+            },
+        },
+        3 => Parameter {
+            name: FlashString::new(&ACELERACAO_DE_RETORNO),
+            send: |transport, data| {
+                transport
+                    .aceleracao_de_retorno()
+                    .set(data.arquivo_de_eixo.aceleracao_de_retorno.get().into())
+            },
+        },
+        4 => Parameter {
+            name: FlashString::new(&VELOCIDADE_DE_AVANCO),
+            send: |transport, data| {
+                transport
+                    .velocidade_de_avanco()
+                    .set(data.arquivo_de_eixo.velocidade_de_avanco.get().into())
+            },
+        },
+        5 => Parameter {
+            name: FlashString::new(&VELOCIDADE_DE_RETORNO),
+            send: |transport, data| {
+                transport
+                    .velocidade_de_retorno()
+                    .set(data.arquivo_de_eixo.velocidade_de_retorno.get().into())
+            },
+        },
+        6 => Parameter {
+            name: FlashString::new(&NUMERO_DE_MENSAGEM_NO_AVANCO),
+            send: |transport, data| {
+                transport.numero_de_mensagem_no_avanco().set(
+                    data.arquivo_de_eixo
+                        .numero_de_mensagem_no_avanco
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        7 => Parameter {
+            name: FlashString::new(&NUMERO_DE_MENSAGEM_NO_RETORNO),
+            send: |transport, data| {
+                transport.numero_de_mensagem_no_retorno().set(
+                    data.arquivo_de_eixo
+                        .numero_de_mensagem_no_retorno
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        8 => Parameter {
+            name: FlashString::new(&PRIMEIRA_MENSAGEM_NO_AVANCO),
+            send: |transport, data| {
+                transport.primeira_mensagem_no_avanco().set(
+                    data.arquivo_de_eixo
+                        .primeira_mensagem_no_avanco
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        9 => Parameter {
+            name: FlashString::new(&ULTIMA_MENSAGEM_NO_AVANCO),
+            send: |transport, data| {
+                transport
+                    .ultima_mensagem_no_avanco()
+                    .set(data.arquivo_de_eixo.ultima_mensagem_no_avanco.get().into())
+            },
+        },
+        10 => Parameter {
+            name: FlashString::new(&PRIMEIRA_MENSAGEM_NO_RETORNO),
+            send: |transport, data| {
+                transport.primeira_mensagem_no_retorno().set(
+                    data.arquivo_de_eixo
+                        .primeira_mensagem_no_retorno
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        11 => Parameter {
+            name: FlashString::new(&ULTIMA_MENSAGEM_NO_RETORNO),
+            send: |transport, data| {
+                transport
+                    .ultima_mensagem_no_retorno()
+                    .set(data.arquivo_de_eixo.ultima_mensagem_no_retorno.get().into())
+            },
+        },
+        12 => Parameter {
+            name: FlashString::new(&LOGICA_DO_SINAL_DE_IMPRESSAO),
+            send: |transport, data| {
+                transport.logica_do_sinal_de_impressao().set(
+                    data.arquivo_de_eixo
+                        .logica_do_sinal_de_impressao
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        13 => Parameter {
+            name: FlashString::new(&LARGURA_DO_SINAL_DE_IMPRESSAO),
+            send: |transport, data| {
+                transport.largura_do_sinal_de_impressao().set(
+                    data.arquivo_de_eixo
+                        .largura_do_sinal_de_impressao
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        14 => Parameter {
+            name: FlashString::new(&REVERSAO_DE_MENSAGEM_VIA_SERIAL),
+            send: |transport, data| {
+                transport.reversao_de_mensagem_via_serial().set(
+                    data.arquivo_de_eixo
+                        .reversao_de_mensagem_via_serial
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        15 => Parameter {
+            name: FlashString::new(&SELECAO_DE_MENSAGEM_VIA_SERIAL),
+            send: |transport, data| {
+                transport.selecao_de_mensagem_via_serial().set(
+                    data.arquivo_de_eixo
+                        .selecao_de_mensagem_via_serial
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        16 => Parameter {
+            name: FlashString::new(&RETARDO_NO_START_AUTOMATICO),
+            send: |transport, data| {
+                transport.retardo_no_start_automatico().set(
+                    data.arquivo_de_eixo
+                        .retardo_no_start_automatico
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        17 => Parameter {
+            name: FlashString::new(&RETARDO_NO_START_EXTERNO),
+            send: |transport, data| {
+                transport
+                    .retardo_no_start_externo()
+                    .set(data.arquivo_de_eixo.retardo_no_start_externo.get().into())
+            },
+        },
+        18 => Parameter {
+            name: FlashString::new(&START_AUTOMATICO_NO_AVANCO),
+            send: |transport, data| {
+                transport
+                    .start_automatico_no_avanco()
+                    .set(data.arquivo_de_eixo.start_automatico_no_avanco.get().into())
+            },
+        },
+        19 => Parameter {
+            name: FlashString::new(&START_AUTOMATICO_NO_RETORNO),
+            send: |transport, data| {
+                transport.start_automatico_no_retorno().set(
+                    data.arquivo_de_eixo
+                        .start_automatico_no_retorno
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        20 => Parameter {
+            name: FlashString::new(&MODO_DE_TRABALHO_DO_EIXO),
+            send: |transport, data| {
+                transport
+                    .modo_de_trabalho_do_eixo()
+                    .set(data.arquivo_de_eixo.modo_de_trabalho_do_eixo.get().into())
+            },
+        },
+        21 => Parameter {
+            name: FlashString::new(&ANTECIPACAO_DA_SAIDA_DE_START),
+            send: |transport, data| {
+                transport.antecipacao_da_saida_de_start().set(
+                    data.arquivo_de_eixo
+                        .antecipacao_da_saida_de_start
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        22 => Parameter {
+            name: FlashString::new(&SAIDA_DE_START_NO_AVANCO),
+            send: |transport, data| {
+                transport
+                    .saida_de_start_no_avaco()
+                    .set(data.arquivo_de_eixo.saida_de_start_no_avaco.get().into())
+            },
+        },
+        23 => Parameter {
+            name: FlashString::new(&SAIDA_DE_START_NO_RETORNO),
+            send: |transport, data| {
+                transport
+                    .saida_de_start_no_retorno()
+                    .set(data.arquivo_de_eixo.saida_de_start_no_retorno.get().into())
+            },
+        },
+        24 => Parameter {
+            name: FlashString::new(&ENTRADA_DE_START_ENTRE_EIXOS),
+            send: |transport, data| {
+                transport.entrada_de_start_entre_eixos().set(
+                    data.arquivo_de_eixo
+                        .entrada_de_start_entre_eixos
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        25 => Parameter {
+            name: FlashString::new(&RETARDO_DO_START_ENTRE_EIXOS),
+            send: |transport, data| {
+                transport.retardo_do_start_entre_eixos().set(
+                    data.arquivo_de_eixo
+                        .retardo_do_start_entre_eixos
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        26 => Parameter {
+            name: FlashString::new(&START_PELO_TECLADO_E_EXTERNO),
+            send: |transport, data| {
+                transport.start_pelo_teclado_e_externo().set(
+                    data.arquivo_de_eixo
+                        .start_pelo_teclado_e_externo
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        27 => Parameter {
+            name: FlashString::new(&RETARDO_NO_SINAL_DE_IMPRESSAO),
+            send: |transport, data| {
+                transport.retardo_no_sinal_de_impressao().set(
+                    data.arquivo_de_eixo
+                        .retardo_no_sinal_de_impressao
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        28 => Parameter {
+            name: FlashString::new(&RETARDO_NO_START_PASSO_A_PASSO),
+            send: |transport, data| {
+                transport.retardo_no_start_passo_a_passo().set(
+                    data.arquivo_de_eixo
+                        .retardo_no_start_passo_a_passo
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        29 => Parameter {
+            name: FlashString::new(&START_AUTOMATICO_PASSO_A_PASSO),
+            send: |transport, data| {
+                transport.start_automatico_passo_a_passo().set(
+                    data.arquivo_de_eixo
+                        .start_automatico_passo_a_passo
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        30 => Parameter {
+            name: FlashString::new(&SAIDA_START_PASSO_A_PASSO),
+            send: |transport, data| {
+                transport.saida_de_start_passo_a_passo().set(
+                    data.arquivo_de_eixo
+                        .saida_de_start_passo_a_passo
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        31 => Parameter {
+            name: FlashString::new(&JANELA_DE_PROTECAO_DO_GIRO),
+            send: |transport, data| {
+                transport.janela_de_protecao_do_giro().set(
+                    data.configuracao_de_eixo
+                        .janela_de_protecao_do_giro
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        32 => Parameter {
+            name: FlashString::new(&DESLOCAMENTO_GIRO_DO_MOTOR),
+            send: |transport, data| {
+                transport.deslocamento_giro_do_motor().set(
+                    data.configuracao_de_eixo
+                        .deslocamento_giro_do_motor
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        33 => Parameter {
+            name: FlashString::new(&GIRO_COM_FUNCAO_DE_PROTECAO),
+            send: |transport, data| {
+                transport.giro_com_funcao_de_protecao().set(
+                    data.configuracao_de_eixo
+                        .giro_com_funcao_de_protecao
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        34 => Parameter {
+            name: FlashString::new(&GIRO_COM_FUNCAO_DE_CORRECAO),
+            send: |transport, data| {
+                transport.giro_com_funcao_de_correcao().set(
+                    data.configuracao_de_eixo
+                        .giro_com_funcao_de_correcao
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        35 => Parameter {
+            name: FlashString::new(&LOGICA_DO_START_EXTERNO),
+            send: |transport, data| {
+                transport.logica_do_start_externo().set(
+                    data.configuracao_de_eixo
+                        .logica_do_start_externo
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        36 => Parameter {
+            name: FlashString::new(&VALOR_DA_POSICAO_DA_REFERENCIA),
+            send: |transport, data| {
+                transport.valor_da_posicao_de_referencia().set(
+                    data.configuracao_de_eixo
+                        .valor_da_posicao_de_referencia
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        37 => Parameter {
+            name: FlashString::new(&VELOCIDADE_PARA_REFERENCIA),
+            send: |transport, data| {
+                transport.velocidade_para_referencia().set(
+                    data.configuracao_de_eixo
+                        .velocidade_para_referencia
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        38 => Parameter {
+            name: FlashString::new(&ACELERACAO_PARA_REFERENCIA),
+            send: |transport, data| {
+                transport.aceleracao_para_referencia().set(
+                    data.configuracao_de_eixo
+                        .aceleracao_para_referencia
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        39 => Parameter {
+            name: FlashString::new(&REDUCAO_DA_CORRENTE_EM_REPOUSO),
+            send: |transport, data| {
+                transport.reducao_da_corrente_em_repouso().set(
+                    data.configuracao_de_eixo
+                        .reducao_da_corrente_em_repouso
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        40 => Parameter {
+            name: FlashString::new(&REFERENCIA_PELO_START_EXTERNO),
+            send: |transport, data| {
+                transport.referencia_pelo_start_externo().set(
+                    data.configuracao_de_eixo
+                        .referencia_pelo_start_externo
+                        .get()
+                        .into(),
+                )
+            },
+        },
+        _ => return None,
+    };
+    Some(parameter)
+}
+
+/// Final result of the upload
+#[derive(Copy, Clone, PartialEq)]
+pub enum UploadResult {
+    Success,
+    /// Number of parameters which were not sent
+    Failure(u8),
+    Aborted,
+}
+
+/// Sends all the parameters of the programs loaded in ram to the cmpp boards, one parameter per
+/// update, showing the progress to the user.
+///
+/// NOTE: If a transaction fails the remaining parameters of the same axis are not sent, because
+/// probably the board is not connected and each transaction would wait its timeout.
+pub struct ProgramUpload<'a> {
+    model: &'a DataModel,
+    transport_x: &'a TransportLayer<'a>,
+    transport_y: &'a TransportLayer<'a>,
+    /// Axis being sent
+    eixo: Eixo,
+    /// Index of the next parameter to be sent in the current axis
+    next_parameter: u8,
+    /// Number of parameters which were not sent
+    failures: u8,
+    progress: ProgressBar,
+    /// Summary shown to the user in the end of the upload
+    summary: Option<(UploadResult, MessageBox)>,
+}
+
+impl<'a> ProgramUpload<'a> {
+    const TOTAL_OF_PARAMETERS: u16 = NUMBER_OF_PARAMETERS as u16 * 2; // both axis
+
+    pub fn new(
+        model: &'a DataModel,
+        transport_x: &'a TransportLayer<'a>,
+        transport_y: &'a TransportLayer<'a>,
+    ) -> Self {
+        Self {
+            model,
+            transport_x,
+            transport_y,
+            eixo: Eixo::X,
+            next_parameter: 0,
+            failures: 0,
+            progress: ProgressBar::new(FlashString::default(), Self::TOTAL_OF_PARAMETERS),
+            summary: None,
+        }
+    }
+
+    /// Returns `None` while the upload is running or its summary is being shown to the user
+    pub fn get_result(&self) -> Option<UploadResult> {
+        match &self.summary {
+            Some((result, summary)) if summary.get_result().is_some() => Some(*result),
+            _ => None,
+        }
+    }
+
+    fn get_transport(&self) -> &'a TransportLayer<'a> {
+        match self.eixo {
+            Eixo::X => self.transport_x,
+            Eixo::Y => self.transport_y,
+        }
+    }
+
+    /// Number of parameters already sent (or failed) in both axis
+    fn get_progress(&self) -> u16 {
+        let axis_done = match self.eixo {
+            Eixo::X => 0,
+            Eixo::Y => NUMBER_OF_PARAMETERS as u16,
+        };
+        axis_done + self.next_parameter as u16
+    }
+
+    /// Goes to the next axis, or finishes the upload if it was the last one
+    fn next_axis(&mut self) {
+        match self.eixo {
+            Eixo::X => {
+                self.eixo = Eixo::Y;
+                self.next_parameter = 0;
+            }
+            Eixo::Y => {
+                let result = if self.failures == 0 {
+                    UploadResult::Success
+                } else {
+                    UploadResult::Failure(self.failures)
+                };
+                self.finish(result);
+            }
+        }
+    }
+
+    fn finish(&mut self, result: UploadResult) {
+        if result == UploadResult::Success {
+            self.model.concluir_envio();
+        }
+        let summary = match result {
+            UploadResult::Success => {
+                MessageBox::new(FlashString::new(&CARGA_CONCLUIDA), FlashString::default())
+            }
+            UploadResult::Failure(failures) => MessageBox::new(
+                FlashString::new(&CARGA_COM_FALHA),
+                FlashString::new(&PARAMETROS_NAO_ENVIADOS),
+            )
+            .with_value(failures as u16),
+            UploadResult::Aborted => MessageBox::new(
+                FlashString::new(&CARGA_INTERROMPIDA),
+                FlashString::default(),
+            ),
+        };
+        self.summary = Some((result, summary));
+    }
+
+    /// Sends the next parameter
+    fn send_next_parameter(&mut self) {
+        let Some(parameter) = get_parameter(self.next_parameter) else {
+            self.next_axis();
+            return;
+        };
+        self.progress.set_caption(parameter.name);
+        let data = CmppData {
+            arquivo_de_eixo: self.model.get_arquivo_de_eixo(self.eixo),
+            configuracao_de_eixo: self.model.get_configuracao_do_eixo(self.eixo),
+        };
+        match (parameter.send)(self.get_transport(), &data) {
+            Ok(_) => {
+                self.next_parameter += 1;
+            }
+            Err(_) => {
+                // skips the remaining parameters of the axis
+                self.failures += NUMBER_OF_PARAMETERS - self.next_parameter;
+                self.next_parameter = NUMBER_OF_PARAMETERS;
+            }
+        }
+        self.progress.set_progress(self.get_progress());
+    }
+}
+
+impl Widget for ProgramUpload<'_> {
+    fn send_key(&mut self, key: KeyCode) {
+        match &mut self.summary {
+            Some((_, summary)) => summary.send_key(key),
+            None => {
+                if key == KeyCode::KEY_ESC {
+                    self.finish(UploadResult::Aborted);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        match &mut self.summary {
+            Some((_, summary)) => summary.update(),
+            None => self.send_next_parameter(),
+        }
+    }
+
+    fn draw(&self, region: &mut ScreenRegion) {
+        if let Some((_, summary)) = &self.summary {
+            summary.draw(region);
+            return;
+        }
+        // the progress bar takes the whole region except the end of the first line, where the
+        // axis is printed
+        let axis = match self.eixo {
+            Eixo::X => FlashString::new(&CARGA_DO_EIXO_X),
+            Eixo::Y => FlashString::new(&CARGA_DO_EIXO_Y),
+        };
+        self.progress.draw(region);
+        let axis_column = region.width().saturating_sub(axis.len());
+        let area = Rectangle::new(Point::new(axis_column, 0), Point::new(region.width(), 1));
+        let mut axis_region = region.sub_region(area);
+        axis_region.clear();
+        axis_region.print(axis);
+    }
+}
+
+#[cfg(all(test, not(any(feature = "lcd_20x4", feature = "lcd_16x2"))))]
+mod tests {
+    use super::*;
+    use crate::board::host::keyboard::ScriptedKeyboard;
+    use crate::menu::snapshot::{
+        assert_screen, draw, make_disconnected_datalink, make_emulated_cmpp_datalink,
+        make_mechanical_properties, run,
+    };
+
+    /// Updates the upload until its summary is shown
+    fn update_until_the_summary(upload: &mut ProgramUpload) {
+        while upload.summary.is_none() {
+            upload.update();
+        }
+    }
+
+    #[test]
+    fn it_shows_the_parameter_being_sent_and_the_progress() {
+        let datalink = make_emulated_cmpp_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut upload = ProgramUpload::new(&model, &transport, &transport);
+        for _ in 0..20 {
+            upload.update();
+        }
+        assert_eq!(
+            draw(&upload),
+            [
+                " Start automatico no retorno      Eixo X",
+                " #######....................... 24%     ",
+            ]
+        );
+        // the axis Y starts in the update after the last parameter of the axis X
+        for _ in 20..NUMBER_OF_PARAMETERS + 2 {
+            upload.update();
+        }
+        assert_eq!(
+            draw(&upload),
+            [
+                " Posicao inicial                  Eixo Y",
+                " ###############............... 51%     ",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_concludes_the_upload_when_all_the_parameters_are_sent() {
+        let datalink = make_emulated_cmpp_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut upload = ProgramUpload::new(&model, &transport, &transport);
+        update_until_the_summary(&mut upload);
+        assert!(!model.tem_envio_pendente());
+        assert_screen(
+            &mut upload,
+            ScriptedKeyboard::new(),
+            &[" Carga do programa concluida", ""],
+        );
+        // the summary is closed by its timeout
+        assert!(upload.get_result().is_none());
+        run(&mut upload, ScriptedKeyboard::new().wait(2000));
+        assert!(upload.get_result() == Some(UploadResult::Success));
+    }
+
+    #[test]
+    fn it_skips_the_rest_of_the_axis_when_a_transaction_fails() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut upload = ProgramUpload::new(&model, &transport, &transport);
+        // the first parameter of the axis X fails and the others are not sent
+        upload.update();
+        assert!(upload.eixo == Eixo::X);
+        assert_eq!(upload.failures, NUMBER_OF_PARAMETERS);
+        assert_eq!(upload.get_progress(), NUMBER_OF_PARAMETERS as u16);
+        // the same happens in the axis Y
+        upload.update();
+        upload.update();
+        assert!(upload.eixo == Eixo::Y);
+        assert_eq!(upload.failures, NUMBER_OF_PARAMETERS * 2);
+        update_until_the_summary(&mut upload);
+        assert!(model.tem_envio_pendente());
+        assert_screen(
+            &mut upload,
+            ScriptedKeyboard::new(),
+            &[
+                " Falha na carga do programa",
+                " Parametros nao enviados: 82",
+            ],
+        );
+        // the summary is closed by any key
+        run(&mut upload, ScriptedKeyboard::new().key(KeyCode::KEY_ENTER));
+        assert!(upload.get_result() == Some(UploadResult::Failure(82)));
+    }
+
+    #[test]
+    fn it_aborts_the_upload_with_esc() {
+        let datalink = make_disconnected_datalink();
+        let transport = TransportLayer::new(&datalink, make_mechanical_properties());
+        let model = DataModel::new();
+        let mut upload = ProgramUpload::new(&model, &transport, &transport);
+        assert_screen(
+            &mut upload,
+            ScriptedKeyboard::new().key(KeyCode::KEY_ESC),
+            &[" Carga do programa interrompida", ""],
+        );
+        assert!(upload.eixo == Eixo::X);
+        assert!(model.tem_envio_pendente());
+        assert!(upload.get_result().is_none());
+        run(&mut upload, ScriptedKeyboard::new().key(KeyCode::KEY_ESC));
+        assert!(upload.get_result() == Some(UploadResult::Aborted));
+    }
+}
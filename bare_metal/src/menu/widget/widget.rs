@@ -1,17 +1,22 @@
-use crate::{board::keypad::KeyCode, menu::screen_buffer::ScreenBuffer};
-
-use crate::geometry::point::Point;
+use crate::{
+    board::{keyboard::KeyEvent, keypad::KeyCode},
+    menu::screen_buffer::ScreenRegion,
+};
 
 /// A component that can be draw on lcd display, receive key strokes from keyboard and update its own state
 pub trait Widget {
     fn send_key(&mut self, key: KeyCode);
+    /// Receives all the events of the keyboard, by default just the keys pressed are considered
+    fn send_key_event(&mut self, event: KeyEvent) {
+        if let KeyEvent::Pressed(key) = event {
+            self.send_key(key)
+        }
+    }
     fn update(&mut self);
-    /// `start_point` points to the coordinates to start to draw the widget
-    /// TODO: Not sure this parameter should be a rectangle or even do not exist
-    /// TODO: Instead of `start_point` i should give a rectangle where the widget is responsible to draw it self
-    /// while the client is responsible to define where this rectangle is placed in the screen. This kind of implementation
-    /// make draw() method more generic
-    fn draw(&self, screen_buffer: &mut ScreenBuffer, start_point: Point);
+    /// Draws the widget inside the given `region`, the widget is responsible to draw it self while
+    /// the client is responsible to define where this region is placed in the screen. Anything
+    /// drawn outside of the region is clipped.
+    fn draw(&self, region: &mut ScreenRegion);
 }
 
 /// Represents an editable type (ie: editable Widget)
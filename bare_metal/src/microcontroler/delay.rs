@@ -1,11 +1,16 @@
 //! Just a wrapper over the ruduino lib
+//!
+//! In the host the delays do not block, they advance the virtual clock (see [`super::timer`]),
+//! unless the clock follows the wall clock, then they sleep.
 
+#[cfg(target_arch = "avr")]
 use ruduino::delay::{delay_ms as delay_ms__, delay_us as delay_us__};
 
 /// Blocking delay given miliseconds value
 ///
 /// The ruduino delay appears to clamp miliseconds values above 1024.
 /// Here we will allow values greater than that.
+#[cfg(target_arch = "avr")]
 pub fn delay_ms(total_ms: u64) {
     delay_ms__(total_ms);
 
@@ -26,6 +31,26 @@ pub fn delay_ms(total_ms: u64) {
     */
 }
 
+#[cfg(target_arch = "avr")]
 pub fn delay_us(total_us: u64) {
     delay_us__(total_us)
 }
+
+#[cfg(not(target_arch = "avr"))]
+pub fn delay_ms(total_ms: u64) {
+    if super::timer::is_real_time() {
+        std::thread::sleep(std::time::Duration::from_millis(total_ms));
+    } else {
+        super::timer::advance_virtual_clock(total_ms);
+    }
+}
+
+/// NOTE: The virtual clock has resolution of 1 milisec, so the fraction is discarded
+#[cfg(not(target_arch = "avr"))]
+pub fn delay_us(total_us: u64) {
+    if super::timer::is_real_time() {
+        std::thread::sleep(std::time::Duration::from_micros(total_us));
+    } else {
+        super::timer::advance_virtual_clock(total_us / 1000);
+    }
+}
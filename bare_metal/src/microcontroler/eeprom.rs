@@ -1,16 +1,23 @@
+use core::cell::Cell;
+
 use cross_platform::{protocol::datalink::datalink::word16::Word16, utils::cursor::Cursor};
+#[cfg(target_arch = "avr")]
 use ruduino::{cores::current as avr_core, interrupt::without_interrupts, Register};
 
+#[cfg(target_arch = "avr")]
 use avr_core::{EEAR, EECR, EEDR, SPMCSR};
 
 use crate::fatal_error;
 
+/// Total size in bytes of the EEPROM available in the atmega328p
+pub const EEPROM_SIZE: u16 = 1024;
+
 /// Low-level function to write one single byte in local EEPROM
 /// NOTE: This code was originally written by `MalteT` and was grab by fvilante from github commit below:
 /// https://github.com/MalteT/counter-avr/blob/ebb7ca36d7a04b11265cb41024798a38ac31ad05/src/main.rs#L251
-/// TODO: Make address u16 or something that can address 1kb of EEPROM
 /// TODO: Use crate avr-device when possible to make register manipulation instead of ruduino
-fn write_eeprom_raw(address: u8, byte: u8) {
+#[cfg(target_arch = "avr")]
+fn write_eeprom_raw(address: u16, byte: u8) {
     without_interrupts(|| {
         // Do not acces eeprom, if it is written to or the flash is currently programmed!
         while EECR::is_set(EECR::EEPE) || SPMCSR::is_set(SPMCSR::SPMEN) {}
@@ -29,9 +36,9 @@ fn write_eeprom_raw(address: u8, byte: u8) {
 /// Low-level function that reads one single byte from local EEPROM
 /// NOTE: This code was originally written by `MalteT` and was grab by fvilante from github commit below:
 /// https://github.com/MalteT/counter-avr/blob/ebb7ca36d7a04b11265cb41024798a38ac31ad05/src/main.rs#L251
-/// TODO: Make address u16 or something that can address 1kb of EEPROM
 /// TODO: Use crate avr-device when possible to make register manipulation instead of ruduino
-fn read_eeprom_byte_raw(address: u8) -> u8 {
+#[cfg(target_arch = "avr")]
+fn read_eeprom_byte_raw(address: u16) -> u8 {
     without_interrupts(|| {
         // Do not acces eeprom, if it is written to or the flash is currently programmed!
         while EECR::is_set(EECR::EEPE) || SPMCSR::is_set(SPMCSR::SPMEN) {}
@@ -46,61 +53,102 @@ fn read_eeprom_byte_raw(address: u8) -> u8 {
     })
 }
 
+#[cfg(not(target_arch = "avr"))]
+std::thread_local! {
+    /// Emulated eeprom of the host, it starts erased (all bytes 0xFF) as a never flashed board
+    static HOST_EEPROM: core::cell::RefCell<[u8; EEPROM_SIZE as usize]> =
+        core::cell::RefCell::new([0xFF; EEPROM_SIZE as usize]);
+    /// File where the emulated eeprom is persisted, see [`persist_in_file`]
+    static HOST_EEPROM_FILE: core::cell::RefCell<Option<std::fs::File>> =
+        core::cell::RefCell::new(None);
+}
+
+/// Keeps the eeprom of the host in the given file, so it survives between the runs of the
+/// simulator. The previous content of the file is loaded, a new file starts erased.
+#[cfg(not(target_arch = "avr"))]
+pub fn persist_in_file(path: &std::path::Path) -> std::io::Result<()> {
+    use std::io::{Read, Seek, Write};
+    let mut file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    let mut content = std::vec::Vec::new();
+    file.read_to_end(&mut content)?;
+    HOST_EEPROM.with(|eeprom| {
+        let mut eeprom = eeprom.borrow_mut();
+        let size = content.len().min(EEPROM_SIZE as usize);
+        eeprom[..size].copy_from_slice(&content[..size]);
+        // a short file is completed with the erased bytes
+        file.rewind()?;
+        file.write_all(&*eeprom)
+    })?;
+    HOST_EEPROM_FILE.with(|eeprom_file| *eeprom_file.borrow_mut() = Some(file));
+    Ok(())
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn write_eeprom_raw(address: u16, byte: u8) {
+    use std::io::{Seek, SeekFrom, Write};
+    HOST_EEPROM.with(|eeprom| eeprom.borrow_mut()[address as usize] = byte);
+    HOST_EEPROM_FILE.with(|eeprom_file| {
+        if let Some(file) = eeprom_file.borrow_mut().as_mut() {
+            file.seek(SeekFrom::Start(address as u64))
+                .and_then(|_| file.write_all(&[byte]))
+                .expect("Cannot write the eeprom file");
+        }
+    })
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn read_eeprom_byte_raw(address: u16) -> u8 {
+    HOST_EEPROM.with(|eeprom| eeprom.borrow()[address as usize])
+}
+
 /// mid-level eeprom type
 #[derive(Copy, Clone)]
-pub struct EepromAddress(pub u8);
+pub struct EepromAddress(pub u16);
 
 impl EepromAddress {
     pub fn read(&self) -> u8 {
         read_eeprom_byte_raw(self.0)
     }
+
+    /// Writes the byte only if it is different from the value already stored. This saves eeprom
+    /// write cycles and time (each byte written takes ~3.4ms).
     pub fn write(&self, val: u8) {
-        write_eeprom_raw(self.0, val)
+        if self.read() != val {
+            write_eeprom_raw(self.0, val)
+        }
+    }
+
+    /// Returns the address of the next byte or None if current address is out of the eeprom range.
+    fn next_address(&self) -> Option<u16> {
+        if self.0 < EEPROM_SIZE {
+            Some(self.0 + 1)
+        } else {
+            None
+        }
     }
 
-    /// EEprom address out of 255 range.
-    /// TODO: Currently this eeprom driver only address 255 bytes, change it to address the 1KB available
-    ///      in the avr328p
+    /// EEprom address out of range.
     pub fn out_of_range_error() -> ! {
         //TODO: Currently panic is not showing message in lcd because this is a costly operation in terms of flash consumption
         //because that it's necessary to elaborate a way to show this error message on lcd display in a cheap way.
         fatal_error!(108)
     }
 
-    /// TODO: KNOWN-ISSUES: only address first 255 bytes of eeprom, and cannot address the last word address.
     pub fn write_u8(&self, val: u8) -> EepromAddress {
-        let address = self.0;
-        let Some(next_address) = address.checked_add(1) else {
+        let Some(next_address) = self.next_address() else {
             Self::out_of_range_error()
         };
         self.write(val);
         EepromAddress(next_address)
     }
 
-    /// Writes u16 into the current address (in `little-endian` format) and returns the address of the next chunk.
-    /// TODO: KNOWN-ISSUES: only address first 255 bytes of eeprom, and cannot address the last word address.
-    pub fn write_u16(&self, val: u16) -> EepromAddress {
-        let (byte_low, byte_high) = Word16::from_u16(val).split_bytes();
-        let next = self.write_u8(byte_low);
-        let next = next.write_u8(byte_high);
-        next
-    }
-
-    /// TODO: Cursor is being write in eeprom using 3 bytes, but if Cursor::start is always zero we can use just 2 bytes
-    pub fn write_cursor(&self, cursor: Cursor) -> EepromAddress {
-        let byte_0 = cursor.get_current();
-        let byte_1 = cursor.get_range().start; // TODO: Check if this byte is always 0, and if it is remove it from eeprom
-        let byte_2 = cursor.get_range().end;
-        let next = self.write_u8(byte_0);
-        let next = next.write_u8(byte_1);
-        let next = next.write_u8(byte_2);
-        next
-    }
-
-    /// TODO: KNOWN-ISSUES: only address first 255 bytes of eeprom, and cannot address the last word address.
     pub fn read_u8(&self) -> (u8, Self) {
-        let address = self.0;
-        let Some(next_address) = address.checked_add(1) else {
+        let Some(next_address) = self.next_address() else {
             Self::out_of_range_error()
         };
         let value = self.read();
@@ -109,8 +157,7 @@ impl EepromAddress {
 
     /// Reads one byte from eeprom address and advance address by one. Returns None if error.
     pub fn read_u8_mut(&mut self) -> Option<u8> {
-        let address = self.0;
-        if let Some(next_address) = address.checked_add(1) {
+        if let Some(next_address) = self.next_address() {
             let value = self.read();
             self.0 = next_address;
             Some(value)
@@ -120,7 +167,6 @@ impl EepromAddress {
     }
 
     /// Return the u16 read (in `little-endian` format) and the address pointing to the next chunk
-    /// TODO: KNOWN-ISSUES: only address first 255 bytes of eeprom, and cannot address the last word address.
     pub fn read_u16(&self) -> (u16, Self) {
         let (byte_low, next) = self.read_u8();
         let (byte_high, next) = next.read_u8();
@@ -151,3 +197,137 @@ impl EepromAddress {
     // None if iterator is exausted before enough data has been iterated to construct type T
     //fn read_iterable<T: FromIterator>(&self) -> Option<T> {}
 }
+
+/// Destination of the bytes of a record stored in eeprom, the bytes are written in sequence and
+/// each write returns the writer of the next byte.
+pub trait EepromWriter: Copy {
+    fn write_u8(&self, val: u8) -> Self;
+
+    /// Writes u16 into the current address (in `little-endian` format) and returns the address of the next chunk.
+    fn write_u16(&self, val: u16) -> Self {
+        let (byte_low, byte_high) = Word16::from_u16(val).split_bytes();
+        let next = self.write_u8(byte_low);
+        let next = next.write_u8(byte_high);
+        next
+    }
+
+    /// TODO: Cursor is being write in eeprom using 3 bytes, but if Cursor::start is always zero we can use just 2 bytes
+    fn write_cursor(&self, cursor: Cursor) -> Self {
+        let byte_0 = cursor.get_current();
+        let byte_1 = cursor.get_range().start; // TODO: Check if this byte is always 0, and if it is remove it from eeprom
+        let byte_2 = cursor.get_range().end;
+        let next = self.write_u8(byte_0);
+        let next = next.write_u8(byte_1);
+        let next = next.write_u8(byte_2);
+        next
+    }
+}
+
+impl EepromWriter for EepromAddress {
+    fn write_u8(&self, val: u8) -> Self {
+        EepromAddress::write_u8(self, val)
+    }
+}
+
+/// Writes a record into eeprom a few bytes at a time, so a big record does not block the main
+/// loop for long (each byte takes ~3.4ms to be written).
+///
+/// The record is serialized from its beginning in each call, the bytes before the `cursor` were
+/// written by the previous calls and are skipped. The bytes equal to the ones in the eeprom are
+/// only read, at most `budget` bytes are really written in each call.
+#[derive(Copy, Clone)]
+pub struct IncrementalWriter<'a> {
+    address: EepromAddress,
+    /// Position of the current byte in the record
+    offset: u16,
+    /// Position of the next byte to be written
+    cursor: &'a Cell<u16>,
+    /// Bytes which still may be written in this call
+    budget: &'a Cell<u8>,
+}
+
+impl<'a> IncrementalWriter<'a> {
+    pub fn new(address: EepromAddress, cursor: &'a Cell<u16>, budget: &'a Cell<u8>) -> Self {
+        Self {
+            address,
+            offset: 0,
+            cursor,
+            budget,
+        }
+    }
+
+    /// True if all the bytes of the record were written, when called on the writer returned by the
+    /// serialization of the whole record
+    pub fn is_finished(&self) -> bool {
+        self.cursor.get() >= self.offset
+    }
+}
+
+impl<'a> EepromWriter for IncrementalWriter<'a> {
+    fn write_u8(&self, val: u8) -> Self {
+        let Some(next_address) = self.address.next_address() else {
+            EepromAddress::out_of_range_error()
+        };
+        if self.offset == self.cursor.get() {
+            let is_written = self.address.read() == val;
+            if is_written || self.budget.get() > 0 {
+                if !is_written {
+                    write_eeprom_raw(self.address.0, val);
+                    self.budget.set(self.budget.get() - 1);
+                }
+                self.cursor.set(self.offset + 1);
+            }
+        }
+        Self {
+            address: EepromAddress(next_address),
+            offset: self.offset + 1,
+            ..*self
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "avr")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_persists_the_eeprom_in_a_file() {
+        let path = std::env::temp_dir().join("it_persists_the_eeprom_in_a_file.bin");
+        let _ = std::fs::remove_file(&path);
+
+        // a new file starts erased
+        persist_in_file(&path).unwrap();
+        assert_eq!(EepromAddress(10).read_u16().0, 0xFFFF);
+        EepromAddress(10).write_u16(1234);
+
+        // each test runs in its own thread, so this one has a new eeprom
+        std::thread::spawn(move || {
+            persist_in_file(&path).unwrap();
+            assert_eq!(EepromAddress(10).read_u16().0, 1234);
+            let _ = std::fs::remove_file(&path);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn it_writes_a_record_a_few_bytes_at_a_time() {
+        fn record(writer: IncrementalWriter) -> IncrementalWriter {
+            writer.write_u16(0x1234).write_u8(0xFF).write_u16(0x5678)
+        }
+        let (cursor, calls) = (Cell::new(0), Cell::new(0));
+        loop {
+            calls.set(calls.get() + 1);
+            let budget = Cell::new(2);
+            let writer = record(IncrementalWriter::new(EepromAddress(20), &cursor, &budget));
+            if writer.is_finished() {
+                break;
+            }
+        }
+        // the byte 0xFF is already in the erased eeprom, so only 4 bytes are written
+        assert_eq!(calls.get(), 2);
+        assert_eq!(EepromAddress(20).read_u16().0, 0x1234);
+        assert_eq!(EepromAddress(22).read(), 0xFF);
+        assert_eq!(EepromAddress(23).read_u16().0, 0x5678);
+    }
+}
@@ -1,23 +1,21 @@
 //! Utility to generates asynchronous assymetric parametrizable retangular wave form
 
-use crate::microcontroler::timer::now;
+use cross_platform::types::time::{Duration, Instant};
 
-type TimePoint = u64;
-type Duration = u16;
+use crate::microcontroler::timer::now_instant;
 
 /// Generates a non-synchronous assymetric parametrizable retangular wave form
 pub struct RectangularWave {
     up_interval: Duration,
     down_interval: Duration,
-    next_time_point: TimePoint,
+    next_time_point: Instant,
     current_state: bool,
 }
 
 impl RectangularWave {
-    /// interval in miliseconds
     pub fn new(up_interval: Duration, down_interval: Duration) -> Self {
         let initial_state = true;
-        let next_time_point = now() as TimePoint + up_interval as TimePoint;
+        let next_time_point = now_instant() + up_interval;
         Self {
             up_interval,
             down_interval,
@@ -30,17 +28,16 @@ impl RectangularWave {
     ///
     /// You should call this method in a frequency relative to the wave form being generated
     pub fn update(&mut self) {
-        let is_time_to_change_state = now() as TimePoint > self.next_time_point;
+        let is_time_to_change_state = self.next_time_point.is_before(now_instant());
         if is_time_to_change_state {
             // toggle state
             self.current_state = !self.current_state;
             // calc next time point
-            let delta: TimePoint = if self.current_state {
+            let delta = if self.current_state {
                 self.up_interval
             } else {
                 self.down_interval
-            }
-            .into();
+            };
             self.next_time_point += delta;
         }
     }
@@ -52,3 +49,33 @@ impl RectangularWave {
         self.current_state
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::microcontroler::timer::advance_virtual_clock;
+
+    #[test]
+    fn it_keeps_the_wave_across_the_wrap_of_the_clock() {
+        advance_virtual_clock(u32::MAX as u64 - 250);
+        let mut wave = RectangularWave::new(Duration::from_millis(200), Duration::from_millis(100));
+        let mut changes = Vec::new();
+        for time in 0..700 {
+            wave.update();
+            if changes.last().map(|(_, state)| *state) != Some(wave.read()) {
+                changes.push((time, wave.read()));
+            }
+            advance_virtual_clock(1);
+        }
+        assert_eq!(
+            changes,
+            [
+                (0, true),
+                (201, false),
+                (301, true),
+                (501, false),
+                (601, true)
+            ]
+        );
+    }
+}
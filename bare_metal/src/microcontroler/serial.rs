@@ -57,9 +57,162 @@
 //! }
 //! ```
 //!
+//! In the board the bytes are received and transmitted by the interrupts of the uart, through the
+//! [`SerialBuffers`], so no byte is lost while the main loop is busy (ie: rendering the lcd).
+//!
+//! In the host the bytes go to the port plugged with [`connect`], ie: the emulated cmpp of the
+//! simulator.
+//!
+use cross_platform::serial_mock::ring_buffer::RingBuffer;
+#[cfg(target_arch = "avr")]
 use ruduino::legacy::serial;
 
+/// Bytes lost in the reception
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct Overruns {
+    /// The receive buffer was full, the main loop is not reading the bytes fast enough
+    pub buffer: u16,
+    /// The uart received a byte before the previous one was read, the interrupts were disabled
+    /// for too long
+    pub hardware: u16,
+}
+
+/// Buffers between the interrupts of the uart and the application
+///
+/// The interrupt side calls [`SerialBuffers::on_byte_received`] and
+/// [`SerialBuffers::next_byte_to_transmit`], the application side calls
+/// [`SerialBuffers::try_transmit`] and [`SerialBuffers::try_receive`].
+pub struct SerialBuffers<const RX_SIZE: usize, const TX_SIZE: usize> {
+    rx: RingBuffer<u8, RX_SIZE>,
+    tx: RingBuffer<u8, TX_SIZE>,
+    overruns: Overruns,
+    /// True if some byte was lost since the last [`SerialBuffers::take_overrun`]
+    has_overrun: bool,
+}
+
+impl<const RX_SIZE: usize, const TX_SIZE: usize> SerialBuffers<RX_SIZE, TX_SIZE> {
+    pub const fn new() -> Self {
+        Self {
+            rx: RingBuffer::new([0; RX_SIZE]),
+            tx: RingBuffer::new([0; TX_SIZE]),
+            overruns: Overruns {
+                buffer: 0,
+                hardware: 0,
+            },
+            has_overrun: false,
+        }
+    }
+
+    /// Stores the byte received by the uart. `hardware_overrun` is true if the uart has lost the
+    /// bytes before this one.
+    pub fn on_byte_received(&mut self, byte: u8, hardware_overrun: bool) {
+        if hardware_overrun {
+            self.overruns.hardware = self.overruns.hardware.saturating_add(1);
+            self.has_overrun = true;
+        }
+        if self.rx.write(byte).is_err() {
+            self.overruns.buffer = self.overruns.buffer.saturating_add(1);
+            self.has_overrun = true;
+        }
+    }
+
+    /// Byte to be written in the uart, `None` if there is nothing to transmit
+    pub fn next_byte_to_transmit(&mut self) -> Option<u8> {
+        self.tx.read().ok()
+    }
+
+    /// Queues the byte to be transmitted, Err if the transmit buffer is full
+    pub fn try_transmit(&mut self, byte: u8) -> Result<(), ()> {
+        self.tx.write(byte).map_err(|_| ())
+    }
+
+    pub fn try_receive(&mut self) -> Option<u8> {
+        self.rx.read().ok()
+    }
+
+    /// True if there is no byte waiting to be transmitted
+    pub fn is_transmit_buffer_empty(&self) -> bool {
+        self.tx.is_empty()
+    }
+
+    pub fn get_overruns(&self) -> Overruns {
+        self.overruns
+    }
+
+    /// True once if some byte was lost since the last call
+    pub fn take_overrun(&mut self) -> bool {
+        core::mem::replace(&mut self.has_overrun, false)
+    }
+}
+
+// Uart driven by interrupts (avr)
+
+#[cfg(target_arch = "avr")]
+mod uart {
+    use core::cell::RefCell;
+
+    use avr_device::atmega328p::USART0;
+    use avr_device::interrupt::{self, Mutex};
+
+    use super::SerialBuffers;
+
+    /// The receive buffer holds some frames of the cmpp protocol, which have 9 bytes (or more if
+    /// some byte is escaped)
+    static BUFFERS: Mutex<RefCell<SerialBuffers<64, 32>>> =
+        Mutex::new(RefCell::new(SerialBuffers::new()));
+
+    pub fn registers() -> &'static avr_device::atmega328p::usart0::RegisterBlock {
+        // SAFETY: The registers are only changed inside critical sections or inside the interrupts
+        unsafe { &*USART0::ptr() }
+    }
+
+    pub fn with_buffers<T>(f: impl FnOnce(&mut SerialBuffers<64, 32>) -> T) -> T {
+        interrupt::free(|cs| f(&mut BUFFERS.borrow(cs).borrow_mut()))
+    }
+
+    /// Enables the interrupts of the reception, the uart must be configured before
+    pub fn enable_interrupts() {
+        registers().ucsr0b.modify(|_, w| w.rxcie0().set_bit());
+    }
+
+    /// Enables the interrupt which transmits the buffer, it disables itself when the buffer is
+    /// empty
+    pub fn start_transmission() {
+        registers().ucsr0b.modify(|_, w| w.udrie0().set_bit());
+    }
+
+    #[avr_device::interrupt(atmega328p)]
+    fn USART_RX() {
+        let uart = registers();
+        // the flag of overrun must be read before the byte
+        let hardware_overrun = uart.ucsr0a.read().dor0().bit_is_set();
+        let byte = uart.udr0.read().bits();
+        interrupt::free(|cs| {
+            BUFFERS
+                .borrow(cs)
+                .borrow_mut()
+                .on_byte_received(byte, hardware_overrun)
+        });
+    }
+
+    #[avr_device::interrupt(atmega328p)]
+    fn USART_UDRE() {
+        let uart = registers();
+        match interrupt::free(|cs| BUFFERS.borrow(cs).borrow_mut().next_byte_to_transmit()) {
+            Some(byte) => {
+                // writing one clears the flag, it is set again when this byte leaves the shift
+                // register. Only TXC0 is written: a read-modify-write would also write back the
+                // other flags read as one (the single speed mode, U2X0 = 0, is kept)
+                uart.ucsr0a.write(|w| w.txc0().set_bit());
+                uart.udr0.write(|w| unsafe { w.bits(byte) })
+            }
+            None => uart.ucsr0b.modify(|_, w| w.udrie0().clear_bit()),
+        }
+    }
+}
+
 /// you must call this function before call other serial related functions
+#[cfg(target_arch = "avr")]
 pub fn init(baud_rate: u32) {
     let BAUD: u32 = baud_rate;
     let UBRR: u16 = (ruduino::config::CPU_FREQUENCY_HZ / 16 / BAUD - 1) as u16;
@@ -69,36 +222,211 @@ pub fn init(baud_rate: u32) {
         .parity(serial::Parity::Disabled)
         .stop_bits(serial::StopBits::OneBit)
         .configure();
+    uart::enable_interrupts();
 }
 
-/// non-blocking checking if there exists a byte to be transmit
+/// non-blocking checking if there is room in the transmit buffer
+#[cfg(target_arch = "avr")]
 pub fn ready_to_transmit() -> bool {
-    serial::ready_to_transmit()
+    uart::with_buffers(|buffers| !buffers.tx.is_full())
 }
 
 /// Does a blocking transfer of one byte
+#[cfg(target_arch = "avr")]
 pub fn transmit(byte: u8) {
-    serial::transmit(byte)
+    while try_transmit(byte).is_err() {}
 }
 
 /// Non-blocking transmission of one byte and OK, or if not ready to transmit yet return Err
 /// NOTE: Pay attention the Err does not represent an error, but that transmission could not be performed yet,
 /// and that you may try send the same byte a few moment later.
+#[cfg(target_arch = "avr")]
 pub fn try_transmit(byte: u8) -> Result<(), ()> {
-    serial::try_transmit(byte)
+    uart::with_buffers(|buffers| buffers.try_transmit(byte))?;
+    uart::start_transmission();
+    Ok(())
 }
 
 /// non-blocking checking if there exists a byte to be received
+#[cfg(target_arch = "avr")]
 pub fn ready_to_receive() -> bool {
-    serial::ready_to_receive()
+    uart::with_buffers(|buffers| !buffers.rx.is_empty())
 }
 
 /// Does a blocking read of one byte
+#[cfg(target_arch = "avr")]
 pub fn receive() -> u8 {
-    serial::receive()
+    loop {
+        if let Some(byte) = try_receive() {
+            return byte;
+        }
+    }
 }
 
 /// non-blocking try to read one byte from serial, if `Some` byte available returns it, else returns `None`
+#[cfg(target_arch = "avr")]
 pub fn try_receive() -> Option<u8> {
-    serial::try_receive()
+    uart::with_buffers(|buffers| buffers.try_receive())
+}
+
+/// Same as [`try_receive`] but it gives `Err` (once) if some byte was lost since the last call,
+/// so the frame being received is known to be broken.
+#[cfg(target_arch = "avr")]
+pub fn try_receive_checked() -> Result<Option<u8>, ()> {
+    uart::with_buffers(|buffers| match buffers.take_overrun() {
+        true => Err(()),
+        false => Ok(buffers.try_receive()),
+    })
+}
+
+/// True when all the bytes transmitted have left the uart, ie: the driver of a RS-485 bus may be
+/// disabled (see [`cross_platform::protocol::datalink::datalink::HalfDuplex`])
+#[cfg(target_arch = "avr")]
+pub fn is_transmission_complete() -> bool {
+    uart::with_buffers(|buffers| {
+        buffers.is_transmit_buffer_empty() && uart::registers().ucsr0a.read().txc0().bit_is_set()
+    })
+}
+
+/// Bytes lost in the reception since the power-on
+#[cfg(target_arch = "avr")]
+pub fn get_overruns() -> Overruns {
+    uart::with_buffers(|buffers| buffers.get_overruns())
+}
+
+// Serial port (host)
+
+/// Serial port of the host, it takes the place of the microcontroler's uart
+#[cfg(not(target_arch = "avr"))]
+pub trait HostSerialPort {
+    fn set_baudrate(&mut self, baud_rate: u32);
+    /// Same as [`try_transmit`]
+    fn try_transmit(&mut self, byte: u8) -> Result<(), ()>;
+    /// Same as [`try_receive`]
+    fn try_receive(&mut self) -> Option<u8>;
+}
+
+#[cfg(not(target_arch = "avr"))]
+std::thread_local! {
+    static HOST_SERIAL_PORT: core::cell::RefCell<Option<std::boxed::Box<dyn HostSerialPort>>> =
+        core::cell::RefCell::new(None);
+}
+
+/// Plugs the port in the serial. While no port is plugged the bytes transmitted are lost and
+/// nothing is received, as if the cable was unplugged.
+#[cfg(not(target_arch = "avr"))]
+pub fn connect(port: impl HostSerialPort + 'static) {
+    HOST_SERIAL_PORT
+        .with(|serial_port| *serial_port.borrow_mut() = Some(std::boxed::Box::new(port)));
+}
+
+#[cfg(not(target_arch = "avr"))]
+fn with_port<T>(default: T, f: impl FnOnce(&mut dyn HostSerialPort) -> T) -> T {
+    HOST_SERIAL_PORT.with(|serial_port| match serial_port.borrow_mut().as_mut() {
+        Some(port) => f(port.as_mut()),
+        None => default,
+    })
+}
+
+/// you must call this function before call other serial related functions
+#[cfg(not(target_arch = "avr"))]
+pub fn init(baud_rate: u32) {
+    with_port((), |port| port.set_baudrate(baud_rate))
+}
+
+/// Does a blocking transfer of one byte
+#[cfg(not(target_arch = "avr"))]
+pub fn transmit(byte: u8) {
+    while try_transmit(byte).is_err() {}
+}
+
+/// Non-blocking transmission of one byte and OK, or if not ready to transmit yet return Err
+#[cfg(not(target_arch = "avr"))]
+pub fn try_transmit(byte: u8) -> Result<(), ()> {
+    with_port(Ok(()), |port| port.try_transmit(byte))
+}
+
+/// Does a blocking read of one byte
+#[cfg(not(target_arch = "avr"))]
+pub fn receive() -> u8 {
+    loop {
+        if let Some(byte) = try_receive() {
+            return byte;
+        }
+    }
+}
+
+/// non-blocking try to read one byte from serial, if `Some` byte available returns it, else returns `None`
+#[cfg(not(target_arch = "avr"))]
+pub fn try_receive() -> Option<u8> {
+    with_port(None, |port| port.try_receive())
+}
+
+/// The ports of the host transmit the bytes at once
+#[cfg(not(target_arch = "avr"))]
+pub fn is_transmission_complete() -> bool {
+    true
+}
+
+/// Same as [`try_receive`], the ports of the host do not lose bytes
+#[cfg(not(target_arch = "avr"))]
+pub fn try_receive_checked() -> Result<Option<u8>, ()> {
+    Ok(try_receive())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_transmits_the_bytes_in_order() {
+        let mut buffers: SerialBuffers<4, 2> = SerialBuffers::new();
+        assert!(buffers.try_transmit(0x1B).is_ok());
+        assert!(buffers.try_transmit(0x02).is_ok());
+        // the interrupt has not transmitted anything yet
+        assert!(buffers.try_transmit(0xC1).is_err());
+        assert_eq!(buffers.next_byte_to_transmit(), Some(0x1B));
+        assert!(buffers.try_transmit(0xC1).is_ok());
+        assert_eq!(buffers.next_byte_to_transmit(), Some(0x02));
+        assert_eq!(buffers.next_byte_to_transmit(), Some(0xC1));
+        assert_eq!(buffers.next_byte_to_transmit(), None);
+        assert!(buffers.is_transmit_buffer_empty());
+    }
+
+    #[test]
+    fn it_keeps_the_bytes_received_while_the_application_is_busy() {
+        let mut buffers: SerialBuffers<4, 2> = SerialBuffers::new();
+        for byte in [1, 2, 3] {
+            buffers.on_byte_received(byte, false);
+        }
+        assert_eq!(buffers.try_receive(), Some(1));
+        assert_eq!(buffers.try_receive(), Some(2));
+        assert_eq!(buffers.try_receive(), Some(3));
+        assert_eq!(buffers.try_receive(), None);
+        assert_eq!(buffers.get_overruns(), Overruns::default());
+        assert!(!buffers.take_overrun());
+    }
+
+    #[test]
+    fn it_counts_the_bytes_lost() {
+        let mut buffers: SerialBuffers<2, 2> = SerialBuffers::new();
+        for byte in [1, 2, 3, 4] {
+            buffers.on_byte_received(byte, false);
+        }
+        buffers.try_receive();
+        buffers.on_byte_received(5, true);
+        assert_eq!(
+            buffers.get_overruns(),
+            Overruns {
+                buffer: 2,
+                hardware: 1
+            }
+        );
+        // the loss is reported once
+        assert!(buffers.take_overrun());
+        assert!(!buffers.take_overrun());
+        // the bytes which have not been lost are still received
+        assert_eq!(buffers.try_receive(), Some(2));
+        assert_eq!(buffers.try_receive(), Some(5));
+    }
 }
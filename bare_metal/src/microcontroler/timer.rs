@@ -2,6 +2,10 @@
 //!
 //! Below code has beeing derived from code here:
 //! https://github.com/Rahix/avr-hal/blob/main/examples/arduino-uno/src/bin/uno-timer.rs
+//!
+//! In the host there is no timer interruption, instead [`now`] reads a virtual clock which is
+//! advanced explicitly by the tests (see [`advance_virtual_clock`]) and by the delays. The
+//! simulator calls [`init_timer`] to use the wall clock instead.
 
 /*
  This is derived from Rahix' comment to
@@ -9,17 +13,24 @@ https://github.com/Rahix/avr-hal/issues/75
 and then modernized to account for API drift since 2020
 */
 
+#[cfg(target_arch = "avr")]
 use avr_device::atmega328p::tc1::tccr1b::CS1_A;
+#[cfg(target_arch = "avr")]
 use avr_device::atmega328p::TC1;
+#[cfg(target_arch = "avr")]
 use core::mem;
+use cross_platform::types::time::Instant;
 //use panic_halt as _;
 
+#[cfg(target_arch = "avr")]
 struct InterruptState {
     clock_counter: ClockCounter, // increments on each tick of the clock
 }
 
+#[cfg(target_arch = "avr")]
 static mut INTERRUPT_STATE: mem::MaybeUninit<InterruptState> = mem::MaybeUninit::uninit();
 
+#[cfg(target_arch = "avr")]
 const fn calc_overflow(clock_hz: u32, target_hz: u32, prescale: u32) -> u32 {
     /*
     https://github.com/Rahix/avr-hal/issues/75
@@ -28,6 +39,7 @@ const fn calc_overflow(clock_hz: u32, target_hz: u32, prescale: u32) -> u32 {
     clock_hz / target_hz / prescale - 1
 }
 
+#[cfg(target_arch = "avr")]
 fn rig_timer(tmr1: &TC1) {
     /*
      https://ww1.microchip.com/downloads/en/DeviceDoc/Atmel-7810-Automotive-Microcontrollers-ATmega328P_Datasheet.pdf
@@ -60,6 +72,7 @@ fn rig_timer(tmr1: &TC1) {
     tmr1.timsk1.write(|w| w.ocie1a().set_bit()); //enable this specific interrupt
 }
 
+#[cfg(target_arch = "avr")]
 pub fn init_timer() {
     fn set_initial_state(initial_state: InterruptState) {
         unsafe {
@@ -95,6 +108,7 @@ pub fn init_timer() {
 }
 
 // This is the timer interruption handler
+#[cfg(target_arch = "avr")]
 #[avr_device::interrupt(atmega328p)]
 fn TIMER1_COMPA() {
     let state = unsafe {
@@ -111,10 +125,12 @@ fn TIMER1_COMPA() {
 
 //
 
+#[cfg(target_arch = "avr")]
 struct ClockCounter {
     count: u64,
 }
 
+#[cfg(target_arch = "avr")]
 impl ClockCounter {
     fn new() -> Self {
         Self { count: 0x00 }
@@ -133,9 +149,56 @@ impl ClockCounter {
 ///
 /// expected one tick every 1 milisec. NOTE: Period may change in future implementations.
 /// this implemantation is to be considered an initial version.
+#[cfg(target_arch = "avr")]
 pub fn now() -> u64 {
     let state = unsafe { &*INTERRUPT_STATE.as_ptr() };
 
-    let value = state.clock_counter.read();
+    // the counter is read byte by byte, so the interrupt must not increment it in the middle
+    let value = avr_device::interrupt::free(|_| state.clock_counter.read());
     value
 }
+
+/// Same as [`now`] but as an [`Instant`], which is safe to compute durations across the wrap of the
+/// 32 bits counter.
+pub fn now_instant() -> Instant {
+    // the truncation is the wrap of the counter
+    Instant::from_millis(now() as u32)
+}
+
+// Virtual clock (host)
+
+#[cfg(not(target_arch = "avr"))]
+std::thread_local! {
+    /// Each test runs in its own thread, so each one has its own clock starting from zero
+    static VIRTUAL_CLOCK: core::cell::Cell<u64> = core::cell::Cell::new(0);
+    /// Instant of the [`init_timer`] call, if any. Since then the clock follows the wall clock.
+    static POWER_ON: core::cell::Cell<Option<std::time::Instant>> = core::cell::Cell::new(None);
+}
+
+/// Makes the clock follow the wall clock, as the simulator needs. The tests do not call it, so
+/// their clock only moves when it is advanced.
+#[cfg(not(target_arch = "avr"))]
+pub fn init_timer() {
+    POWER_ON.with(|power_on| power_on.set(Some(std::time::Instant::now())));
+}
+
+/// True if the clock follows the wall clock, see [`init_timer`]
+#[cfg(not(target_arch = "avr"))]
+pub fn is_real_time() -> bool {
+    POWER_ON.with(|power_on| power_on.get().is_some())
+}
+
+/// Returns the time of the virtual clock in milisecs
+#[cfg(not(target_arch = "avr"))]
+pub fn now() -> u64 {
+    match POWER_ON.with(|power_on| power_on.get()) {
+        Some(power_on) => power_on.elapsed().as_millis() as u64,
+        None => VIRTUAL_CLOCK.with(|clock| clock.get()),
+    }
+}
+
+/// Moves the virtual clock forward, as if the given milisecs had passed
+#[cfg(not(target_arch = "avr"))]
+pub fn advance_virtual_clock(milisecs: u64) {
+    VIRTUAL_CLOCK.with(|clock| clock.set(clock.get() + milisecs));
+}
@@ -0,0 +1,317 @@
+//! Cooperative scheduler of the main loop
+//!
+//! The tasks are identified by a value given by the application (ie: an enum), they are not
+//! closures, so the tasks may share the state of the application without borrowing problems. The
+//! application runs the task chosen by the scheduler:
+//!
+//! ```
+//! #[derive(Copy, Clone, PartialEq)]
+//! enum Task {
+//!     Keyboard,
+//!     Render,
+//! }
+//!
+//! let mut scheduler: Scheduler<Task, 2> = Scheduler::new();
+//! scheduler.add_periodic(Task::Keyboard, Priority::High, Duration::from_millis(1));
+//! scheduler.add_periodic(Task::Render, Priority::Low, Duration::from_millis(33));
+//! loop {
+//!     let has_run = scheduler.run_next(|task| match task {
+//!         Task::Keyboard => scan_keyboard(),
+//!         Task::Render => render(),
+//!     });
+//!     if !has_run {
+//!         delay_ms(scheduler.idle_time().as_millis() as u64);
+//!     }
+//! }
+//! ```
+//!
+//! The tasks are never interrupted by other tasks, each task must do a small piece of work and
+//! return. The time spent in each task is accounted (see [`TaskStats`]) to find the tasks which
+//! increase the latency of the others.
+//!
+//! The times are [`Instant`]s of the clock of the microcontroler, so the scheduler keeps working
+//! when the clock wraps.
+
+use cross_platform::types::time::{Duration, Instant};
+use heapless::Vec;
+
+use crate::{fatal_error, microcontroler::timer::now_instant};
+
+/// When many tasks are due, the one with the highest priority runs first
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+/// Execution time of a task
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub struct TaskStats {
+    /// Times the task has run
+    pub runs: u32,
+    /// Sum of the execution times, it saturates at [`Duration::MAX`]
+    pub total_time: Duration,
+    /// Longest execution time
+    pub max_time: Duration,
+    /// Longest time the task has waited since it was due
+    pub max_delay: Duration,
+}
+
+struct TaskEntry<T> {
+    id: T,
+    priority: Priority,
+    /// `None` if the task runs once
+    period: Option<Duration>,
+    /// Time in which the task is due
+    next_run: Instant,
+    stats: TaskStats,
+}
+
+/// Runs up to `N` tasks identified by `T`
+pub struct Scheduler<T: Copy + PartialEq, const N: usize> {
+    tasks: Vec<TaskEntry<T>, N>,
+}
+
+impl<T: Copy + PartialEq, const N: usize> Scheduler<T, N> {
+    pub fn new() -> Self {
+        Self { tasks: Vec::new() }
+    }
+
+    fn position(&self, id: T) -> Option<usize> {
+        self.tasks.iter().position(|task| task.id == id)
+    }
+
+    /// Adds the task, or changes it if it has been added before. The task is due `delay` after now.
+    fn add(&mut self, id: T, priority: Priority, period: Option<Duration>, delay: Duration) {
+        let next_run = now_instant() + delay;
+        match self.position(id) {
+            Some(index) => {
+                let task = &mut self.tasks[index];
+                task.priority = priority;
+                task.period = period;
+                task.next_run = next_run;
+            }
+            None => {
+                let task = TaskEntry {
+                    id,
+                    priority,
+                    period,
+                    next_run,
+                    stats: TaskStats::default(),
+                };
+                if self.tasks.push(task).is_err() {
+                    // the capacity of the scheduler is smaller than the number of tasks
+                    fatal_error!(112)
+                }
+            }
+        }
+    }
+
+    /// Runs the task every `period`, starting now
+    pub fn add_periodic(&mut self, id: T, priority: Priority, period: Duration) {
+        self.add(id, priority, Some(period), Duration::ZERO)
+    }
+
+    /// Runs the task once, after the `delay`. If the task is already scheduled it is postponed,
+    /// ie: to save something after the user stops to change it.
+    pub fn schedule_once(&mut self, id: T, priority: Priority, delay: Duration) {
+        self.add(id, priority, None, delay)
+    }
+
+    /// Removes the task, it does not run anymore
+    pub fn cancel(&mut self, id: T) {
+        if let Some(index) = self.position(id) {
+            self.tasks.swap_remove(index);
+        }
+    }
+
+    pub fn is_scheduled(&self, id: T) -> bool {
+        self.position(id).is_some()
+    }
+
+    /// Time until the next task is due, zero if some task is due now. The main loop may sleep
+    /// this time.
+    pub fn idle_time(&self) -> Duration {
+        let now = now_instant();
+        self.tasks
+            .iter()
+            .map(|task| match now.has_reached(task.next_run) {
+                true => Duration::ZERO,
+                false => task.next_run.duration_since(now),
+            })
+            .min()
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Execution time of the task, `None` if it is not scheduled
+    pub fn get_stats(&self, id: T) -> Option<TaskStats> {
+        self.position(id).map(|index| self.tasks[index].stats)
+    }
+
+    /// Runs the due task with the highest priority (the one due for longer if they have the same
+    /// priority), returns `false` if no task is due.
+    ///
+    /// A periodic task which is late is not run many times to catch up, its next run is one period
+    /// after this one.
+    pub fn run_next(&mut self, run: impl FnOnce(T)) -> bool {
+        let start = now_instant();
+        let Some(index) = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| start.has_reached(task.next_run))
+            .min_by(|(_, a), (_, b)| {
+                // the first task added wins the tie
+                let (a_delay, b_delay) = (start - a.next_run, start - b.next_run);
+                b.priority.cmp(&a.priority).then(b_delay.cmp(&a_delay))
+            })
+            .map(|(index, _)| index)
+        else {
+            return false;
+        };
+
+        let task = &mut self.tasks[index];
+        let delay = start.duration_since(task.next_run);
+        run(task.id);
+        let elapsed = now_instant().duration_since(start);
+
+        let stats = &mut task.stats;
+        stats.runs = stats.runs.wrapping_add(1);
+        stats.total_time = stats.total_time + elapsed;
+        stats.max_time = stats.max_time.max(elapsed);
+        stats.max_delay = stats.max_delay.max(delay);
+        match task.period {
+            Some(period) => {
+                let next_run = task.next_run + period;
+                task.next_run = match start.is_before(next_run) {
+                    true => next_run,
+                    false => start + Duration::from_millis(1),
+                };
+            }
+            None => {
+                self.tasks.swap_remove(index);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::microcontroler::timer::{advance_virtual_clock, now};
+
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Task {
+        Fast,
+        Slow,
+        Once,
+    }
+
+    /// Runs the scheduler during the given time and returns the time in which each task ran,
+    /// counted from the start. Each task takes the given time to run.
+    fn run_during(
+        scheduler: &mut Scheduler<Task, 3>,
+        milisecs: u64,
+        execution_time: impl Fn(Task) -> u64,
+    ) -> std::vec::Vec<(u64, Task)> {
+        let start = now();
+        let mut runs = std::vec::Vec::new();
+        while now() - start < milisecs {
+            let ran = scheduler.run_next(|task| {
+                runs.push((now() - start, task));
+                advance_virtual_clock(execution_time(task));
+            });
+            if !ran {
+                advance_virtual_clock(1);
+            }
+        }
+        runs
+    }
+
+    #[test]
+    fn it_runs_the_periodic_tasks() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_periodic(Task::Fast, Priority::Normal, Duration::from_millis(10));
+        scheduler.add_periodic(Task::Slow, Priority::Normal, Duration::from_millis(25));
+        let runs = run_during(&mut scheduler, 51, |_| 0);
+        assert_eq!(
+            runs,
+            [
+                (0, Task::Fast),
+                (0, Task::Slow),
+                (10, Task::Fast),
+                (20, Task::Fast),
+                (25, Task::Slow),
+                (30, Task::Fast),
+                (40, Task::Fast),
+                (50, Task::Fast),
+                (50, Task::Slow),
+            ]
+        );
+        assert_eq!(scheduler.get_stats(Task::Fast).unwrap().runs, 6);
+    }
+
+    #[test]
+    fn it_runs_the_highest_priority_first() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_periodic(Task::Slow, Priority::Low, Duration::from_millis(10));
+        scheduler.add_periodic(Task::Fast, Priority::High, Duration::from_millis(10));
+        let runs = run_during(&mut scheduler, 1, |_| 0);
+        assert_eq!(runs, [(0, Task::Fast), (0, Task::Slow)]);
+    }
+
+    #[test]
+    fn it_runs_a_one_shot_task_after_the_delay() {
+        let mut scheduler = Scheduler::new();
+        scheduler.schedule_once(Task::Once, Priority::Normal, Duration::from_millis(20));
+        run_during(&mut scheduler, 10, |_| 0);
+        // rescheduling postpones the task
+        scheduler.schedule_once(Task::Once, Priority::Normal, Duration::from_millis(20));
+        let runs = run_during(&mut scheduler, 40, |_| 0);
+        assert_eq!(runs, [(20, Task::Once)]);
+        assert!(!scheduler.is_scheduled(Task::Once));
+    }
+
+    #[test]
+    fn it_accounts_the_execution_time() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_periodic(Task::Fast, Priority::High, Duration::from_millis(10));
+        scheduler.add_periodic(Task::Slow, Priority::Low, Duration::from_millis(100));
+        let runs = run_during(&mut scheduler, 100, |task| match task {
+            Task::Slow => 15,
+            _ => 1,
+        });
+        // the slow task delays the fast one, which does not run twice to catch up
+        assert_eq!(
+            &runs[..3],
+            [(0, Task::Fast), (1, Task::Slow), (16, Task::Fast)]
+        );
+        let slow = scheduler.get_stats(Task::Slow).unwrap();
+        assert_eq!((slow.runs, slow.max_time), (1, Duration::from_millis(15)));
+        assert_eq!(slow.total_time, Duration::from_millis(15));
+        let fast = scheduler.get_stats(Task::Fast).unwrap();
+        assert_eq!(fast.max_delay, Duration::from_millis(6));
+        assert_eq!(fast.total_time, Duration::from_millis(fast.runs));
+    }
+
+    #[test]
+    fn it_cancels_a_task() {
+        let mut scheduler = Scheduler::new();
+        scheduler.add_periodic(Task::Fast, Priority::Normal, Duration::from_millis(10));
+        scheduler.cancel(Task::Fast);
+        assert!(run_during(&mut scheduler, 20, |_| 0).is_empty());
+    }
+
+    #[test]
+    fn it_gives_the_time_until_the_next_task() {
+        let mut scheduler = Scheduler::new();
+        assert_eq!(scheduler.idle_time(), Duration::from_millis(0));
+        scheduler.add_periodic(Task::Fast, Priority::Normal, Duration::from_millis(10));
+        scheduler.schedule_once(Task::Once, Priority::Normal, Duration::from_millis(4));
+        assert_eq!(scheduler.idle_time(), Duration::from_millis(0));
+        run_during(&mut scheduler, 1, |_| 0);
+        assert_eq!(scheduler.idle_time(), Duration::from_millis(3));
+    }
+}
@@ -0,0 +1,129 @@
+//! Simulator of the board in the terminal of the PC (Linux)
+//!
+//! It runs the real application ([`crate::app::run`]) replacing the peripherals of the board: the
+//! lcd is drawn in the terminal, the keys of the PC are mapped to the keys of the keypad, the leds
+//! and the buzzer are shown below the lcd, the eeprom is persisted in a file and the cmpp boards
+//! are emulated (or reached through a real serial port).
+//!
+//! # Usage
+//!
+//! ```text
+//! cargo run --package bare_metal -- [--eeprom <file>] [--tty <serial device>]
+//! ```
+//!
+//! * `--eeprom`: file where the eeprom is persisted (default: `eeprom.bin`)
+//! * `--tty`: serial device where the cmpp boards are connected, ie: `/dev/ttyUSB0`. If it is not
+//!   given the cmpp boards are emulated.
+//!
+//! To quit press `Ctrl+C`.
+
+pub mod cmpp;
+pub mod display;
+pub mod keyboard;
+pub mod peripherals;
+pub mod terminal;
+pub mod tty;
+
+use std::path::PathBuf;
+
+use crate::{
+    app,
+    microcontroler::{eeprom, serial},
+};
+
+use self::{cmpp::EmulatedCmpp, peripherals::PeripheralsSimulator, tty::TtySerialPort};
+
+const USAGE: &str = "usage: bare_metal [--eeprom <file>] [--tty <serial device>]";
+
+/// Command line options
+pub struct Options {
+    /// File where the eeprom is persisted
+    pub eeprom: PathBuf,
+    /// Serial device where the cmpp boards are connected, `None` if they are emulated
+    pub tty: Option<PathBuf>,
+}
+
+impl Options {
+    const DEFAULT_EEPROM: &'static str = "eeprom.bin";
+
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self {
+            eeprom: PathBuf::from(Self::DEFAULT_EEPROM),
+            tty: None,
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing the value of {}", arg));
+            match arg.as_str() {
+                "--eeprom" => options.eeprom = value()?.into(),
+                "--tty" => options.tty = Some(value()?.into()),
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// Description of the cmpp connection, to be shown to the user
+    pub fn describe_cmpp(&self) -> String {
+        match &self.tty {
+            Some(path) => format!("cmpp em {}", path.display()),
+            None => "cmpp emulado".into(),
+        }
+    }
+}
+
+fn exit_with_error(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1)
+}
+
+/// Entry point of the simulator
+pub fn run() -> ! {
+    let options = Options::parse(std::env::args().skip(1))
+        .unwrap_or_else(|error| exit_with_error(format!("{}\n{}", error, USAGE)));
+
+    eeprom::persist_in_file(&options.eeprom).unwrap_or_else(|error| {
+        exit_with_error(format!("{}: {}", options.eeprom.display(), error))
+    });
+
+    match &options.tty {
+        Some(path) => serial::connect(
+            TtySerialPort::open(path)
+                .unwrap_or_else(|error| exit_with_error(format!("{}: {}", path.display(), error))),
+        ),
+        None => serial::connect(EmulatedCmpp::new()),
+    }
+
+    terminal::enter_raw_mode()
+        .unwrap_or_else(|error| exit_with_error(format!("terminal: {}", error)));
+
+    app::run(PeripheralsSimulator::new(&options))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn it_uses_the_default_options() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.eeprom, PathBuf::from("eeprom.bin"));
+        assert_eq!(options.tty, None);
+    }
+
+    #[test]
+    fn it_parses_the_options() {
+        let options = parse(&["--tty", "/dev/ttyUSB0", "--eeprom", "/tmp/board.bin"]).unwrap();
+        assert_eq!(options.eeprom, PathBuf::from("/tmp/board.bin"));
+        assert_eq!(options.tty, Some(PathBuf::from("/dev/ttyUSB0")));
+    }
+
+    #[test]
+    fn it_rejects_wrong_options() {
+        assert!(parse(&["--baudrate", "9600"]).is_err());
+        assert!(parse(&["--tty"]).is_err());
+    }
+}
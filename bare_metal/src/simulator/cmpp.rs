@@ -0,0 +1,158 @@
+//! Emulation of the cmpp boards connected to the serial, so the simulator does not need them
+//!
+//! Each channel is an independent board whose memory is a set of words: the master reads and
+//! writes them (or their bits) and the emulated board answers with `ACK`, as a real board does.
+//! The motion is not emulated, the axis is always referenced and stopped.
+
+use std::collections::{HashMap, VecDeque};
+
+use cross_platform::protocol::datalink::{
+    datalink::{word16::Word16, Direction, DirectionAndChannel},
+    decoder::Decoder,
+    frame::{Frame, Payload},
+    prelude::StartByte,
+};
+
+use crate::microcontroler::serial::HostSerialPort;
+
+pub struct EmulatedCmpp {
+    decoder: Decoder,
+    /// Words written by the master, indexed by (channel, word address). The others are zero.
+    memory: HashMap<(u8, u8), u16>,
+    /// Bytes of the answers not read by the master yet
+    answers: VecDeque<u8>,
+}
+
+impl EmulatedCmpp {
+    /// Status of the axis: referenced and in the last position
+    const STATUS: u8 = 0b0000_0011;
+
+    pub fn new() -> Self {
+        Self {
+            decoder: Decoder::new(),
+            memory: HashMap::new(),
+            answers: VecDeque::new(),
+        }
+    }
+
+    fn answer(&mut self, payload: Payload) {
+        let direction_and_channel = DirectionAndChannel::from_raw(payload.direction_and_channel);
+        let word_address = payload.command;
+        let channel = direction_and_channel.get_channel().to_u8();
+        let value = payload.get_word().to_u16();
+        let word = self.memory.entry((channel, word_address)).or_insert(0);
+        let (byte_low, byte_high) = match direction_and_channel.get_direction() {
+            Direction::Get => Word16::from_u16(*word).split_bytes(),
+            Direction::Set => {
+                *word = value;
+                (Self::STATUS, 0)
+            }
+            Direction::SetBitmask => {
+                *word |= value;
+                (Self::STATUS, 0)
+            }
+            Direction::ResetBitmask => {
+                *word &= !value;
+                (Self::STATUS, 0)
+            }
+        };
+        let answer = Frame::new(
+            StartByte::ACK,
+            Payload {
+                byte_low,
+                byte_high,
+                ..payload
+            },
+        );
+        self.answers.extend(answer.encode());
+    }
+}
+
+impl HostSerialPort for EmulatedCmpp {
+    fn set_baudrate(&mut self, _baud_rate: u32) {
+        // the emulated board talks in any speed
+    }
+
+    fn try_transmit(&mut self, byte: u8) -> Result<(), ()> {
+        // the frames which are not well formed are ignored, the master will timeout
+        if let Ok(Some(frame)) = self.decoder.parse_next(byte) {
+            if frame.start_byte == StartByte::STX {
+                self.answer(frame.payload);
+            }
+        }
+        Ok(())
+    }
+
+    fn try_receive(&mut self) -> Option<u8> {
+        self.answers.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use cross_platform::protocol::{datalink::datalink::Datalink, transport::channel::Channel};
+
+    use super::*;
+    use crate::microcontroler::{serial, timer::now_instant};
+
+    fn make_datalink(channel: u8) -> Datalink {
+        fn try_rx() -> Result<Option<u8>, ()> {
+            Ok(serial::try_receive())
+        }
+        fn try_tx(byte: u8) -> Option<()> {
+            serial::try_transmit(byte).ok()
+        }
+        Datalink {
+            channel: Channel::from_u8(channel).unwrap(),
+            now: now_instant,
+            timeout_ms: 1000,
+            try_rx,
+            try_tx,
+            debug_reception: None,
+            half_duplex: None,
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
+        }
+    }
+
+    fn get_word(datalink: &Datalink, word_address: u8) -> u16 {
+        let Ok(Ok(pacote)) = datalink.get_word16(word_address) else {
+            panic!("the emulated cmpp has not answered");
+        };
+        pacote.data.to_u16()
+    }
+
+    #[test]
+    fn it_answers_the_words_written() {
+        serial::connect(EmulatedCmpp::new());
+        let datalink = make_datalink(0);
+        assert_eq!(get_word(&datalink, 0x50), 0);
+        let Ok(Ok(pacote)) = datalink.set_word16(0x50, 0x1B1B) else {
+            panic!("the emulated cmpp has not answered");
+        };
+        assert!(pacote.status.is_referenced());
+        assert_eq!(get_word(&datalink, 0x50), 0x1B1B);
+    }
+
+    #[test]
+    fn it_sets_and_resets_the_bits() {
+        serial::connect(EmulatedCmpp::new());
+        let datalink = make_datalink(0);
+        assert!(datalink.set_bit_mask(0x32, 0b1010).is_ok());
+        assert!(datalink.reset_bit_mask(0x32, 0b0010).is_ok());
+        assert_eq!(get_word(&datalink, 0x32), 0b1000);
+    }
+
+    #[test]
+    fn it_keeps_the_channels_apart() {
+        serial::connect(EmulatedCmpp::new());
+        let axis_x = make_datalink(0);
+        let axis_y = make_datalink(1);
+        assert!(axis_x.set_word16(0x50, 100).is_ok());
+        assert!(axis_y.set_word16(0x50, 200).is_ok());
+        assert_eq!(get_word(&axis_x, 0x50), 100);
+        assert_eq!(get_word(&axis_y, 0x50), 200);
+    }
+}
@@ -0,0 +1,202 @@
+//! Front panel of the board drawn in the terminal: the lcd, the leds and the buzzer
+//!
+//! ```text
+//! Julia PCI - simulador | cmpp emulado | eeprom: eeprom.bin
+//! ┌────────────────────────────────────────┐
+//! │ Selecione modo de programacao desejado │
+//! │     MANUAL    EXECUCAO    PROGRAMA     │
+//! └────────────────────────────────────────┘
+//! ○ ERRO   ○ POS.ALC   ○ MANUAL   ○ EXECUCAO   ○ PROGRAMA   ○ BUZZER (3 bips)
+//! ```
+
+use core::cell::Cell;
+
+use crate::{
+    board::{
+        front_panel::FrontPanel,
+        host::{front_panel::FrontPanelRecorder, lcd::LcdFramebuffer},
+        lcd::{interface::Lcd, NUMBER_OF_COLS, NUMBER_OF_LINES},
+    },
+    geometry::point::Point,
+};
+
+use super::{keyboard, terminal};
+
+// Layout, in rows and columns of the terminal
+
+const TITLE_ROW: u16 = 1;
+/// Row of the top border of the lcd
+const LCD_ROW: u16 = 2;
+const LEDS_ROW: u16 = LCD_ROW + NUMBER_OF_LINES as u16 + 2;
+const HELP_ROW: u16 = LEDS_ROW + 2;
+/// Row below the drawing, where the terminal is left when the simulator ends
+pub const BOTTOM_ROW: u16 = HELP_ROW + keyboard::HELP.len() as u16 + 1;
+
+/// Draws the parts of the panel which do not change: the title, the border of the lcd and the
+/// help of the keys
+pub fn draw_panel(title: &str) {
+    terminal::clear_screen();
+    terminal::print_line_at(TITLE_ROW, 1, title);
+    let border = "─".repeat(NUMBER_OF_COLS as usize);
+    terminal::print_at(LCD_ROW, 1, &format!("┌{}┐", border));
+    for line in 0..NUMBER_OF_LINES as u16 {
+        let row = LCD_ROW + 1 + line;
+        terminal::print_at(row, 1, "│");
+        terminal::print_at(row, NUMBER_OF_COLS as u16 + 2, "│");
+    }
+    terminal::print_at(
+        LCD_ROW + NUMBER_OF_LINES as u16 + 1,
+        1,
+        &format!("└{}┘", border),
+    );
+    for (index, help) in keyboard::HELP.iter().enumerate() {
+        terminal::print_line_at(HELP_ROW + index as u16, 1, help);
+    }
+}
+
+/// Lcd drawn inside the border of [`draw_panel`]. As the hardware lcd it is written char by char,
+/// so the screen buffer optimizations are seen as in the board.
+pub struct TerminalLcd {
+    framebuffer: LcdFramebuffer,
+}
+
+impl TerminalLcd {
+    pub fn new() -> Self {
+        Self {
+            framebuffer: LcdFramebuffer::new(),
+        }
+    }
+
+    fn draw_char(&self, point: Point, byte: u8) {
+        let row = LCD_ROW + 1 + point.y as u16;
+        let col = 2 + point.x as u16;
+        terminal::print_at(
+            row,
+            col,
+            LcdFramebuffer::to_char(byte).encode_utf8(&mut [0; 4]),
+        );
+    }
+}
+
+impl Lcd for TerminalLcd {
+    fn clear(&self) {
+        self.framebuffer.clear();
+        for (row, line) in self.framebuffer.get_lines().iter().enumerate() {
+            terminal::print_at(LCD_ROW + 1 + row as u16, 2, line);
+        }
+    }
+
+    fn print_u8(&self, byte: u8) {
+        let cursor = self.framebuffer.get_cursor();
+        self.framebuffer.print_u8(byte);
+        // as the hardware, the chars beyond the right border are not shown
+        if cursor.x < NUMBER_OF_COLS {
+            self.draw_char(cursor, byte);
+        }
+    }
+
+    fn set_cursor(&self, point: Point) {
+        self.framebuffer.set_cursor(point)
+    }
+
+    fn create_char(&self, slot: u8, bitmap: &[u8; 8]) {
+        self.framebuffer.create_char(slot, bitmap)
+    }
+}
+
+/// Leds and buzzer of the panel, drawn below the lcd
+pub struct TerminalPanel {
+    state: Cell<FrontPanelRecorder>,
+    /// Times the buzzer was turned on, because the beeps are too short to be seen
+    beeps: Cell<u32>,
+}
+
+impl TerminalPanel {
+    pub fn new() -> Self {
+        let panel = Self {
+            state: Cell::new(FrontPanelRecorder::new()),
+            beeps: Cell::new(0),
+        };
+        panel.draw();
+        panel
+    }
+
+    fn update(&self, change: impl FnOnce(&mut FrontPanelRecorder)) {
+        let mut state = self.state.get();
+        change(&mut state);
+        self.state.set(state);
+        self.draw();
+    }
+
+    fn set_buzzer(&self, on: bool) {
+        if on && !self.state.get().buzzer {
+            self.beeps.set(self.beeps.get() + 1);
+        }
+        self.update(|state| state.buzzer = on);
+    }
+
+    fn draw(&self) {
+        // the led turned on is green, except the led of error which is red
+        fn led(name: &str, on: bool, color: &str) -> String {
+            if on {
+                format!("\x1b[{}m●\x1b[0m {}", color, name)
+            } else {
+                format!("○ {}", name)
+            }
+        }
+        const GREEN: &str = "32";
+        const RED: &str = "31";
+        const YELLOW: &str = "33";
+        let state = self.state.get();
+        let leds = [
+            led("ERRO", state.led_erro, RED),
+            led("POS.ALC", state.led_pos_alc, GREEN),
+            led("MANUAL", state.led_manual, GREEN),
+            led("EXECUCAO", state.led_execucao, GREEN),
+            led("PROGRAMA", state.led_programa, GREEN),
+            led(
+                &format!("BUZZER ({} bips)", self.beeps.get()),
+                state.buzzer,
+                YELLOW,
+            ),
+        ];
+        terminal::print_line_at(LEDS_ROW, 1, &leds.join("   "));
+    }
+}
+
+/// Front panel which controls the leds and the buzzer of the [`TerminalPanel`]
+pub struct TerminalFrontPanel<'a> {
+    panel: &'a TerminalPanel,
+}
+
+impl<'a> TerminalFrontPanel<'a> {
+    pub fn new(panel: &'a TerminalPanel) -> Self {
+        Self { panel }
+    }
+}
+
+impl<'a> FrontPanel for TerminalFrontPanel<'a> {
+    fn LED_ERRO(&mut self, on: bool) {
+        self.panel.update(|state| state.led_erro = on)
+    }
+
+    fn LED_POS_ALC(&mut self, on: bool) {
+        self.panel.update(|state| state.led_pos_alc = on)
+    }
+
+    fn BUZZER(&mut self, on: bool) {
+        self.panel.set_buzzer(on)
+    }
+
+    fn LED_MANUAL(&mut self, on: bool) {
+        self.panel.update(|state| state.led_manual = on)
+    }
+
+    fn LED_EXECUCAO(&mut self, on: bool) {
+        self.panel.update(|state| state.led_execucao = on)
+    }
+
+    fn LED_PROGRAMA(&mut self, on: bool) {
+        self.panel.update(|state| state.led_programa = on)
+    }
+}
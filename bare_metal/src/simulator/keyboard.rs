@@ -0,0 +1,227 @@
+//! Keyboard of the PC mapped to the keypad of the board
+
+use std::{
+    io::{self, Read},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use crate::board::{
+    keyboard::{KeyEvent, Keyboard},
+    keypad::KeyCode,
+};
+
+use super::terminal;
+
+/// Help of the keys, shown below the panel
+pub const HELP: [&str; 5] = [
+    "F1..F4: F1..F4   F5/g: START   F6/s: STOP   F7/m: MANUAL   F8/e: EXECUCAO   F9/p: PROGRAMA",
+    "Setas: direcionais   Tab e Shift+Tab: setas brancas   Insert: INS   Delete: DEL",
+    "0..9 . e -: teclas numericas   Enter: ENTER   Esc: ESC",
+    "S: segurar STOP   H: segurar ESC   Alt+tecla: CTRL+tecla",
+    "Ctrl+C: sair do simulador",
+];
+
+const ESC: u8 = 0x1B;
+const CTRL_C: u8 = 0x03;
+
+/// Bytes sent by the terminal for each key. The function keys have two variations because the
+/// terminals do not agree on them.
+const KEYMAP: &[(&[u8], KeyCode)] = &[
+    // function keys
+    (b"\x1bOP", KeyCode::KEY_F1),
+    (b"\x1bOQ", KeyCode::KEY_F2),
+    (b"\x1bOR", KeyCode::KEY_F3),
+    (b"\x1bOS", KeyCode::KEY_F4),
+    (b"\x1b[11~", KeyCode::KEY_F1),
+    (b"\x1b[12~", KeyCode::KEY_F2),
+    (b"\x1b[13~", KeyCode::KEY_F3),
+    (b"\x1b[14~", KeyCode::KEY_F4),
+    // execution and menu keys
+    (b"\x1b[15~", KeyCode::KEY_START),
+    (b"\x1b[17~", KeyCode::KEY_STOP),
+    (b"\x1b[18~", KeyCode::KEY_MANUAL),
+    (b"\x1b[19~", KeyCode::KEY_EXECUCAO),
+    (b"\x1b[20~", KeyCode::KEY_PROGRAMA),
+    (b"g", KeyCode::KEY_START),
+    (b"s", KeyCode::KEY_STOP),
+    (b"m", KeyCode::KEY_MANUAL),
+    (b"e", KeyCode::KEY_EXECUCAO),
+    (b"p", KeyCode::KEY_PROGRAMA),
+    // direction keys
+    (b"\x1b[A", KeyCode::KEY_DIRECIONAL_PARA_CIMA),
+    (b"\x1b[B", KeyCode::KEY_DIRECIONAL_PARA_BAIXO),
+    (b"\x1b[C", KeyCode::KEY_DIRECIONAL_PARA_DIREITA),
+    (b"\x1b[D", KeyCode::KEY_DIRECIONAL_PARA_ESQUERDA),
+    // edition and registry keys
+    (b"\t", KeyCode::KEY_SETA_BRANCA_DIREITA),
+    (b"\x1b[Z", KeyCode::KEY_SETA_BRANCA_ESQUERDA),
+    (b"\x1b[2~", KeyCode::KEY_INS),
+    (b"\x1b[3~", KeyCode::KEY_DEL),
+    // flow keys
+    (b"\r", KeyCode::KEY_ENTER),
+    (b"\n", KeyCode::KEY_ENTER),
+    // printable keys
+    (b"0", KeyCode::KEY_0),
+    (b"1", KeyCode::KEY_1),
+    (b"2", KeyCode::KEY_2),
+    (b"3", KeyCode::KEY_3),
+    (b"4", KeyCode::KEY_4),
+    (b"5", KeyCode::KEY_5),
+    (b"6", KeyCode::KEY_6),
+    (b"7", KeyCode::KEY_7),
+    (b"8", KeyCode::KEY_8),
+    (b"9", KeyCode::KEY_9),
+    (b".", KeyCode::KEY_PONTO),
+    (b"-", KeyCode::KEY_MAIS_OU_MENOS),
+];
+
+/// The terminal cannot tell how long a key is held, so the long presses have their own keys
+const LONG_PRESSES: &[(&[u8], KeyCode)] = &[(b"S", KeyCode::KEY_STOP), (b"H", KeyCode::KEY_ESC)];
+
+/// Size of the sequence and the key at the start of the bytes, if any
+fn find_key(keymap: &[(&[u8], KeyCode)], bytes: &[u8]) -> Option<(usize, KeyCode)> {
+    keymap
+        .iter()
+        .find(|(sequence, _)| bytes.starts_with(sequence))
+        .map(|(sequence, key)| (sequence.len(), *key))
+}
+
+/// Key events typed in the bytes read from the terminal
+///
+/// The terminals send each escape sequence at once, so an ESC byte which does not start a sequence
+/// is the ESC key, or the `Alt` if it is followed by other key (the chords with `KEY_CTRL` are typed
+/// with `Alt`). The unknown sequences and the keys without meaning in the keypad are discarded.
+pub fn decode(bytes: &[u8]) -> Vec<KeyEvent> {
+    let mut events = Vec::new();
+    let mut rest = bytes;
+    while let Some(&first) = rest.first() {
+        if let Some((size, key)) = find_key(KEYMAP, rest) {
+            events.push(KeyEvent::Pressed(key));
+            rest = &rest[size..];
+        } else if let Some((size, key)) = find_key(LONG_PRESSES, rest) {
+            events.push(KeyEvent::LongPress(key));
+            rest = &rest[size..];
+        } else if first == ESC {
+            match rest.get(1) {
+                Some(b'[') | Some(b'O') => break,
+                Some(&second) if second != ESC => match find_key(KEYMAP, &rest[1..]) {
+                    Some((size, key)) => {
+                        events.push(KeyEvent::Ctrl(key));
+                        rest = &rest[1 + size..];
+                    }
+                    None => rest = &rest[2..],
+                },
+                _ => {
+                    events.push(KeyEvent::Pressed(KeyCode::KEY_ESC));
+                    rest = &rest[1..];
+                }
+            }
+        } else {
+            rest = &rest[1..];
+        }
+    }
+    events
+}
+
+/// Reads the terminal in background and sends the keys typed. Ends the simulator on `Ctrl+C`.
+pub fn spawn_terminal_reader() -> Receiver<KeyEvent> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut buffer = [0; 32];
+        loop {
+            let bytes = match stdin.read(&mut buffer) {
+                Ok(0) | Err(_) => terminal::quit(),
+                Ok(size) => &buffer[..size],
+            };
+            if bytes.contains(&CTRL_C) {
+                terminal::quit();
+            }
+            for event in decode(bytes) {
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    receiver
+}
+
+/// Keyboard which gives the keys typed in the terminal
+pub struct TerminalKeyboard<'a> {
+    events: &'a Receiver<KeyEvent>,
+}
+
+impl<'a> TerminalKeyboard<'a> {
+    pub fn new(events: &'a Receiver<KeyEvent>) -> Self {
+        Self { events }
+    }
+}
+
+impl<'a> Keyboard for TerminalKeyboard<'a> {
+    fn get_key(&mut self) -> Option<KeyEvent> {
+        self.events.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(keys: &[KeyCode]) -> Vec<KeyEvent> {
+        keys.iter().map(|key| KeyEvent::Pressed(*key)).collect()
+    }
+
+    #[test]
+    fn it_decodes_the_printable_keys() {
+        let expected = pressed(&[
+            KeyCode::KEY_1,
+            KeyCode::KEY_2,
+            KeyCode::KEY_PONTO,
+            KeyCode::KEY_5,
+            KeyCode::KEY_ENTER,
+        ]);
+        assert_eq!(decode(b"12.5\r"), expected);
+    }
+
+    #[test]
+    fn it_decodes_the_escape_sequences() {
+        let expected = pressed(&[
+            KeyCode::KEY_F1,
+            KeyCode::KEY_F1,
+            KeyCode::KEY_PROGRAMA,
+            KeyCode::KEY_DIRECIONAL_PARA_BAIXO,
+            KeyCode::KEY_DEL,
+        ]);
+        assert_eq!(decode(b"\x1bOP\x1b[11~\x1b[20~\x1b[B\x1b[3~"), expected);
+    }
+
+    #[test]
+    fn it_decodes_the_esc_key() {
+        assert_eq!(decode(b"\x1b"), pressed(&[KeyCode::KEY_ESC]));
+        assert_eq!(
+            decode(b"\x1b\x1b"),
+            pressed(&[KeyCode::KEY_ESC, KeyCode::KEY_ESC])
+        );
+    }
+
+    #[test]
+    fn it_decodes_the_long_presses_and_the_chords() {
+        assert_eq!(
+            decode(b"S\x1b1H"),
+            [
+                KeyEvent::LongPress(KeyCode::KEY_STOP),
+                KeyEvent::Ctrl(KeyCode::KEY_1),
+                KeyEvent::LongPress(KeyCode::KEY_ESC),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_discards_the_unknown_keys() {
+        // F12, the letter 'x' and Alt+'x'
+        assert!(decode(b"\x1b[24~").is_empty());
+        assert_eq!(decode(b"x1\x1bx"), pressed(&[KeyCode::KEY_1]));
+    }
+}
@@ -0,0 +1,61 @@
+//! Peripherals of the board replaced by the terminal, see [`crate::board::peripherals`]
+
+use std::sync::mpsc::Receiver;
+
+use crate::{
+    board::{
+        front_panel::FrontPanel,
+        keyboard::{KeyEvent, Keyboard},
+        peripherals::Peripherals,
+    },
+    menu::screen_buffer::ScreenBuffer,
+    microcontroler::timer::init_timer,
+};
+
+use super::{
+    display::{self, TerminalFrontPanel, TerminalLcd, TerminalPanel},
+    keyboard::{self, TerminalKeyboard},
+    Options,
+};
+
+pub struct PeripheralsSimulator {
+    lcd: TerminalLcd,
+    panel: TerminalPanel,
+    /// Keys typed in the terminal
+    key_events: Receiver<KeyEvent>,
+}
+
+impl PeripheralsSimulator {
+    /// Draws the panel in the terminal and starts to read the keys
+    ///
+    /// NOTE: Call this function once during the entire lifetime of the program
+    pub fn new(options: &Options) -> impl Peripherals {
+        // the time passes as in the board
+        init_timer();
+
+        display::draw_panel(&format!(
+            "Julia PCI - simulador | {} | eeprom: {}",
+            options.describe_cmpp(),
+            options.eeprom.display()
+        ));
+        Self {
+            lcd: TerminalLcd::new(),
+            panel: TerminalPanel::new(),
+            key_events: keyboard::spawn_terminal_reader(),
+        }
+    }
+}
+
+impl Peripherals for PeripheralsSimulator {
+    fn get_keyboard(&self) -> impl Keyboard + '_ {
+        TerminalKeyboard::new(&self.key_events)
+    }
+
+    fn get_front_panel(&self) -> impl FrontPanel + '_ {
+        TerminalFrontPanel::new(&self.panel)
+    }
+
+    fn get_screen_buffer(&self) -> ScreenBuffer {
+        ScreenBuffer::new(&self.lcd)
+    }
+}
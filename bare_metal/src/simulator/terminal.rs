@@ -0,0 +1,83 @@
+//! Low-level access to the terminal: raw mode and positioned writes (ANSI escape sequences)
+//!
+//! The terminal is configured with the `stty` command, as the serial devices (see
+//! [`super::tty`]).
+
+use std::{
+    io::{self, Write},
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+/// Settings of the terminal before the raw mode, to restore them when the simulator ends
+static SAVED_SETTINGS: OnceLock<String> = OnceLock::new();
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(io::Error::new(io::ErrorKind::Other, message))
+    }
+}
+
+/// Keys are received as soon as they are typed and they are not echoed. The terminal is restored
+/// when the simulator quits or panics (ie: in a fatal error).
+pub fn enter_raw_mode() -> io::Result<()> {
+    let settings = stty(&["-g"])?;
+    stty(&["raw", "-echo"])?;
+    let _ = SAVED_SETTINGS.set(settings);
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        leave_raw_mode();
+        default_hook(info)
+    }));
+
+    clear_screen();
+    hide_cursor(true);
+    Ok(())
+}
+
+/// Restores the terminal as it was before [`enter_raw_mode`]
+pub fn leave_raw_mode() {
+    if let Some(settings) = SAVED_SETTINGS.get() {
+        let _ = stty(&[settings]);
+        hide_cursor(false);
+        print_at(super::display::BOTTOM_ROW, 1, "\r\n");
+    }
+}
+
+/// Restores the terminal and ends the simulator
+pub fn quit() -> ! {
+    leave_raw_mode();
+    std::process::exit(0)
+}
+
+fn write(text: &str) {
+    let mut stdout = io::stdout().lock();
+    let _ = stdout.write_all(text.as_bytes());
+    let _ = stdout.flush();
+}
+
+pub fn clear_screen() {
+    write("\x1b[2J");
+}
+
+fn hide_cursor(hide: bool) {
+    write(if hide { "\x1b[?25l" } else { "\x1b[?25h" });
+}
+
+/// Prints the text starting at the given position of the terminal (the first row and column are 1)
+pub fn print_at(row: u16, col: u16, text: &str) {
+    write(&format!("\x1b[{};{}H{}", row, col, text));
+}
+
+/// Same as [`print_at`] but clears the rest of the line
+pub fn print_line_at(row: u16, col: u16, text: &str) {
+    write(&format!("\x1b[{};{}H{}\x1b[K", row, col, text));
+}
@@ -0,0 +1,78 @@
+//! Serial device of the PC (ie: an usb-serial adapter), to talk to the real cmpp boards
+//!
+//! The device is configured with the `stty` command (8 bits, no parity, one stop bit, raw mode).
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use crate::microcontroler::serial::HostSerialPort;
+
+pub struct TtySerialPort {
+    path: PathBuf,
+    device: File,
+    /// Bytes received by the reading thread
+    received: Receiver<u8>,
+}
+
+impl TtySerialPort {
+    /// Speed used until the application sets it (see [`crate::microcontroler::serial::init`])
+    const DEFAULT_BAUD_RATE: u32 = 9600;
+
+    pub fn open(path: &Path) -> io::Result<Self> {
+        configure(path, Self::DEFAULT_BAUD_RATE)?;
+        let device = OpenOptions::new().read(true).write(true).open(path)?;
+        let mut reader = device.try_clone()?;
+        let (sender, received) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buffer = [0; 64];
+            while let Ok(size) = reader.read(&mut buffer) {
+                for byte in &buffer[..size] {
+                    if sender.send(*byte).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            path: path.to_path_buf(),
+            device,
+            received,
+        })
+    }
+}
+
+fn configure(path: &Path, baud_rate: u32) -> io::Result<()> {
+    let status = Command::new("stty")
+        .arg("-F")
+        .arg(path)
+        .arg(baud_rate.to_string())
+        .args(["cs8", "-parenb", "-cstopb", "raw", "-echo"])
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        let message = format!("cannot configure the serial device ({})", status);
+        Err(io::Error::new(io::ErrorKind::Other, message))
+    }
+}
+
+impl HostSerialPort for TtySerialPort {
+    fn set_baudrate(&mut self, baud_rate: u32) {
+        // the device keeps the previous speed if it cannot be changed
+        let _ = configure(&self.path, baud_rate);
+    }
+
+    fn try_transmit(&mut self, byte: u8) -> Result<(), ()> {
+        self.device.write_all(&[byte]).map_err(|_| ())
+    }
+
+    fn try_receive(&mut self) -> Option<u8> {
+        self.received.try_recv().ok()
+    }
+}
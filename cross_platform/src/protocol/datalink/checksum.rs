@@ -10,7 +10,7 @@ pub fn calc_checksum(frame: &Frame) -> u8 {
         .payload
         .as_array()
         .iter()
-        .fold(0, |sum, a| sum + a)
+        .fold(0, |sum: u8, a| sum.wrapping_add(*a))
         .wrapping_add(start_byte)
         .wrapping_add(ETX)
         .wrapping_neg()
@@ -41,7 +41,7 @@ mod tests {
             //expects:
             //  - checksum = 0 if i = 0
             //  - higher i, higher checksum; 1 to 1 relationship
-            [0xC1, 0x50, 0x61, 0x02 + 0x87 - i].into()
+            [0xC1, 0x50, 0x61, (0x02 + 0x87 as u8).wrapping_sub(i)].into()
         }
         for i in 0..255 as u8 {
             let result = calc_checksum(&Frame {
@@ -51,4 +51,23 @@ mod tests {
             assert_eq!(i, result);
         }
     }
+
+    #[test]
+    fn it_wraps_the_sum_of_a_big_payload() {
+        // the sum of the payload does not fit in a byte
+        let frame = Frame {
+            start_byte: StartByte::ACK,
+            payload: [0xFF, 0xFE, 0x80, 0x81].into(),
+        };
+        let result = calc_checksum(&frame);
+        assert_eq!(result, 0xF9);
+        // the sum of the frame and its checksum is zero
+        let sum = frame
+            .payload
+            .as_array()
+            .iter()
+            .fold(0u8, |sum, a| sum.wrapping_add(*a));
+        let sum = sum.wrapping_add(StartByte::ACK as u8).wrapping_add(ETX);
+        assert_eq!(sum.wrapping_add(result), 0);
+    }
 }
@@ -1,4 +1,8 @@
+use core::cell::Cell;
+
+use crate::types::time::{Duration, Instant};
 use crate::utils::bit_wise::{get_bit_at, get_bit_at_as_bool, reset_bit_at};
+use crate::utils::numerical::u32_to_u16_clamper;
 
 use self::word16::Word16;
 
@@ -33,6 +37,9 @@ pub enum DLError {
     /// Masters may use STX as start byte)
     SlaveHasReturnedStartByteAsNeitherAckNorNack,
     SlaveHasReturnedNack(SlaveFrame),
+    /// In half-duplex the echo differs from the frame sent, other device has driven the bus at
+    /// the same time (see [`HalfDuplex`])
+    BusCollision,
 }
 
 impl From<SlaveFrameNack> for DLError {
@@ -150,7 +157,8 @@ impl Direction {
     pub fn from_u8_unchecked(value: u8) -> Direction {
         let bit_7 = get_bit_at(value, BIT_7);
         let bit_6 = get_bit_at(value, BIT_6);
-        let direction_number = (bit_7 << 7) + (bit_6 << 6);
+        // NOTE: the bits are already in their positions
+        let direction_number = bit_7 + bit_6;
         Direction::from_u8(direction_number).unwrap_or_else(|_| {
             // Should never happen
             // TODO: Instead of panic call "fatal_error!(109)". To acomplish that is necessary to make
@@ -479,6 +487,21 @@ impl PacoteDeRetornoComErro {
 
 ///////////////////////////////////////////////////////////
 
+/// Control of a RS-485 transceiver in a multi-drop bus, where the master and the slaves share the
+/// same pair of wires.
+///
+/// The master drives the bus only while it transmits, then it releases the bus so the slave may
+/// answer. The transceiver also receives what the master transmits, so each frame sent comes back
+/// as an echo before the answer of the slave.
+#[derive(Copy, Clone)]
+pub struct HalfDuplex {
+    /// Sets the driver-enable line (DE) of the transceiver, `true` to drive the bus
+    pub set_driver_enable: fn(bool),
+    /// True when the last byte has left the shift register of the uart, ie: the bus may be
+    /// released without cutting the end of the frame
+    pub is_transmission_complete: fn() -> bool,
+}
+
 /// A cmpp Datalink is capable to send and receive data frames. It basically implements the cmpp protocol v1 specification.
 /// See official specification for more details.
 pub struct Datalink {
@@ -493,10 +516,18 @@ pub struct Datalink {
     pub try_tx: fn(u8) -> Option<()>,
     /// Ok_None if nothing to receive, Err if some error happened, Ok_Some if a byte has been received
     pub try_rx: fn() -> Result<Option<u8>, ()>,
-    /// Returns miliseconds elapsed since `Epoch` (when machine was turned on)
-    pub now: fn() -> u16,
+    /// Returns the current time, see [`Instant`]
+    pub now: fn() -> Instant,
     /// If there exists some function, then call it with the content of each byte received
     pub debug_reception: Option<fn(u8)>,
+    /// `Some` if the serial is a RS-485 bus, `None` if it is a full-duplex line (ie: RS-232)
+    pub half_duplex: Option<HalfDuplex>,
+    /// If true the bytes which can not be decoded (ie: noise in the line) are discarded and the
+    /// reception goes on until a valid frame or the timeout, see [`Decoder::new_resynchronizing`]
+    pub resynchronize: bool,
+    /// Bytes discarded while resynchronizing since the creation of the datalink, it saturates at
+    /// `u16::MAX`. Starts with zero, see [`Datalink::get_discarded_bytes`].
+    pub discarded_bytes: Cell<u16>,
 }
 
 impl Datalink {
@@ -516,10 +547,26 @@ impl Datalink {
         encoded
     }
 
+    /// Sends the frame. In half-duplex the bus is driven only during the transmission, it is
+    /// released even if the transmission times out.
     fn transmit(&self, encoded_data: Encoder) -> Result<(), DLError> {
+        match self.half_duplex {
+            Some(half_duplex) => {
+                (half_duplex.set_driver_enable)(true);
+                let result = self
+                    .transmit_bytes(encoded_data)
+                    .and_then(|()| self.wait_transmission_complete(half_duplex));
+                (half_duplex.set_driver_enable)(false);
+                result
+            }
+            None => self.transmit_bytes(encoded_data),
+        }
+    }
+
+    fn transmit_bytes(&self, encoded_data: Encoder) -> Result<(), DLError> {
         let now = (self.now);
         let start_time = now();
-        let timeout = self.timeout_ms;
+        let timeout = Duration::from_millis(self.timeout_ms.into());
         let try_tx = (self.try_tx);
         for byte in encoded_data {
             loop {
@@ -527,9 +574,11 @@ impl Datalink {
                     break;
                 } else {
                     // test for timeout
-                    let time_elapsed = now() - start_time;
-                    if time_elapsed > (self.timeout_ms) {
-                        return Err(DLError::SerialTransmissionTimeedOut(time_elapsed));
+                    let time_elapsed = now().duration_since(start_time);
+                    if time_elapsed > timeout {
+                        return Err(DLError::SerialTransmissionTimeedOut(u32_to_u16_clamper(
+                            time_elapsed.as_millis(),
+                        )));
                     }
                 };
             }
@@ -537,21 +586,67 @@ impl Datalink {
         Ok(())
     }
 
+    /// The bytes given to `try_tx` may be still in the buffers of the uart, waits them leave
+    fn wait_transmission_complete(&self, half_duplex: HalfDuplex) -> Result<(), DLError> {
+        let now = (self.now);
+        let start_time = now();
+        let timeout = Duration::from_millis(self.timeout_ms.into());
+        while !(half_duplex.is_transmission_complete)() {
+            let time_elapsed = now().duration_since(start_time);
+            if time_elapsed > timeout {
+                return Err(DLError::SerialTransmissionTimeedOut(u32_to_u16_clamper(
+                    time_elapsed.as_millis(),
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Synchronously reads RX waiting for stream of bytes to decode, considering the
     /// specified timeout time.
     /// I reads a response Frame but does not checks if the Frame is an SlaveFrame
     /// (start_byte equals 'ACK' or 'NACK'). Alternatively use [`Self::receive`].
-    fn receive_frame(&self) -> Result<Frame, DLError> {
+    ///
+    /// In half-duplex the first bytes received are the `echo` of the frame sent, they are compared
+    /// with it to detect a collision in the bus and then discarded.
+    fn receive_frame(&self, echo: Option<Encoder>) -> Result<Frame, DLError> {
+        let mut decoder = match self.resynchronize {
+            true => Decoder::new_resynchronizing(),
+            false => Decoder::new(),
+        };
+        let result = self.decode_frame(echo, &mut decoder);
+        let discarded_bytes = self.discarded_bytes.get();
+        self.discarded_bytes
+            .set(discarded_bytes.saturating_add(decoder.get_discarded_bytes()));
+        result
+    }
+
+    /// Bytes discarded while resynchronizing (ie: noise in the line), see [`Datalink::resynchronize`]
+    pub fn get_discarded_bytes(&self) -> u16 {
+        self.discarded_bytes.get()
+    }
+
+    fn decode_frame(
+        &self,
+        mut echo: Option<Encoder>,
+        decoder: &mut Decoder,
+    ) -> Result<Frame, DLError> {
         let now = (self.now);
         let start_time = now();
-        let timeout = self.timeout_ms;
+        let timeout = Duration::from_millis(self.timeout_ms.into());
+        let has_timed_out = || now().duration_since(start_time) > timeout;
         let try_receive_some_byte_from_serial = (self.try_rx);
-        let mut decoder = Decoder::new();
         // Loops until something meaningful happens
         loop {
             match try_receive_some_byte_from_serial() {
                 // Ok we received some byte !
                 Ok(Some(byte)) => {
+                    if let Some(byte_sent) = echo.as_mut().and_then(|echo| echo.next()) {
+                        if byte != byte_sent {
+                            return Err(DLError::BusCollision);
+                        }
+                        continue;
+                    }
                     if let Some(debug_log) = self.debug_reception {
                         debug_log(byte);
                     };
@@ -571,21 +666,27 @@ impl Datalink {
 
                 // No byte available in this turn, check for timeout time to decide if is possible to continue waiting for.
                 Ok(None) => {
-                    let time_elapsed = now() - start_time;
-                    if time_elapsed > (self.timeout_ms) {
+                    if has_timed_out() {
                         return Err(DLError::Timeout(self.timeout_ms));
                     }
                 }
 
-                // Recepetion error occuried
+                // Recepetion error occuried, when resynchronizing the frame being decoded may have
+                // lost some byte, so it is thrown away and the reception goes on until the timeout
+                Err(_) if self.resynchronize => {
+                    decoder.discard_frame();
+                    if has_timed_out() {
+                        return Err(DLError::Timeout(self.timeout_ms));
+                    }
+                }
                 Err(_) => return Err(DLError::SerialReceptionError),
             }
         }
     }
 
     /// Perform all reception check plus check if the response is not a MasterFrame (start_byte equals STX)
-    fn receive(&self) -> Result<SlaveFrame, DLError> {
-        let received_frame = self.receive_frame()?;
+    fn receive(&self, echo: Option<Encoder>) -> Result<SlaveFrame, DLError> {
+        let received_frame = self.receive_frame(echo)?;
         // check if it is an slave frame and return it
         received_frame
             .try_into()
@@ -602,7 +703,16 @@ impl Datalink {
         let encoded = Self::encode_data(self.channel, direction, word_address, word_value);
         self.transmit(encoded)?;
         // Receive
-        self.receive()
+        let echo = match self.half_duplex {
+            Some(_) => Some(Self::encode_data(
+                self.channel,
+                direction,
+                word_address,
+                word_value,
+            )),
+            None => None,
+        };
+        self.receive(echo)
     }
 
     /// TODO: reduce code surface when possible (reduce redundance)
@@ -726,10 +836,11 @@ pub mod emulated {
         encoder::Encoder,
         prelude::StartByte,
     };
+    use crate::types::time::Instant;
 
     /// Does never timeout ;)! Because time does not pass :D !
-    pub fn lazy_now() -> u16 {
-        0
+    pub fn lazy_now() -> Instant {
+        Instant::from_millis(0)
     }
 
     /// emulated server's buffer
@@ -870,6 +981,9 @@ mod tests {
             try_rx: loopback_try_rx,
             now: lazy_now,
             debug_reception: None,
+            half_duplex: None,
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
         };
 
         // run
@@ -977,11 +1091,279 @@ mod tests {
                 DLError::SlaveHasReturnedNack(nack_frame) => {
                     assert!(false, "SlaveHasReturnedNack");
                 }
+                DLError::BusCollision => {
+                    assert!(false, "BusCollision");
+                }
             },
         };
         assert_eq!(check, 1, "Everything is checked")
     }
 
+    #[test]
+    fn it_times_out_across_the_wrap_of_the_clock() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+
+        /// Each reading of the clock advances 1 milisec, starting just before the wrap
+        static CLOCK: AtomicU32 = AtomicU32::new(u32::MAX - 500);
+        fn ticking_now() -> Instant {
+            Instant::from_millis(CLOCK.fetch_add(1, Ordering::Relaxed))
+        }
+        fn silent_try_rx() -> Result<Option<u8>, ()> {
+            Ok(None)
+        }
+        let datalink = Datalink {
+            channel: Channel::from_u8(1).unwrap(),
+            timeout_ms: 1000,
+            try_tx: |_| Some(()),
+            try_rx: silent_try_rx,
+            now: ticking_now,
+            debug_reception: None,
+            half_duplex: None,
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
+        };
+        let start = ticking_now();
+        let result = datalink.get_word16(0x50);
+        assert!(matches!(result, Err(DLError::Timeout(1000))));
+        // it has waited the whole timeout, neither less nor forever
+        let elapsed = ticking_now().duration_since(start);
+        assert!(elapsed > Duration::from_millis(1000) && elapsed < Duration::from_millis(1010));
+    }
+
+    #[test]
+    fn it_reads_the_direction_and_channel() {
+        let channel = Channel::from_u8(5).unwrap();
+        for direction in [
+            Direction::Get,
+            Direction::ResetBitmask,
+            Direction::SetBitmask,
+            Direction::Set,
+        ] {
+            let direction_and_channel =
+                DirectionAndChannel::from_raw(channel.to_u8() + direction as u8);
+            assert_eq!(direction_and_channel.get_direction(), direction);
+            assert_eq!(direction_and_channel.get_channel(), channel);
+        }
+    }
+
+    #[test]
+    fn it_skips_the_echo_in_half_duplex() {
+        use core::sync::atomic::{AtomicBool, Ordering};
+        use heapless::Deque;
+
+        // RS-485 bus in which each byte driven by the master comes back as an echo, and a slave
+        // which answers the requests with the word 0x1234
+        static DRIVER_ENABLE: AtomicBool = AtomicBool::new(false);
+        static mut BUS: Deque<u8, 40> = Deque::new();
+        static mut SLAVE: Decoder = Decoder::new();
+        fn set_driver_enable(on: bool) {
+            DRIVER_ENABLE.store(on, Ordering::Relaxed)
+        }
+        fn try_tx(byte: u8) -> Option<()> {
+            // the byte does not reach the bus if the driver is disabled
+            assert!(DRIVER_ENABLE.load(Ordering::Relaxed));
+            unsafe {
+                BUS.push_back(byte).unwrap();
+                if let Ok(Some(request)) = SLAVE.parse_next(byte) {
+                    let [direction_and_channel, word_address, _, _] = request.payload.as_array();
+                    let payload = [direction_and_channel, word_address, 0x34, 0x12].into();
+                    for answer in Encoder::new(Frame::new(StartByte::ACK, payload)) {
+                        BUS.push_back(answer).unwrap();
+                    }
+                }
+            }
+            Some(())
+        }
+        fn try_rx() -> Result<Option<u8>, ()> {
+            unsafe { Ok(BUS.pop_front()) }
+        }
+        let datalink = Datalink {
+            channel: Channel::from_u8(1).unwrap(),
+            timeout_ms: 1000,
+            try_tx,
+            try_rx,
+            now: emulated::lazy_now,
+            debug_reception: None,
+            half_duplex: Some(HalfDuplex {
+                set_driver_enable,
+                is_transmission_complete: || true,
+            }),
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
+        };
+        let Ok(Ok(answer)) = datalink.get_word16(0x50) else {
+            panic!("the answer of the slave was not received")
+        };
+        assert_eq!(answer.data.to_u16(), 0x1234);
+        // the bus is released for the slave
+        assert!(!DRIVER_ENABLE.load(Ordering::Relaxed));
+        assert!(unsafe { BUS.is_empty() });
+    }
+
+    #[test]
+    fn it_detects_a_collision_in_the_bus() {
+        use heapless::Deque;
+
+        // other device drives the bus at the same time, so the echo is corrupted
+        static mut BUS: Deque<u8, 40> = Deque::new();
+        fn try_tx(byte: u8) -> Option<()> {
+            let collision = 0x40;
+            unsafe { BUS.push_back(byte | collision).unwrap() };
+            Some(())
+        }
+        fn try_rx() -> Result<Option<u8>, ()> {
+            unsafe { Ok(BUS.pop_front()) }
+        }
+        let datalink = Datalink {
+            channel: Channel::from_u8(1).unwrap(),
+            timeout_ms: 1000,
+            try_tx,
+            try_rx,
+            now: emulated::lazy_now,
+            debug_reception: None,
+            half_duplex: Some(HalfDuplex {
+                set_driver_enable: |_| {},
+                is_transmission_complete: || true,
+            }),
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
+        };
+        let result = datalink.get_word16(0x50);
+        assert!(matches!(result, Err(DLError::BusCollision)));
+    }
+
+    #[test]
+    fn it_releases_the_bus_if_the_transmission_does_not_complete() {
+        use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+        static DRIVER_ENABLE: AtomicBool = AtomicBool::new(false);
+        static CLOCK: AtomicU32 = AtomicU32::new(0);
+        fn ticking_now() -> Instant {
+            Instant::from_millis(CLOCK.fetch_add(1, Ordering::Relaxed))
+        }
+        fn set_driver_enable(on: bool) {
+            DRIVER_ENABLE.store(on, Ordering::Relaxed)
+        }
+        let datalink = Datalink {
+            channel: Channel::from_u8(1).unwrap(),
+            timeout_ms: 100,
+            try_tx: |_| Some(()),
+            try_rx: || Ok(None),
+            now: ticking_now,
+            debug_reception: None,
+            half_duplex: Some(HalfDuplex {
+                set_driver_enable,
+                // ie: the uart is stuck
+                is_transmission_complete: || false,
+            }),
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
+        };
+        let result = datalink.get_word16(0x50);
+        assert!(matches!(
+            result,
+            Err(DLError::SerialTransmissionTimeedOut(_))
+        ));
+        assert!(!DRIVER_ENABLE.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn it_receives_the_answer_after_the_noise_in_the_line() {
+        use super::super::prelude::ESC;
+        use heapless::Deque;
+
+        // the answer is preceded by noise and by an answer broken by a glitch
+        static mut LINE: Deque<u8, 40> = Deque::new();
+        fn try_tx(_byte: u8) -> Option<()> {
+            let answer = || {
+                let payload = [0x01, 0x50, 0x34, 0x12].into();
+                Encoder::new(Frame::new(StartByte::ACK, payload))
+            };
+            unsafe {
+                // the answer is read only after the whole request is sent
+                if LINE.is_empty() {
+                    let glitch = 0x7F;
+                    let noise = [0x00, 0xFF, ESC, glitch].into_iter();
+                    let broken_answer = answer().take(5).chain([glitch]);
+                    for byte in noise.chain(broken_answer) {
+                        LINE.push_back(byte).unwrap();
+                    }
+                    for byte in answer() {
+                        LINE.push_back(byte).unwrap();
+                    }
+                }
+            }
+            Some(())
+        }
+        fn try_rx() -> Result<Option<u8>, ()> {
+            unsafe { Ok(LINE.pop_front()) }
+        }
+        let datalink = Datalink {
+            channel: Channel::from_u8(1).unwrap(),
+            timeout_ms: 1000,
+            try_tx,
+            try_rx,
+            now: emulated::lazy_now,
+            debug_reception: None,
+            half_duplex: None,
+            resynchronize: true,
+            discarded_bytes: Cell::new(0),
+        };
+        let Ok(Ok(answer)) = datalink.get_word16(0x50) else {
+            panic!("the answer of the slave was not received")
+        };
+        assert_eq!(answer.data.to_u16(), 0x1234);
+        // the noise and the broken answer
+        assert_eq!(datalink.get_discarded_bytes(), 10);
+    }
+
+    #[test]
+    fn it_receives_the_answer_after_an_error_of_reception() {
+        use heapless::Deque;
+
+        // the first answer is broken by an error of reception (ie: a framing error in the uart),
+        // `None` is the error
+        static mut LINE: Deque<Option<u8>, 40> = Deque::new();
+        fn try_tx(_byte: u8) -> Option<()> {
+            let answer = || {
+                let payload = [0x01, 0x50, 0x34, 0x12].into();
+                Encoder::new(Frame::new(StartByte::ACK, payload)).map(Some)
+            };
+            unsafe {
+                if LINE.is_empty() {
+                    let broken_answer = answer().take(4).chain([None]);
+                    for byte in broken_answer.chain(answer()) {
+                        LINE.push_back(byte).unwrap();
+                    }
+                }
+            }
+            Some(())
+        }
+        fn try_rx() -> Result<Option<u8>, ()> {
+            match unsafe { LINE.pop_front() } {
+                Some(None) => Err(()),
+                Some(byte) => Ok(byte),
+                None => Ok(None),
+            }
+        }
+        let datalink = Datalink {
+            channel: Channel::from_u8(1).unwrap(),
+            timeout_ms: 1000,
+            try_tx,
+            try_rx,
+            now: emulated::lazy_now,
+            debug_reception: None,
+            half_duplex: None,
+            resynchronize: true,
+            discarded_bytes: Cell::new(0),
+        };
+        let Ok(Ok(answer)) = datalink.get_word16(0x50) else {
+            panic!("the answer of the slave was not received")
+        };
+        assert_eq!(answer.data.to_u16(), 0x1234);
+        assert_eq!(datalink.get_discarded_bytes(), 4);
+    }
+
     // /////////////////////////////////////
     // TODO: Test for check each error condition (ie: timeout, checksum wrong, etc)
     // ////////////////////
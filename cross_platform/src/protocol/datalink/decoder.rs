@@ -23,12 +23,27 @@ pub enum State {
     WaitingChecksum,
 }
 
+fn parse_start_byte(byte: u8) -> Option<StartByte> {
+    match byte {
+        STX => Some(StartByte::STX),
+        ACK => Some(StartByte::ACK),
+        NACK => Some(StartByte::NACK),
+        _ => None,
+    }
+}
+
 pub struct Decoder {
     start_byte: StartByte,
     state: State,
     payload_index: usize,
     payload_buffer: [u8; MAX_BUFFER_LEN],
     last_was_esc: bool,
+    /// If true the decoding errors are not returned, see [`Decoder::new_resynchronizing`]
+    resynchronize: bool,
+    /// Bytes received of the frame being decoded (only counted when resynchronizing)
+    received_length: u16,
+    /// Bytes thrown away while resynchronizing
+    discarded_bytes: u16,
 }
 
 impl Decoder {
@@ -39,9 +54,27 @@ impl Decoder {
             payload_index: 0,
             payload_buffer: [0x00; MAX_BUFFER_LEN],
             last_was_esc: false,
+            resynchronize: false,
+            received_length: 0,
+            discarded_bytes: 0,
         }
     }
 
+    /// Decoder which never fails: the garbage between the frames and the broken frames (ie: by the
+    /// noise of the motors in the line) are discarded, and the decoding restarts on the next `ESC`
+    /// followed by a start byte. The bytes discarded are counted in
+    /// [`Decoder::get_discarded_bytes`].
+    pub const fn new_resynchronizing() -> Self {
+        let mut decoder = Self::new();
+        decoder.resynchronize = true;
+        decoder
+    }
+
+    /// Bytes discarded while resynchronizing, it saturates at `u16::MAX`
+    pub fn get_discarded_bytes(&self) -> u16 {
+        self.discarded_bytes
+    }
+
     pub fn reset(&mut self) {
         self.start_byte = StartByte::STX;
         self.state = State::WaitingFirstEsc;
@@ -50,6 +83,14 @@ impl Decoder {
         self.last_was_esc = false;
     }
 
+    /// Throws away the frame being decoded, ie: after an error of reception in which some of its
+    /// bytes may have been lost. When resynchronizing its bytes are counted as discarded.
+    pub fn discard_frame(&mut self) {
+        self.reset();
+        self.discard(self.received_length);
+        self.received_length = 0;
+    }
+
     /// Signals the client that more information must be provided and that the parsing is not finished yet.
     fn exit_but_not_done_yet(&self) -> Result<Option<Frame>, DecodingError> {
         Ok(None)
@@ -75,10 +116,57 @@ impl Decoder {
         self.exit_but_not_done_yet()
     }
 
+    fn discard(&mut self, length: u16) {
+        self.discarded_bytes = self.discarded_bytes.saturating_add(length);
+    }
+
+    /// The frame being decoded is broken by the `byte`. Its bytes are discarded except the ones
+    /// which may be the start of the next frame: an `ESC`, or an `ESC` and a start byte.
+    fn hunt_next_frame(&mut self, byte: u8, was_after_esc: bool) {
+        self.reset();
+        let kept_length = if byte == ESC {
+            self.state = State::WaitingStartByte;
+            1
+        } else if let (true, Some(start_byte)) = (was_after_esc, parse_start_byte(byte)) {
+            self.start_byte = start_byte;
+            self.state = State::ReceivingData;
+            2
+        } else {
+            0
+        };
+        self.discard(self.received_length - kept_length);
+        self.received_length = kept_length;
+    }
+
     /// Parses asynchronously each byte according to cmpp protocol v1.
     ///
     /// Returns Ok(None) if still decoding, Ok(Some(frame)) if a frame has been parsed and Err if some decidubg error hapenned.
+    /// When resynchronizing the errors are not returned, the decoding just goes on.
     pub fn parse_next(&mut self, byte: u8) -> Result<Option<Frame>, DecodingError> {
+        if !self.resynchronize {
+            return self.parse_strict(byte);
+        }
+        if self.state == State::WaitingFirstEsc && byte != ESC {
+            // garbage between the frames
+            self.discard(1);
+            return Ok(None);
+        }
+        let was_after_esc = self.last_was_esc;
+        self.received_length += 1;
+        match self.parse_strict(byte) {
+            Ok(Some(frame)) => {
+                self.received_length = 0;
+                Ok(Some(frame))
+            }
+            Ok(None) => Ok(None),
+            Err(_) => {
+                self.hunt_next_frame(byte, was_after_esc);
+                Ok(None)
+            }
+        }
+    }
+
+    fn parse_strict(&mut self, byte: u8) -> Result<Option<Frame>, DecodingError> {
         match self.state {
             State::WaitingFirstEsc => {
                 self.state = State::WaitingStartByte;
@@ -86,12 +174,10 @@ impl Decoder {
             }
 
             State::WaitingStartByte => {
-                self.start_byte = match byte {
-                    STX => Ok(StartByte::STX),
-                    ACK => Ok(StartByte::ACK),
-                    NACK => Ok(StartByte::NACK),
-                    _ => Err(DecodingError::InvalidStartByte(byte)),
-                }?;
+                self.start_byte = match parse_start_byte(byte) {
+                    Some(start_byte) => start_byte,
+                    None => return self.exit_with_error(DecodingError::InvalidStartByte(byte)),
+                };
                 self.state = State::ReceivingData;
                 self.exit_but_not_done_yet()
             }
@@ -153,7 +239,7 @@ impl Decoder {
                     } else {
                         Err(DecodingError::InvalidChecksum {
                             expected: expected_checksum,
-                            received: expected_checksum,
+                            received: incomming_checksum,
                         })
                     }
                 }
@@ -200,6 +286,8 @@ impl Decoder {
 #[cfg(test)]
 mod tests {
 
+    use heapless::Vec;
+
     use crate::protocol::datalink::frame::Payload;
 
     use super::*;
@@ -211,8 +299,25 @@ mod tests {
                 return Ok(frame);
             }
         }
-        //Error: "Input is fully proccessed but no frame result was generated"
-        fatal_error!()
+        panic!("Input is fully proccessed but no frame result was generated")
+    }
+
+    /// Frames decoded while resynchronizing
+    fn run_resynchronizing(decoder: &mut Decoder, input: &[u8]) -> Vec<Frame, 4> {
+        input
+            .iter()
+            .filter_map(|byte| decoder.parse_next(*byte).unwrap())
+            .collect()
+    }
+
+    // 1B 02 C1 50 61 02 1B 03 87
+    const FRAME: [u8; 9] = [0x1B, 0x02, 0xC1, 0x50, 0x61, 0x02, 0x1B, 0x03, 0x87];
+
+    fn frame() -> Frame {
+        Frame {
+            start_byte: StartByte::STX,
+            payload: [0xC1, 0x50, 0x61, 0x02].into(),
+        }
     }
 
     #[test]
@@ -278,4 +383,69 @@ mod tests {
         let actual = run_decoder(&probe).unwrap();
         assert_eq!(expected, actual);
     }
+
+    #[test]
+    fn it_fails_on_an_unexpected_byte() {
+        let mut probe = FRAME;
+        probe[1] = 0x55;
+        assert!(matches!(
+            run_decoder(&probe),
+            Err(DecodingError::InvalidStartByte(0x55))
+        ));
+        let mut probe = FRAME;
+        probe[8] = 0x88;
+        assert!(matches!(
+            run_decoder(&probe),
+            Err(DecodingError::InvalidChecksum {
+                expected: 0x87,
+                received: 0x88
+            })
+        ));
+    }
+
+    #[test]
+    fn it_skips_the_garbage_before_the_frame() {
+        let mut decoder = Decoder::new_resynchronizing();
+        let mut probe: Vec<u8, 32> = Vec::from_slice(&[0x00, 0xFF, 0x03]).unwrap();
+        probe.extend(FRAME);
+        assert_eq!(run_resynchronizing(&mut decoder, &probe), [frame()]);
+        assert_eq!(decoder.get_discarded_bytes(), 3);
+    }
+
+    #[test]
+    fn it_resynchronizes_after_a_broken_frame() {
+        let mut decoder = Decoder::new_resynchronizing();
+        // the frame is cut by a glitch then it is sent again
+        let mut probe: Vec<u8, 32> = Vec::from_slice(&FRAME[..4]).unwrap();
+        probe.push(0x7F);
+        probe.extend(FRAME);
+        assert_eq!(run_resynchronizing(&mut decoder, &probe), [frame()]);
+        // the 4 bytes of the broken frame and the glitch
+        assert_eq!(decoder.get_discarded_bytes(), 5);
+    }
+
+    #[test]
+    fn it_resynchronizes_on_a_start_byte_inside_a_frame() {
+        let mut decoder = Decoder::new_resynchronizing();
+        // the ESC of the end of the frame is followed by a new frame (its ESC is shared)
+        let mut probe: Vec<u8, 32> = Vec::from_slice(&FRAME[..7]).unwrap();
+        probe.extend_from_slice(&FRAME[1..]).unwrap();
+        assert_eq!(run_resynchronizing(&mut decoder, &probe), [frame()]);
+        assert_eq!(decoder.get_discarded_bytes(), 6);
+    }
+
+    #[test]
+    fn it_resynchronizes_after_a_wrong_checksum() {
+        let mut decoder = Decoder::new_resynchronizing();
+        let mut broken = FRAME;
+        broken[8] = 0x88;
+        let mut probe: Vec<u8, 32> = Vec::from_slice(&broken).unwrap();
+        probe.extend(FRAME);
+        probe.extend(FRAME);
+        assert_eq!(
+            run_resynchronizing(&mut decoder, &probe),
+            [frame(), frame()]
+        );
+        assert_eq!(decoder.get_discarded_bytes(), 9);
+    }
 }
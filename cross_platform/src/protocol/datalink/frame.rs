@@ -156,4 +156,27 @@ mod tests {
         assert_eq!(expected, frame.payload);
         assert_eq!(start_byte, frame.start_byte);
     }
+
+    #[test]
+    fn it_keeps_the_direction_and_the_checksum_through_the_encoding() {
+        use super::super::{
+            datalink::{Direction, DirectionAndChannel},
+            decoder::Decoder,
+        };
+
+        // a `Set` in the channel 1, the sum of the payload does not fit in a byte
+        let frame = Frame::make_master_block([0xC1, 0xFF, 0xFE, 0x80].into());
+        let mut decoder = Decoder::new();
+        let decoded = frame
+            .encode()
+            .map(|byte| decoder.parse_next(byte).unwrap())
+            .last()
+            .flatten();
+        assert_eq!(decoded, Some(frame));
+        assert_eq!(frame.encode().last(), Some(frame.checksum()));
+        let direction_and_channel =
+            DirectionAndChannel::from_raw(frame.payload.direction_and_channel);
+        assert_eq!(direction_and_channel.get_direction(), Direction::Set);
+        assert_eq!(direction_and_channel.get_channel().to_u8(), 1);
+    }
 }
@@ -1303,6 +1303,8 @@ impl<'a> TransportLayer<'a> {
 #[cfg(test)]
 mod tests {
 
+    use core::cell::Cell;
+
     use crate::protocol::{
         datalink::datalink::{
             emulated::{lazy_now, loopback_try_rx, smart_try_tx},
@@ -1332,6 +1334,9 @@ mod tests {
             try_rx: loopback_try_rx,
             now: lazy_now,
             debug_reception: None,
+            half_duplex: None,
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
         };
 
         let transport = TransportLayer {
@@ -1362,6 +1367,9 @@ mod tests {
             try_rx: loopback_try_rx,
             now: lazy_now,
             debug_reception: None,
+            half_duplex: None,
+            resynchronize: false,
+            discarded_bytes: Cell::new(0),
         };
 
         let transport = TransportLayer {
@@ -1,2 +1,3 @@
 pub mod delay;
 pub mod serial_connection;
+pub mod time;
@@ -0,0 +1,219 @@
+//! Monotonic time given by a free running counter of milisecs (ie: the timer interrupt of the
+//! microcontroler)
+//!
+//! The counter has 32 bits, so it wraps after ~49 days of power-on. The arithmetic wraps as the
+//! counter does: the duration between two instants is correct across the wrap if it is shorter
+//! than half of the counter range (~24 days). For this reason the instants are not ordered with
+//! `<`, use [`Instant::is_before`] or compare the durations.
+//!
+//! # Example
+//!
+//! ```
+//! use cross_platform::types::time::{Duration, Instant};
+//!
+//! let start = Instant::from_millis(u32::MAX - 99);
+//! let now = start + Duration::from_millis(300);
+//! assert_eq!(now.as_millis(), 200); // the counter has wrapped
+//! assert!(now.duration_since(start) > Duration::from_millis(250));
+//! assert!(start.is_before(now));
+//! ```
+
+use core::ops::{Add, AddAssign, Sub};
+
+/// A point in the time, in milisecs since an arbitrary origin (ie: the power-on)
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Instant {
+    millis: u32,
+}
+
+impl Instant {
+    /// The counter of milisecs may be bigger than 32 bits, it is truncated as the counter would
+    /// wrap.
+    pub const fn from_millis(millis: u32) -> Self {
+        Self { millis }
+    }
+
+    pub const fn as_millis(self) -> u32 {
+        self.millis
+    }
+
+    /// Time elapsed from `earlier` until `self`
+    ///
+    /// NOTE: If `earlier` is after `self` the result is meaningless (a huge duration).
+    pub const fn duration_since(self, earlier: Instant) -> Duration {
+        Duration::from_millis(self.millis.wrapping_sub(earlier.millis))
+    }
+
+    /// True if `self` happens before `other`
+    pub const fn is_before(self, other: Instant) -> bool {
+        (other.millis.wrapping_sub(self.millis) as i32) > 0
+    }
+
+    /// True if the `deadline` is `self` or it is before `self`
+    pub const fn has_reached(self, deadline: Instant) -> bool {
+        !self.is_before(deadline)
+    }
+}
+
+impl Add<Duration> for Instant {
+    type Output = Instant;
+
+    fn add(self, duration: Duration) -> Instant {
+        Instant::from_millis(self.millis.wrapping_add(duration.millis))
+    }
+}
+
+impl AddAssign<Duration> for Instant {
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration
+    }
+}
+
+impl Sub<Duration> for Instant {
+    type Output = Instant;
+
+    fn sub(self, duration: Duration) -> Instant {
+        Instant::from_millis(self.millis.wrapping_sub(duration.millis))
+    }
+}
+
+impl Sub<Instant> for Instant {
+    type Output = Duration;
+
+    /// Same as [`Instant::duration_since`]
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+/// A span of time in milisecs
+///
+/// The longest duration is `u32::MAX` milisecs (~49 days), the arithmetic saturates at zero and at
+/// this limit instead of overflowing. Note that only the durations shorter than ~24 days can be
+/// measured between two [`Instant`]s (see the module documentation).
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct Duration {
+    millis: u32,
+}
+
+impl Duration {
+    pub const ZERO: Duration = Duration::from_millis(0);
+    pub const MAX: Duration = Duration::from_millis(u32::MAX);
+
+    pub const fn from_millis(millis: u32) -> Self {
+        Self { millis }
+    }
+
+    /// Saturates at [`Duration::MAX`] if `secs` is bigger than ~49 days
+    pub const fn from_secs(secs: u32) -> Self {
+        Self::from_millis(secs.saturating_mul(1000))
+    }
+
+    pub const fn as_millis(self) -> u32 {
+        self.millis
+    }
+
+    pub const fn saturating_add(self, other: Duration) -> Duration {
+        Duration::from_millis(self.millis.saturating_add(other.millis))
+    }
+
+    pub const fn saturating_sub(self, other: Duration) -> Duration {
+        Duration::from_millis(self.millis.saturating_sub(other.millis))
+    }
+
+    pub const fn checked_add(self, other: Duration) -> Option<Duration> {
+        match self.millis.checked_add(other.millis) {
+            Some(millis) => Some(Duration::from_millis(millis)),
+            None => None,
+        }
+    }
+
+    pub const fn checked_sub(self, other: Duration) -> Option<Duration> {
+        match self.millis.checked_sub(other.millis) {
+            Some(millis) => Some(Duration::from_millis(millis)),
+            None => None,
+        }
+    }
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    /// Same as [`Duration::saturating_add`]
+    fn add(self, other: Duration) -> Duration {
+        self.saturating_add(other)
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    /// Same as [`Duration::saturating_sub`]
+    fn sub(self, other: Duration) -> Duration {
+        self.saturating_sub(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE_THE_WRAP: Instant = Instant::from_millis(u32::MAX - 99);
+
+    #[test]
+    fn it_measures_the_duration_across_the_wrap() {
+        let later = BEFORE_THE_WRAP + Duration::from_millis(300);
+        assert_eq!(later.as_millis(), 200);
+        assert_eq!(
+            later.duration_since(BEFORE_THE_WRAP),
+            Duration::from_millis(300)
+        );
+        assert_eq!(later - Duration::from_millis(300), BEFORE_THE_WRAP);
+    }
+
+    #[test]
+    fn it_orders_the_instants_across_the_wrap() {
+        let later = BEFORE_THE_WRAP + Duration::from_millis(300);
+        assert!(BEFORE_THE_WRAP.is_before(later));
+        assert!(!later.is_before(BEFORE_THE_WRAP));
+        assert!(!later.is_before(later));
+        assert!(later.has_reached(later));
+        assert!(later.has_reached(BEFORE_THE_WRAP));
+        assert!(!BEFORE_THE_WRAP.has_reached(later));
+    }
+
+    #[test]
+    fn it_adds_and_subtracts_the_durations() {
+        let mut instant = Instant::from_millis(1000);
+        instant += Duration::from_secs(2);
+        assert_eq!(instant.as_millis(), 3000);
+        assert_eq!(
+            Duration::from_millis(100) + Duration::from_millis(50) - Duration::from_millis(30),
+            Duration::from_millis(120)
+        );
+        assert_eq!(
+            Duration::from_millis(100).saturating_sub(Duration::from_millis(300)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn it_saturates_the_durations_instead_of_overflowing() {
+        assert_eq!(Duration::from_secs(u32::MAX), Duration::MAX);
+        assert_eq!(
+            Duration::from_secs(4_294_967),
+            Duration::from_millis(4_294_967_000)
+        );
+        assert_eq!(Duration::MAX + Duration::from_millis(1), Duration::MAX);
+        assert_eq!(
+            Duration::from_millis(1) - Duration::from_millis(2),
+            Duration::ZERO
+        );
+        assert_eq!(Duration::MAX.checked_add(Duration::from_millis(1)), None);
+        assert_eq!(Duration::ZERO.checked_sub(Duration::from_millis(1)), None);
+        assert_eq!(
+            Duration::from_millis(3).checked_sub(Duration::from_millis(1)),
+            Some(Duration::from_millis(2))
+        );
+    }
+}
@@ -4,7 +4,7 @@ use super::numerical::{const_clamp, usize_to_u8_clamper};
 
 /// The purpose of a [`Cursor`] is to statefully move a current unsigned integer around a predefined interval of values
 /// normally defined between start (inclusive) and end (exclusive). Through a serie of defined methods.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq)]
 pub struct Cursor {
     /// varies between start (inclusive) and end (exclusive)
     current: u8,